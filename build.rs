@@ -1,7 +1,167 @@
-use std::error::Error;
+use prost::Message;
+use prost_types::{DescriptorProto, FileDescriptorSet};
+use std::{env, error::Error, fmt::Write, fs, path::PathBuf};
+
+// A variant of a `oneof`: its proto field name and the message type it carries.
+struct Variant {
+    field: String, // Proto field name, such as `add_request`.
+    message: String, // Message type name, such as `AddRequest`.
+}
+
+impl Variant {
+    // Returns the Rust name prost gives the variant, such as `AddRequest`.
+    fn rust_name(&self) -> String {
+        self.field
+            .split('_')
+            .map(|word| {
+                let mut chars = word.chars();
+                chars.next().map(|first| first.to_ascii_uppercase().to_string() + chars.as_str()).unwrap_or_default()
+            })
+            .collect()
+    }
+}
 
 fn main() -> Result<(), Box<dyn Error>> {
-    prost_build::compile_protos(&["proto/messages.proto"], &["proto/"])?;
+    let out_dir = PathBuf::from(env::var("OUT_DIR")?);
+    let descriptors = out_dir.join("messages.bin");
+    prost_build::Config::new()
+        .file_descriptor_set_path(&descriptors)
+        .compile_protos(&["proto/messages.proto"], &["proto/"])?;
+
+    // Generate the router from the request and reply oneofs
+    let set = FileDescriptorSet::decode(fs::read(&descriptors)?.as_slice())?;
+    let messages: Vec<&DescriptorProto> = set.file.iter().flat_map(|file| &file.message_type).collect();
+    let requests = oneof_variants(&messages, "ClientMessage")?;
+    let replies = oneof_variants(&messages, "ServerMessage")?;
+    fs::write(out_dir.join("router.rs"), generate_router(&requests, &replies)?)?;
 
     Ok(())
 }
+
+// Returns the variants of the `message` oneof in the named message.
+fn oneof_variants(messages: &[&DescriptorProto], name: &str) -> Result<Vec<Variant>, Box<dyn Error>> {
+    let message = messages
+        .iter()
+        .find(|message| message.name() == name)
+        .ok_or_else(|| format!("messages.proto has no {} message", name))?;
+    let oneof = message
+        .oneof_decl
+        .iter()
+        .position(|oneof| oneof.name() == "message")
+        .ok_or_else(|| format!("{} has no `message` oneof", name))?;
+    Ok(message
+        .field
+        .iter()
+        .filter(|field| field.oneof_index == Some(oneof as i32))
+        .map(|field| Variant {
+            field: field.name().to_string(),
+            message: field.type_name().rsplit('.').next().unwrap_or_default().to_string(),
+        })
+        .collect())
+}
+
+// Finds the reply variant for a request: the one carrying `FooResponse` for a `FooRequest`, or
+// the one carrying the request's own type, as `EchoMessage` does.
+fn reply_for<'a>(request: &Variant, replies: &'a [Variant]) -> Result<&'a Variant, Box<dyn Error>> {
+    let expected = match request.message.strip_suffix("Request") {
+        Some(stem) => format!("{}Response", stem),
+        None => request.message.clone(),
+    };
+    replies.iter().find(|reply| reply.message == expected).ok_or_else(|| {
+        format!(
+            "ClientMessage.{} carries {}, but no ServerMessage variant carries {} to answer it",
+            request.field, request.message, expected
+        )
+        .into()
+    })
+}
+
+// Generates `MessageKind`, the `Router` trait and `route` for the request variants.
+fn generate_router(requests: &[Variant], replies: &[Variant]) -> Result<String, Box<dyn Error>> {
+    let mut code = String::new();
+    writeln!(code, "// This file is @generated by build.rs from the oneofs in messages.proto.")?;
+    writeln!(code)?;
+    writeln!(code, "use crate::handler::{{Context, HandlerError, HandlerResult}};")?;
+    writeln!(code, "use crate::message::{{client_message, server_message}};")?;
+    writeln!(code)?;
+
+    writeln!(code, "// Request variants of `ClientMessage`, named in configuration after their proto fields.")?;
+    writeln!(code, "#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]")?;
+    writeln!(code, "pub enum MessageKind {{")?;
+    for request in requests {
+        writeln!(code, "    {}, // `{}`.", request.rust_name(), request.field)?;
+    }
+    writeln!(code, "}}")?;
+    writeln!(code)?;
+    writeln!(code, "impl MessageKind {{")?;
+    writeln!(code, "    // Every request variant.")?;
+    let all: Vec<String> = requests.iter().map(|request| format!("MessageKind::{}", request.rust_name())).collect();
+    writeln!(code, "    pub const ALL: [MessageKind; {}] = [{}];", requests.len(), all.join(", "))?;
+    writeln!(code)?;
+    writeln!(code, "    // Returns the kind of a request.")?;
+    writeln!(code, "    pub fn of(message: &client_message::Message) -> Self {{")?;
+    writeln!(code, "        match message {{")?;
+    for request in requests {
+        let name = request.rust_name();
+        writeln!(code, "            client_message::Message::{}(_) => MessageKind::{},", name, name)?;
+    }
+    writeln!(code, "        }}")?;
+    writeln!(code, "    }}")?;
+    writeln!(code)?;
+    writeln!(code, "    // Returns the proto field name of the variant.")?;
+    writeln!(code, "    pub fn name(self) -> &'static str {{")?;
+    writeln!(code, "        match self {{")?;
+    for request in requests {
+        writeln!(code, "            MessageKind::{} => \"{}\",", request.rust_name(), request.field)?;
+    }
+    writeln!(code, "        }}")?;
+    writeln!(code, "    }}")?;
+    writeln!(code, "}}")?;
+    writeln!(code)?;
+    writeln!(code, "impl std::str::FromStr for MessageKind {{")?;
+    writeln!(code, "    type Err = std::io::Error;")?;
+    writeln!(code)?;
+    writeln!(code, "    fn from_str(s: &str) -> std::io::Result<Self> {{")?;
+    writeln!(code, "        MessageKind::ALL.into_iter().find(|kind| kind.name() == s).ok_or_else(|| {{")?;
+    writeln!(code, "            std::io::Error::new(std::io::ErrorKind::InvalidInput, format!(\"unknown message type `{{}}`\", s))")?;
+    writeln!(code, "        }})")?;
+    writeln!(code, "    }}")?;
+    writeln!(code, "}}")?;
+    writeln!(code)?;
+    writeln!(code, "impl std::fmt::Display for MessageKind {{")?;
+    writeln!(code, "    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {{")?;
+    writeln!(code, "        f.write_str(self.name())")?;
+    writeln!(code, "    }}")?;
+    writeln!(code, "}}")?;
+    writeln!(code)?;
+
+    writeln!(code, "// Serves every request variant of `ClientMessage` with one method each, returning the matching")?;
+    writeln!(code, "// `ServerMessage` variant. Register an implementation with `Server::register_router`.")?;
+    writeln!(code, "pub trait Router: Send + Sync {{")?;
+    for (index, request) in requests.iter().enumerate() {
+        let reply = reply_for(request, replies)?;
+        if index > 0 {
+            writeln!(code)?;
+        }
+        writeln!(code, "    // Handles `ClientMessage.{}`, answered with `ServerMessage.{}`.", request.field, reply.field)?;
+        writeln!(
+            code,
+            "    fn {}(&self, request: crate::message::{}, context: &Context) -> Result<crate::message::{}, HandlerError>;",
+            request.field, request.message, reply.message
+        )?;
+    }
+    writeln!(code, "}}")?;
+    writeln!(code)?;
+    writeln!(code, "// Calls the router method for `request` and wraps its reply in the matching `ServerMessage` variant.")?;
+    writeln!(code, "pub fn route<R: Router + ?Sized>(router: &R, request: client_message::Message, context: &Context) -> HandlerResult {{")?;
+    writeln!(code, "    match request {{")?;
+    for request in requests {
+        let reply = reply_for(request, replies)?;
+        writeln!(code, "        client_message::Message::{}(request) => router", request.rust_name())?;
+        writeln!(code, "            .{}(request, context)", request.field)?;
+        writeln!(code, "            .map(|reply| Some(server_message::Message::{}(reply))),", reply.rust_name())?;
+    }
+    writeln!(code, "    }}")?;
+    writeln!(code, "}}")?;
+    Ok(code)
+}
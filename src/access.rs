@@ -0,0 +1,95 @@
+use std::{
+    fmt, // Formatting support for network ranges.
+    io::{self, ErrorKind}, // Error type used to reject malformed ranges.
+    net::IpAddr, // Addresses checked against the access list.
+    str::FromStr, // Parsing ranges from configuration text.
+};
+
+// An address range in CIDR notation, such as `10.0.0.0/8`. A bare address matches only itself.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IpNet {
+    addr: IpAddr, // Network address.
+    prefix: u8, // Number of leading bits that must match.
+}
+
+impl IpNet {
+    // Creates a range, rejecting prefixes longer than the address.
+    pub fn new(addr: IpAddr, prefix: u8) -> io::Result<Self> {
+        let max = if addr.is_ipv4() { 32 } else { 128 };
+        if prefix > max {
+            return Err(io::Error::new(
+                ErrorKind::InvalidInput,
+                format!("prefix /{} is too long for {}", prefix, addr),
+            ));
+        }
+        Ok(IpNet { addr, prefix })
+    }
+
+    // Returns true if `ip` falls inside the range. IPv4-mapped IPv6 addresses match IPv4 ranges.
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        let ip = match ip {
+            IpAddr::V6(v6) => v6.to_ipv4_mapped().map(IpAddr::V4).unwrap_or(ip),
+            IpAddr::V4(_) => ip,
+        };
+        match (self.addr, ip) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => {
+                prefix_matches(u32::from(net).into(), u32::from(ip).into(), self.prefix, 32)
+            }
+            (IpAddr::V6(net), IpAddr::V6(ip)) => {
+                prefix_matches(u128::from(net), u128::from(ip), self.prefix, 128)
+            }
+            _ => false,
+        }
+    }
+}
+
+// Compares the leading `prefix` bits of two `width`-bit addresses.
+fn prefix_matches(net: u128, ip: u128, prefix: u8, width: u32) -> bool {
+    if prefix == 0 {
+        return true;
+    }
+    let shift = width - u32::from(prefix);
+    (net >> shift) == (ip >> shift)
+}
+
+impl FromStr for IpNet {
+    type Err = io::Error;
+
+    fn from_str(s: &str) -> io::Result<Self> {
+        let invalid = || io::Error::new(ErrorKind::InvalidInput, format!("invalid address range: {}", s));
+        let (addr, prefix) = match s.split_once('/') {
+            Some((addr, prefix)) => (addr, Some(prefix)),
+            None => (s, None),
+        };
+        let addr: IpAddr = addr.trim().parse().map_err(|_| invalid())?;
+        let prefix = match prefix {
+            Some(prefix) => prefix.trim().parse().map_err(|_| invalid())?,
+            None if addr.is_ipv4() => 32,
+            None => 128,
+        };
+        IpNet::new(addr, prefix)
+    }
+}
+
+impl fmt::Display for IpNet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.addr, self.prefix)
+    }
+}
+
+// Decides which peers may connect. Deny rules win; a non-empty allow list admits only matching peers.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AccessList {
+    pub allow: Vec<IpNet>, // Ranges admitted; empty admits everyone not denied.
+    pub deny: Vec<IpNet>, // Ranges always rejected.
+}
+
+impl AccessList {
+    // Returns true if a peer at `ip` may connect.
+    pub fn permits(&self, ip: IpAddr) -> bool {
+        if self.deny.iter().any(|net| net.contains(ip)) {
+            return false;
+        }
+        self.allow.is_empty() || self.allow.iter().any(|net| net.contains(ip))
+    }
+}
@@ -0,0 +1,98 @@
+use crate::listener::Listener; // Import the listener types inherited sockets become.
+use log::info; // Import macros for structured logging.
+use socket2::{Domain, Socket}; // Wraps inherited descriptors so their options can be adjusted.
+use std::{
+    env, // The LISTEN_* variables describing inherited sockets.
+    io::{self, ErrorKind}, // Error type for malformed activation state.
+    net::TcpListener, // Inherited TCP listening sockets.
+    os::unix::{
+        io::{AsFd, AsRawFd, FromRawFd, OwnedFd, RawFd}, // Conversions between descriptors and sockets.
+        net::UnixListener, // Inherited Unix domain listening sockets.
+        process::CommandExt, // `pre_exec` for arranging descriptors in the child.
+    },
+    process::{Child, Command}, // Spawning the process that inherits the listeners.
+};
+
+// First descriptor passed by the LISTEN_FDS convention (SD_LISTEN_FDS_START).
+pub const LISTEN_FDS_START: RawFd = 3;
+
+// Returns the listening sockets passed to this process through the systemd LISTEN_FDS convention,
+// or None when nothing was passed. When LISTEN_PID is set it must name this process, so variables
+// meant for a parent are ignored. The variables are removed so child processes do not reuse them.
+pub fn listeners_from_env() -> io::Result<Option<Vec<Listener>>> {
+    let count = match env::var("LISTEN_FDS") {
+        Ok(count) => count,
+        Err(_) => return Ok(None),
+    };
+    if let Ok(pid) = env::var("LISTEN_PID") {
+        if pid.parse::<u32>().ok() != Some(std::process::id()) {
+            return Ok(None); // Meant for another process.
+        }
+    }
+    env::remove_var("LISTEN_FDS");
+    env::remove_var("LISTEN_PID");
+    env::remove_var("LISTEN_FDNAMES");
+
+    let count: RawFd = count
+        .parse()
+        .map_err(|_| io::Error::new(ErrorKind::InvalidInput, format!("invalid LISTEN_FDS: {}", count)))?;
+    if count <= 0 {
+        return Ok(None);
+    }
+
+    let mut listeners = Vec::new();
+    for fd in LISTEN_FDS_START..LISTEN_FDS_START + count {
+        // SAFETY: by the LISTEN_FDS convention these descriptors are open, owned by this process
+        // and not wrapped anywhere else; the variables were removed so this happens only once.
+        let socket = unsafe { Socket::from_raw_fd(fd) };
+        socket.set_cloexec(true)?; // Do not leak the descriptor into unrelated children.
+        let listener = if socket.domain()? == Domain::UNIX {
+            Listener::Unix(UnixListener::from(OwnedFd::from(socket)))
+        } else {
+            Listener::Tcp(TcpListener::from(socket))
+        };
+        let addr = listener.local_addr()?; // Fails if the descriptor is not a bound socket.
+        info!("Inherited listening socket {} on {}", fd, addr);
+        listeners.push(listener);
+    }
+    Ok(Some(listeners))
+}
+
+// Spawns `command` with `listeners` passed as descriptors 3, 4, ... and LISTEN_FDS set, so that
+// `listeners_from_env` in the child picks them up. Used for socket activation in tests and for
+// re-exec upgrades that hand the listeners to a new binary.
+pub fn spawn_with_listeners<L: AsFd>(command: &mut Command, listeners: &[L]) -> io::Result<Child> {
+    let fds: Vec<RawFd> = listeners.iter().map(|listener| listener.as_fd().as_raw_fd()).collect();
+    let count = fds.len() as RawFd;
+    command.env("LISTEN_FDS", count.to_string());
+    command.env_remove("LISTEN_PID"); // The child's PID is not known before it is spawned.
+    command.env_remove("LISTEN_FDNAMES");
+
+    // SAFETY: the closure runs in the forked child before exec and only calls the async-signal-safe
+    // `fcntl` and `dup2` on descriptors inherited from the parent.
+    unsafe {
+        command.pre_exec(move || {
+            // Move every listener above the target range first, so no dup2 overwrites a listener
+            // that has not been moved yet.
+            let mut moved = [0 as RawFd; 64];
+            if fds.len() > moved.len() {
+                return Err(io::Error::from_raw_os_error(libc::EMFILE));
+            }
+            for (index, fd) in fds.iter().enumerate() {
+                let high = libc::fcntl(*fd, libc::F_DUPFD_CLOEXEC, LISTEN_FDS_START + count);
+                if high < 0 {
+                    return Err(io::Error::last_os_error());
+                }
+                moved[index] = high;
+            }
+            for (index, fd) in moved[..fds.len()].iter().enumerate() {
+                // dup2 clears close-on-exec on the target, so only the listeners survive exec.
+                if libc::dup2(*fd, LISTEN_FDS_START + index as RawFd) < 0 {
+                    return Err(io::Error::last_os_error());
+                }
+            }
+            Ok(())
+        });
+    }
+    command.spawn()
+}
@@ -0,0 +1,371 @@
+use crate::hooks::DisconnectReason; // Import why connections close.
+use crate::message::{server_message, LastWill, Publication, ServerMessage}; // Import the messages pushed to subscribers.
+use crate::outbound::PushError; // Import reasons a push can fail.
+use crate::registry::{ConnectionId, ConnectionRegistry}; // Import connection tracking.
+use log::debug; // Import macros for structured logging.
+use std::{
+    collections::{hash_map::Entry, BTreeMap, BTreeSet, HashMap, VecDeque}, // Filters, subscribers and pending publications.
+    fmt, // Formatting support for the broker.
+    io, // Failures to start delivery threads.
+    sync::{
+        atomic::{AtomicU64, Ordering}, // Lock-free counters.
+        Arc, Condvar, Mutex, MutexGuard, // Shared state between publishers and delivery threads.
+    },
+    thread, // Support for spawning delivery threads.
+};
+
+// Messages waiting to be handed to one subscriber's connection.
+struct Mailbox {
+    state: Mutex<MailboxState>, // Pending publications.
+    ready: Condvar, // Signalled when a publication is queued or the mailbox closes.
+    limit: usize, // Messages queued before new ones are dropped.
+}
+
+// Mailbox contents guarded by the mutex.
+#[derive(Default)]
+struct MailboxState {
+    pending: VecDeque<server_message::Message>, // Messages in the order they were queued.
+    closed: bool, // Set once the subscriber is removed; the delivery thread then exits.
+}
+
+impl Mailbox {
+    // Queues a message. Returns false if the mailbox is full or closed.
+    fn offer(&self, message: server_message::Message) -> bool {
+        let mut state = self.lock();
+        if state.closed || state.pending.len() >= self.limit {
+            return false;
+        }
+        state.pending.push_back(message);
+        self.ready.notify_one();
+        true
+    }
+
+    // Waits for the next message. Returns None once the mailbox is closed.
+    fn take(&self) -> Option<server_message::Message> {
+        let mut state = self.lock();
+        loop {
+            if state.closed {
+                return None;
+            }
+            if let Some(message) = state.pending.pop_front() {
+                return Some(message);
+            }
+            state = self.ready.wait(state).unwrap_or_else(|poisoned| poisoned.into_inner());
+        }
+    }
+
+    // Discards pending messages and stops the delivery thread.
+    fn close(&self) {
+        let mut state = self.lock();
+        state.closed = true;
+        state.pending.clear();
+        self.ready.notify_all();
+    }
+
+    // Locks the mailbox, recovering from a poisoned lock.
+    fn lock(&self) -> MutexGuard<'_, MailboxState> {
+        self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+// The topic filters of one subscribed connection and its mailbox.
+struct Subscriber {
+    filters: BTreeSet<String>, // Topic filters subscribed to.
+    mailbox: Arc<Mailbox>, // Messages waiting for delivery.
+}
+
+impl Subscriber {
+    // Returns whether any filter of the subscriber matches `topic`.
+    fn wants(&self, topic: &str) -> bool {
+        self.filters.iter().any(|filter| topic_matches(filter, topic))
+    }
+}
+
+// Broker contents guarded by the mutex.
+#[derive(Default)]
+struct State {
+    subscribers: HashMap<ConnectionId, Subscriber>, // Subscribers by connection.
+    retained: BTreeMap<String, Vec<u8>>, // Retained message by topic.
+    wills: HashMap<ConnectionId, LastWill>, // Last wills by connection.
+}
+
+// State shared by every handle to the broker.
+struct Inner {
+    registry: Arc<ConnectionRegistry>, // Connections publications are pushed to.
+    queue_limit: usize, // Mailbox size of each subscriber.
+    state: Mutex<State>, // Subscriptions, retained messages and last wills.
+    published: AtomicU64, // Publications queued for a subscriber.
+    dropped: AtomicU64, // Publications dropped at a full mailbox or outbound queue.
+}
+
+// Routes published messages to the connections subscribed to their topic, with MQTT semantics:
+// topics are `/`-separated levels, and subscriptions are topic filters in which `+` matches one
+// level and a trailing `#` any number of levels. A publication can be kept as its topic's
+// retained message, sent to every later subscription that matches it, and a connection can leave
+// a last will, published if it drops without saying goodbye.
+//
+// Each subscriber has a mailbox of at most `queue_limit` publications drained by its own delivery
+// thread into the connection's outbound queue, so publishers never wait for slow subscribers: a
+// publication that finds the mailbox full is dropped for that subscriber. Publications reach a
+// subscriber in the order they were published, once each however many of its filters match.
+// Other services, such as chat rooms, push through the same mailboxes with `deliver`. Cloning the
+// broker yields another handle to the same subscriptions.
+#[derive(Clone)]
+pub struct Broker {
+    inner: Arc<Inner>, // Shared subscriptions and counters.
+}
+
+impl Broker {
+    // Creates a broker delivering to the connections of `registry`, queueing up to `queue_limit`
+    // publications per subscriber.
+    pub fn new(registry: Arc<ConnectionRegistry>, queue_limit: usize) -> Self {
+        let inner = Inner {
+            registry,
+            queue_limit: queue_limit.max(1),
+            state: Mutex::default(),
+            published: AtomicU64::new(0),
+            dropped: AtomicU64::new(0),
+        };
+        Broker { inner: Arc::new(inner) }
+    }
+
+    // Subscribes connection `id` to the topics matching `filter`, starting its delivery thread on
+    // its first subscription, and queues the retained messages of those topics for it. Returns
+    // the number of filters the connection is subscribed to and of retained messages queued.
+    pub fn subscribe(&self, id: ConnectionId, filter: &str) -> io::Result<(usize, usize)> {
+        let mut state = self.lock();
+        let State { subscribers, retained, .. } = &mut *state;
+        let subscriber = self.attach(subscribers, id)?;
+        if subscriber.filters.insert(filter.to_string()) {
+            debug!("Client {} subscribed to {}", id, filter);
+        }
+        let mut queued = 0;
+        for (topic, payload) in retained.iter().filter(|(topic, _)| topic_matches(filter, topic)) {
+            let publication = Publication { topic: topic.clone(), payload: payload.clone(), retained: true };
+            queued += usize::from(self.offer(id, subscriber, server_message::Message::Publication(publication)));
+        }
+        self.inner.published.fetch_add(queued as u64, Ordering::Relaxed);
+        Ok((subscriber.filters.len(), queued))
+    }
+
+    // Unsubscribes connection `id` from `filter`. Returns the number of filters the connection is
+    // still subscribed to.
+    pub fn unsubscribe(&self, id: ConnectionId, filter: &str) -> usize {
+        match self.lock().subscribers.get_mut(&id) {
+            Some(subscriber) => {
+                if subscriber.filters.remove(filter) {
+                    debug!("Client {} unsubscribed from {}", id, filter);
+                }
+                subscriber.filters.len()
+            }
+            None => 0,
+        }
+    }
+
+    // Removes every subscription and the last will of connection `id` and stops its delivery
+    // thread, discarding publications it has not delivered yet.
+    pub fn remove(&self, id: ConnectionId) {
+        let mut state = self.lock();
+        state.wills.remove(&id);
+        if let Some(subscriber) = state.subscribers.remove(&id) {
+            subscriber.mailbox.close();
+        }
+    }
+
+    // Queues `payload` for every connection subscribed to `topic`, first keeping it as the topic's
+    // retained message if `retain` is set, or clearing that message if the payload is empty.
+    // Returns the number of subscribers it was queued for.
+    pub fn publish(&self, topic: &str, payload: Vec<u8>, retain: bool) -> usize {
+        let mut state = self.lock();
+        if retain && payload.is_empty() {
+            state.retained.remove(topic);
+        } else if retain {
+            state.retained.insert(topic.to_string(), payload.clone());
+        }
+        let publication = Publication { topic: topic.to_string(), payload, retained: false };
+        let mut queued = 0;
+        for (id, subscriber) in state.subscribers.iter().filter(|(_, subscriber)| subscriber.wants(topic)) {
+            queued += usize::from(self.offer(*id, subscriber, server_message::Message::Publication(publication.clone())));
+        }
+        self.inner.published.fetch_add(queued as u64, Ordering::Relaxed);
+        queued
+    }
+
+    // Queues `message` in the mailbox of connection `id`, behind everything queued for it before,
+    // starting its delivery thread if it has none. Returns false if the message was dropped
+    // because the mailbox is full.
+    pub fn deliver(&self, id: ConnectionId, message: server_message::Message) -> io::Result<bool> {
+        let mut state = self.lock();
+        let subscriber = self.attach(&mut state.subscribers, id)?;
+        Ok(self.offer(id, subscriber, message))
+    }
+
+    // Makes `will` the last will of connection `id`, replacing any previous one.
+    pub fn set_will(&self, id: ConnectionId, will: LastWill) {
+        self.lock().wills.insert(id, will);
+    }
+
+    // Forgets the last will of connection `id`, as after a clean disconnect. Returns the will.
+    pub fn clear_will(&self, id: ConnectionId) -> Option<LastWill> {
+        self.lock().wills.remove(&id)
+    }
+
+    // Cleans up after connection `id` closed for `reason`: removes its subscriptions and publishes
+    // its last will, if it still has one, unless the server is shutting down.
+    pub fn disconnected(&self, id: ConnectionId, reason: DisconnectReason) {
+        let will = self.clear_will(id);
+        self.remove(id);
+        if let Some(will) = will.filter(|_| reason != DisconnectReason::Shutdown) {
+            debug!("Publishing the last will of client {} on {} ({})", id, will.topic, reason);
+            self.publish(&will.topic, will.payload, will.retain);
+        }
+    }
+
+    // Returns the filters connection `id` is subscribed to, in order.
+    pub fn subscriptions(&self, id: ConnectionId) -> Vec<String> {
+        self.lock()
+            .subscribers
+            .get(&id)
+            .map(|subscriber| subscriber.filters.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    // Returns the connections with a filter matching `topic`, ordered by ID.
+    pub fn subscribers(&self, topic: &str) -> Vec<ConnectionId> {
+        let mut ids: Vec<ConnectionId> = self
+            .lock()
+            .subscribers
+            .iter()
+            .filter(|(_, subscriber)| subscriber.wants(topic))
+            .map(|(id, _)| *id)
+            .collect();
+        ids.sort_unstable();
+        ids
+    }
+
+    // Returns the retained message of `topic`, if any.
+    pub fn retained(&self, topic: &str) -> Option<Vec<u8>> {
+        self.lock().retained.get(topic).cloned()
+    }
+
+    // Returns the number of publications queued for a subscriber since the broker was created.
+    pub fn published(&self) -> u64 {
+        self.inner.published.load(Ordering::Relaxed)
+    }
+
+    // Returns the number of messages dropped because a subscriber fell behind.
+    pub fn dropped(&self) -> u64 {
+        self.inner.dropped.load(Ordering::Relaxed)
+    }
+
+    // Returns the subscriber entry of connection `id`, creating it with its mailbox and delivery
+    // thread if it has none.
+    fn attach<'a>(&self, subscribers: &'a mut HashMap<ConnectionId, Subscriber>, id: ConnectionId) -> io::Result<&'a mut Subscriber> {
+        Ok(match subscribers.entry(id) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let mailbox = Arc::new(Mailbox { state: Mutex::default(), ready: Condvar::new(), limit: self.inner.queue_limit });
+                self.spawn_delivery(id, Arc::clone(&mailbox))?;
+                entry.insert(Subscriber { filters: BTreeSet::new(), mailbox })
+            }
+        })
+    }
+
+    // Queues a message in the mailbox of subscriber `id`, counting it as dropped if it is full.
+    fn offer(&self, id: ConnectionId, subscriber: &Subscriber, message: server_message::Message) -> bool {
+        if subscriber.mailbox.offer(message) {
+            true
+        } else {
+            self.inner.dropped.fetch_add(1, Ordering::Relaxed);
+            debug!("Dropping a message for client {}: subscriber queue full", id);
+            false
+        }
+    }
+
+    // Starts the thread that hands the messages in `mailbox` to connection `id`.
+    fn spawn_delivery(&self, id: ConnectionId, mailbox: Arc<Mailbox>) -> io::Result<()> {
+        let inner = Arc::clone(&self.inner);
+        thread::Builder::new().name(format!("subscriber-{}", id)).spawn(move || {
+            while let Some(message) = mailbox.take() {
+                let message = ServerMessage { message: Some(message) };
+                match inner.registry.send(id, message) {
+                    Ok(()) => {}
+                    Err(PushError::Dropped | PushError::TimedOut) => {
+                        inner.dropped.fetch_add(1, Ordering::Relaxed); // The slow-consumer policy applies too.
+                    }
+                    Err(e) => {
+                        debug!("Stopping delivery to client {}: {}", id, e);
+                        break; // The connection is going away; its disconnect removes the subscriber.
+                    }
+                }
+            }
+        })?;
+        Ok(())
+    }
+
+    // Locks the broker state, recovering from a poisoned lock.
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.inner.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl fmt::Debug for Broker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.lock();
+        f.debug_struct("Broker")
+            .field("subscribers", &state.subscribers.len())
+            .field("retained", &state.retained.len())
+            .field("wills", &state.wills.len())
+            .field("queue_limit", &self.inner.queue_limit)
+            .finish()
+    }
+}
+
+// Returns whether topic filter `filter` matches `topic`. Both are `/`-separated levels; `+`
+// matches any single level and `#`, only valid as the last level, matches the parent level and
+// any number of levels below it. As in MQTT, wildcards in the first level do not match topics
+// starting with `$`, which are reserved for the server.
+pub fn topic_matches(filter: &str, topic: &str) -> bool {
+    if topic.starts_with('$') && (filter.starts_with('+') || filter.starts_with('#')) {
+        return false;
+    }
+    let mut levels = topic.split('/');
+    for wanted in filter.split('/') {
+        match (wanted, levels.next()) {
+            ("#", _) => return true,
+            ("+", Some(_)) => {}
+            (wanted, Some(level)) if wanted == level => {}
+            _ => return false,
+        }
+    }
+    levels.next().is_none()
+}
+
+// Checks that `filter` can be subscribed to: not empty, with wildcards only as whole levels and
+// `#` only as the last one.
+pub fn validate_filter(filter: &str) -> Result<(), &'static str> {
+    if filter.is_empty() {
+        return Err("topic filter must not be empty");
+    }
+    let levels: Vec<&str> = filter.split('/').collect();
+    for (index, level) in levels.iter().enumerate() {
+        if level.len() > 1 && level.contains(['+', '#']) {
+            return Err("wildcards must occupy a whole topic level");
+        }
+        if *level == "#" && index + 1 != levels.len() {
+            return Err("`#` must be the last topic level");
+        }
+    }
+    Ok(())
+}
+
+// Checks that messages can be published on `topic`: not empty and without wildcards.
+pub fn validate_topic(topic: &str) -> Result<(), &'static str> {
+    if topic.is_empty() {
+        return Err("topic must not be empty");
+    }
+    if topic.contains(['+', '#']) {
+        return Err("topics published to must not contain wildcards");
+    }
+    Ok(())
+}
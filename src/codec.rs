@@ -0,0 +1,136 @@
+use prost::Message; // Import Protobuf support for encoding and decoding messages.
+use std::{
+    fmt, // Formatting support for codec names.
+    io::{self, ErrorKind}, // Error type used to reject malformed frames.
+    str::FromStr, // Parsing codec names from configuration text.
+};
+
+// Maximum number of bytes in a varint length prefix.
+const MAX_PREFIX_LEN: usize = 10;
+
+// Number of bytes in a fixed-width length prefix.
+const FIXED32_PREFIX_LEN: usize = 4;
+
+// How messages are framed on the wire. Each listener picks the codec its connections speak.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Codec {
+    #[default]
+    Varint, // A Protobuf varint length prefix, as written by `encode_length_delimited`.
+    Fixed32, // A four-byte big-endian length prefix, for peers without a varint decoder.
+}
+
+impl Codec {
+    // Encodes a message as a frame: a length prefix followed by the Protobuf payload.
+    pub fn encode<M: Message>(self, message: &M) -> Vec<u8> {
+        match self {
+            Codec::Varint => message.encode_length_delimited_to_vec(),
+            Codec::Fixed32 => {
+                let payload = message.encode_to_vec();
+                let mut frame = (payload.len() as u32).to_be_bytes().to_vec();
+                frame.extend_from_slice(&payload);
+                frame
+            }
+        }
+    }
+}
+
+impl FromStr for Codec {
+    type Err = io::Error;
+
+    fn from_str(s: &str) -> io::Result<Self> {
+        match s {
+            "varint" => Ok(Codec::Varint),
+            "fixed32" => Ok(Codec::Fixed32),
+            _ => Err(io::Error::new(ErrorKind::InvalidInput, format!("unknown codec: {}", s))),
+        }
+    }
+}
+
+impl fmt::Display for Codec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Codec::Varint => "varint",
+            Codec::Fixed32 => "fixed32",
+        })
+    }
+}
+
+// Encodes a message as a frame: a varint length prefix followed by the Protobuf payload.
+pub fn encode_frame<M: Message>(message: &M) -> Vec<u8> {
+    Codec::Varint.encode(message)
+}
+
+// Reassembles length-prefixed frames from the bytes read off a stream.
+pub struct FrameReader {
+    buffer: Vec<u8>, // Bytes received but not yet returned as a frame.
+    codec: Codec, // Framing of the stream.
+    max_frame_len: usize, // Largest payload accepted from the peer.
+}
+
+impl FrameReader {
+    // Creates a reader of varint-prefixed frames that rejects payloads larger than `max_frame_len`.
+    pub fn new(max_frame_len: usize) -> Self {
+        Self::with_codec(Codec::Varint, max_frame_len)
+    }
+
+    // Creates a reader of frames in `codec` that rejects payloads larger than `max_frame_len`.
+    pub fn with_codec(codec: Codec, max_frame_len: usize) -> Self {
+        FrameReader { buffer: Vec::new(), codec, max_frame_len }
+    }
+
+    // Appends bytes read from the stream.
+    pub fn extend(&mut self, bytes: &[u8]) {
+        self.buffer.extend_from_slice(bytes);
+    }
+
+    // Returns true when a partial frame is waiting for more bytes.
+    pub fn has_partial_frame(&self) -> bool {
+        !self.buffer.is_empty()
+    }
+
+    // Removes and returns the next complete frame payload, if one has fully arrived.
+    pub fn next_frame(&mut self) -> io::Result<Option<Vec<u8>>> {
+        let (len, prefix_len) = match self.decode_prefix()? {
+            Some(prefix) => prefix,
+            None => return Ok(None), // The length prefix itself is incomplete.
+        };
+        if len > self.max_frame_len {
+            return Err(io::Error::new(
+                ErrorKind::InvalidData,
+                format!("frame of {} bytes exceeds the {} byte limit", len, self.max_frame_len),
+            ));
+        }
+        if self.buffer.len() < prefix_len + len {
+            return Ok(None); // The payload is incomplete.
+        }
+        let frame = self.buffer[prefix_len..prefix_len + len].to_vec();
+        self.buffer.drain(..prefix_len + len);
+        Ok(Some(frame))
+    }
+
+    // Decodes the length prefix, returning the payload length and the prefix size.
+    fn decode_prefix(&self) -> io::Result<Option<(usize, usize)>> {
+        match self.codec {
+            Codec::Varint => self.decode_varint(),
+            Codec::Fixed32 => Ok(self.buffer.get(..FIXED32_PREFIX_LEN).map(|prefix| {
+                let len = u32::from_be_bytes(prefix.try_into().expect("the prefix is four bytes"));
+                (len as usize, FIXED32_PREFIX_LEN)
+            })),
+        }
+    }
+
+    // Decodes a varint length prefix, returning the payload length and the prefix size.
+    fn decode_varint(&self) -> io::Result<Option<(usize, usize)>> {
+        let mut len: u64 = 0;
+        for (index, byte) in self.buffer.iter().take(MAX_PREFIX_LEN).enumerate() {
+            len |= u64::from(byte & 0x7f) << (7 * index);
+            if byte & 0x80 == 0 {
+                return Ok(Some((len as usize, index + 1)));
+            }
+        }
+        if self.buffer.len() >= MAX_PREFIX_LEN {
+            return Err(io::Error::new(ErrorKind::InvalidData, "invalid frame length prefix"));
+        }
+        Ok(None)
+    }
+}
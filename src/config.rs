@@ -0,0 +1,665 @@
+use crate::access::AccessList; // Import peer access control.
+use crate::codec::Codec; // Import the framing each endpoint speaks.
+use crate::listener::ListenAddr; // Import listen addresses.
+use crate::outbound::SlowConsumerPolicy; // Import the policy applied to full outbound queues.
+use crate::socket::{KeepAlive, Setting, SocketOptions}; // Import TCP socket tuning.
+use crate::tls::TlsConfig; // Import the security of each endpoint.
+use log::LevelFilter; // Log verbosity configured from the config file.
+use std::{
+    fmt, // Naming the message types assigned to pools.
+    fs, // Reading configuration files.
+    io::{self, ErrorKind}, // Error type used to reject invalid settings.
+    path::Path, // Location of configuration files.
+    str::FromStr, // Parsing setting values.
+    time::Duration, // Support for specifying time intervals.
+};
+
+// Timeouts applied to every accepted connection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Timeouts {
+    pub read: Duration, // Longest a single read may block before the handler re-checks server state.
+    pub write: Duration, // Longest a write may block on a client that is not reading.
+    pub idle: Option<Duration>, // Close connections with no inbound traffic or delivered pushes for this long.
+    pub handshake: Option<Duration>, // Close connections that send no request this long after connecting.
+    pub shutdown: Duration, // Longest `Server::run` waits for connections to drain after `stop`.
+}
+
+impl Default for Timeouts {
+    fn default() -> Self {
+        Timeouts {
+            read: Duration::from_secs(10),
+            write: Duration::from_secs(10),
+            idle: Some(Duration::from_secs(300)),
+            handshake: Some(Duration::from_secs(10)),
+            shutdown: Duration::from_secs(10),
+        }
+    }
+}
+
+impl Timeouts {
+    // Rejects zero durations, which the socket API treats as invalid. A zero shutdown timeout is
+    // allowed and closes remaining connections immediately.
+    pub fn validate(&self) -> io::Result<()> {
+        let durations = [
+            ("read", Some(self.read)),
+            ("write", Some(self.write)),
+            ("idle", self.idle),
+            ("handshake", self.handshake),
+        ];
+        for (name, duration) in durations {
+            if duration == Some(Duration::ZERO) {
+                return Err(io::Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("{} timeout must be greater than zero", name),
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+// Settings for each connection's outbound message queue.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OutboundConfig {
+    pub capacity: usize, // Messages waiting to be written before pushes apply the policy; replies may exceed it.
+    pub policy: SlowConsumerPolicy, // What a server push does when the queue is full.
+}
+
+impl Default for OutboundConfig {
+    fn default() -> Self {
+        OutboundConfig {
+            capacity: 64,
+            policy: SlowConsumerPolicy::Block(Duration::from_secs(1)),
+        }
+    }
+}
+
+// What happens to a connection after handling one of its requests panicked. The client receives an
+// `Internal` error response either way.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PanicPolicy {
+    Close, // Close the connection once the error response is written.
+    KeepOpen, // Keep serving the connection's later requests.
+}
+
+// Settings for shedding load based on queue delay; see `shedding::CoDel`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CoDelConfig {
+    pub target: Duration, // Queue delay tolerated without shedding.
+    pub interval: Duration, // How long the delay may stay above target before shedding starts; also the retry-after hint.
+}
+
+impl Default for CoDelConfig {
+    fn default() -> Self {
+        CoDelConfig {
+            target: Duration::from_millis(5),
+            interval: Duration::from_millis(100),
+        }
+    }
+}
+
+// Settings used to build a `Server`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServerConfig {
+    pub timeouts: Timeouts, // Connection timeouts.
+    pub outbound: OutboundConfig, // Outbound queue sizing and slow-consumer handling.
+    pub max_frame_len: usize, // Largest request payload accepted from a client.
+    pub max_in_flight: usize, // Requests per connection handled or awaiting their reply before reading pauses.
+    pub acceptors: usize, // Accept threads, each with its own SO_REUSEPORT listener when more than one.
+    pub socket: SocketOptions, // Options applied to listeners and accepted streams.
+    pub max_connections: Option<usize>, // Connections beyond this many are refused; unlimited when None.
+    pub access: AccessList, // Peers allowed to connect.
+    pub on_panic: PanicPolicy, // Connection handling after a request handler panics.
+    pub pools: Vec<PoolConfig>, // Executor pools for assigned message types; others run on the connection thread.
+    pub load_shedding: Option<CoDelConfig>, // Reject requests once queue delay builds up; never when None.
+    pub subscriber_queue: usize, // Publications queued per subscriber before new ones are dropped.
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        ServerConfig {
+            timeouts: Timeouts::default(),
+            outbound: OutboundConfig::default(),
+            max_frame_len: 64 * 1024,
+            max_in_flight: 32,
+            acceptors: 1,
+            socket: SocketOptions::default(),
+            max_connections: None,
+            access: AccessList::default(),
+            on_panic: PanicPolicy::Close,
+            pools: Vec::new(),
+            load_shedding: None,
+            subscriber_queue: 256,
+        }
+    }
+}
+
+impl ServerConfig {
+    // Checks that every setting is usable before the server binds.
+    pub fn validate(&self) -> io::Result<()> {
+        self.timeouts.validate()?;
+        self.socket.validate()?;
+        if self.outbound.capacity == 0 {
+            return Err(io::Error::new(
+                ErrorKind::InvalidInput,
+                "outbound queue capacity must be greater than zero",
+            ));
+        }
+        if self.acceptors == 0 {
+            return Err(io::Error::new(
+                ErrorKind::InvalidInput,
+                "at least one acceptor is required",
+            ));
+        }
+        if self.max_frame_len == 0 {
+            return Err(io::Error::new(
+                ErrorKind::InvalidInput,
+                "maximum frame length must be greater than zero",
+            ));
+        }
+        if self.subscriber_queue == 0 {
+            return Err(io::Error::new(
+                ErrorKind::InvalidInput,
+                "subscriber queue limit must be greater than zero",
+            ));
+        }
+        if self.max_in_flight == 0 {
+            return Err(io::Error::new(
+                ErrorKind::InvalidInput,
+                "in-flight request limit must be greater than zero",
+            ));
+        }
+        if let Some(shedding) = &self.load_shedding {
+            if shedding.target.is_zero() || shedding.interval.is_zero() {
+                return Err(io::Error::new(
+                    ErrorKind::InvalidInput,
+                    "load shedding target and interval must be greater than zero",
+                ));
+            }
+        }
+        let mut assigned: Vec<&str> = Vec::new();
+        for (index, pool) in self.pools.iter().enumerate() {
+            pool.validate()?;
+            if self.pools[..index].iter().any(|other| other.name == pool.name) {
+                return Err(io::Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("executor pool `{}` is defined more than once", pool.name),
+                ));
+            }
+            for kind in &pool.messages {
+                if assigned.contains(&kind.as_str()) {
+                    return Err(io::Error::new(
+                        ErrorKind::InvalidInput,
+                        format!("message type `{}` is assigned to more than one executor pool", kind),
+                    ));
+                }
+                assigned.push(kind);
+            }
+        }
+        Ok(())
+    }
+
+    // Names the settings that differ from `other` but only take effect after a restart.
+    pub fn restart_required_changes(&self, other: &ServerConfig) -> Vec<&'static str> {
+        let mut changed = Vec::new();
+        if self.acceptors != other.acceptors {
+            changed.push("acceptors");
+        }
+        if self.socket != other.socket {
+            changed.push("socket options");
+        }
+        if self.pools != other.pools {
+            changed.push("executor pools");
+        }
+        if self.load_shedding != other.load_shedding {
+            changed.push("load shedding");
+        }
+        if self.subscriber_queue != other.subscriber_queue {
+            changed.push("subscriber queue");
+        }
+        changed
+    }
+}
+
+// One endpoint a server listens on, with settings that override the server-wide ones.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ListenerConfig {
+    pub address: ListenAddr, // TCP address or Unix socket path.
+    pub acceptors: Option<usize>, // Accept threads for this endpoint; the server-wide count when None.
+    pub socket: Option<SocketOptions>, // Socket options for this endpoint; the server-wide ones when None.
+    pub max_frame_len: Option<usize>, // Largest request accepted on this endpoint; the server-wide limit when None.
+    pub timeouts: Option<Timeouts>, // Connection timeouts for this endpoint, except `shutdown`; the server-wide ones when None.
+    pub codec: Codec, // Framing of requests and replies on this endpoint.
+    pub tls: Option<TlsConfig>, // Secures connections before their first request; plaintext when None.
+}
+
+impl ListenerConfig {
+    // Creates a plaintext, varint-framed endpoint that uses the server-wide settings.
+    pub fn new(address: ListenAddr) -> Self {
+        ListenerConfig {
+            address,
+            acceptors: None,
+            socket: None,
+            max_frame_len: None,
+            timeouts: None,
+            codec: Codec::default(),
+            tls: None,
+        }
+    }
+
+    // Rejects overrides that could not be used.
+    pub fn validate(&self) -> io::Result<()> {
+        if self.acceptors == Some(0) {
+            return Err(io::Error::new(
+                ErrorKind::InvalidInput,
+                format!("{}: at least one acceptor is required", self.address),
+            ));
+        }
+        if self.max_frame_len == Some(0) {
+            return Err(io::Error::new(
+                ErrorKind::InvalidInput,
+                format!("{}: maximum frame length must be greater than zero", self.address),
+            ));
+        }
+        if let Some(socket) = &self.socket {
+            socket.validate()?;
+        }
+        if let Some(timeouts) = &self.timeouts {
+            timeouts.validate()?;
+        }
+        Ok(())
+    }
+}
+
+// A bounded executor pool and the message types it handles. Requests of those types are queued
+// for the pool's workers instead of being handled on their connection's thread, so a burst of one
+// type cannot hold up the others. Replies to pooled requests may overtake earlier replies on the
+// same connection.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PoolConfig {
+    pub name: String, // Name used in metrics and thread names.
+    pub threads: usize, // Worker threads.
+    pub queue_capacity: usize, // Requests that may wait for a worker before new ones are rejected; 0 never queues.
+    pub messages: Vec<String>, // Names of the message types handled by this pool.
+}
+
+impl PoolConfig {
+    // Creates a pool that handles no message types yet.
+    pub fn new(name: &str, threads: usize, queue_capacity: usize) -> Self {
+        PoolConfig { name: name.to_string(), threads, queue_capacity, messages: Vec::new() }
+    }
+
+    // Assigns message types to the pool, such as `MessageKind`s of the default protocol.
+    pub fn handling(mut self, messages: &[impl fmt::Display]) -> Self {
+        self.messages.extend(messages.iter().map(ToString::to_string));
+        self
+    }
+
+    // Rejects pools that could never run a request.
+    pub fn validate(&self) -> io::Result<()> {
+        if self.name.is_empty() {
+            return Err(io::Error::new(ErrorKind::InvalidInput, "executor pool name must not be empty"));
+        }
+        if self.threads == 0 {
+            return Err(io::Error::new(
+                ErrorKind::InvalidInput,
+                format!("executor pool `{}` needs at least one thread", self.name),
+            ));
+        }
+        Ok(())
+    }
+}
+
+// Contents of a configuration file: the endpoints and log level used by the binary, plus the
+// server settings.
+//
+// The file holds one `key = value` setting per line; `#` starts a comment. Durations are in
+// milliseconds, and 0 disables the idle and handshake timeouts. A `keepalive_idle_ms` of 0 turns
+// keepalive off wherever it appears, and `linger_ms = off` turns lingering off. Recognised keys:
+//
+//   address, listen, log_level, acceptors, max_connections, max_frame_len, max_in_flight, allow, deny,
+//   read_timeout_ms, write_timeout_ms, idle_timeout_ms, handshake_timeout_ms, shutdown_timeout_ms,
+//   outbound_capacity, slow_consumer (drop | disconnect | block:<ms>), on_panic (close | keep_open),
+//   tcp_nodelay, keepalive_idle_ms, keepalive_interval_ms, keepalive_retries,
+//   recv_buffer_size, send_buffer_size, linger_ms, backlog, ipv6_only, pool,
+//   shed_target_ms, shed_interval_ms, subscriber_queue
+//
+// `address` takes comma-separated endpoints: `host:port` or `unix:<path>`. Each `listen` line adds
+// one endpoint, optionally followed by overrides for it: `listen = [::]:8080 acceptors=2
+// max_frame_len=1024 codec=fixed32 backlog=64 tcp_nodelay=true ipv6_only=true keepalive_idle_ms=0
+// linger_ms=off read_timeout_ms=500 write_timeout_ms=500 idle_timeout_ms=0 handshake_timeout_ms=1000`,
+// where `codec` is `varint`, the default, or `fixed32`. Endpoint socket and timeout overrides start
+// from the server-wide settings.
+// TLS needs an acceptor from a TLS library, so it is set in code through `ListenerConfig::tls`.
+// Each `pool` line adds an executor pool: `pool = heavy threads=4 queue=16 messages=add_request`,
+// where `messages` lists message type names, the proto field names of the `ClientMessage` oneof
+// for the default protocol. Setting `shed_target_ms` or `shed_interval_ms` enables load shedding,
+// and a `shed_target_ms` of 0 disables it wherever it appears. `allow` and `deny` take
+// comma-separated addresses or CIDR ranges. Without `log_level`, RUST_LOG sets the log level.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigFile {
+    pub listeners: Vec<ListenerConfig>, // Endpoints the binary listens on.
+    pub log_level: Option<LevelFilter>, // Maximum log level; left to RUST_LOG when None.
+    pub server: ServerConfig, // Settings passed to the server.
+}
+
+impl Default for ConfigFile {
+    fn default() -> Self {
+        ConfigFile {
+            listeners: vec![ListenerConfig::new(ListenAddr::Tcp("127.0.0.1:8080".to_string()))],
+            log_level: None,
+            server: ServerConfig::default(),
+        }
+    }
+}
+
+// A `listen` line whose socket and timeout overrides are resolved once the server-wide settings
+// are known.
+struct PendingListener {
+    config: ListenerConfig, // Endpoint and other overrides.
+    socket: Vec<SocketOverride>, // Socket option overrides, applied on top of the server-wide ones.
+    timeouts: Vec<TimeoutOverride>, // Timeout overrides, applied on top of the server-wide ones.
+}
+
+// A socket option set on a single `listen` line.
+enum SocketOverride {
+    Backlog(i32), // Listen backlog.
+    NoDelay(bool), // TCP_NODELAY.
+    OnlyV6(bool), // IPV6_V6ONLY.
+    KeepAliveIdle(Duration), // Keepalive idle time; zero turns keepalive off.
+    Linger(Setting<Duration>), // SO_LINGER.
+}
+
+// A timeout set on a single `listen` line.
+enum TimeoutOverride {
+    Read(Duration), // Read timeout.
+    Write(Duration), // Write timeout.
+    Idle(Option<Duration>), // Idle timeout; None disables it.
+    Handshake(Option<Duration>), // Handshake timeout; None disables it.
+}
+
+impl ConfigFile {
+    // Reads and validates a configuration file.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path)?;
+        Self::parse(&text).map_err(|e| io::Error::new(e.kind(), format!("{}: {}", path.display(), e)))
+    }
+
+    // Parses and validates configuration text. Settings that are not mentioned keep their defaults.
+    pub fn parse(text: &str) -> io::Result<Self> {
+        let mut file = ConfigFile::default();
+        let mut listeners: Option<Vec<PendingListener>> = None; // Replaces the default endpoint when set.
+        let mut shedding = PendingShedding::default(); // Resolved once every line is read.
+        for (index, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default().trim(); // Strip comments.
+            if line.is_empty() {
+                continue;
+            }
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| invalid_data(format!("line {}: expected `key = value`", index + 1)))?;
+            let (key, value) = (key.trim(), value.trim());
+            let applied = match key {
+                "address" => parse_addresses(value).map(|addresses| listeners = Some(addresses)),
+                "listen" => parse_listen(value).map(|listener| listeners.get_or_insert_with(Vec::new).push(listener)),
+                "pool" => parse_pool(value).map(|pool| file.server.pools.push(pool)),
+                "shed_target_ms" => parse_millis(key, value).map(|target| shedding.target = Some(target)),
+                "shed_interval_ms" => parse_millis(key, value).map(|interval| shedding.interval = Some(interval)),
+                _ => file.set(key, value),
+            };
+            applied.map_err(|e| invalid_data(format!("line {}: {}", index + 1, e)))?;
+        }
+        disable_zero_keepalive(&mut file.server.socket);
+        if let Some(listeners) = listeners {
+            file.listeners = listeners
+                .into_iter()
+                .map(|pending| pending.resolve(&file.server.socket, &file.server.timeouts))
+                .collect();
+        }
+        if let Some(load_shedding) = shedding.resolve() {
+            file.server.load_shedding = load_shedding;
+        }
+        file.server.validate()?;
+        for listener in &file.listeners {
+            listener.validate()?;
+        }
+        Ok(file)
+    }
+
+    // Applies one setting.
+    fn set(&mut self, key: &str, value: &str) -> io::Result<()> {
+        let server = &mut self.server;
+        match key {
+            "log_level" => self.log_level = Some(parse(key, value)?),
+            "acceptors" => server.acceptors = parse(key, value)?,
+            "max_connections" => server.max_connections = Some(parse(key, value)?).filter(|max| *max > 0),
+            "max_frame_len" => server.max_frame_len = parse(key, value)?,
+            "max_in_flight" => server.max_in_flight = parse(key, value)?,
+            "allow" => server.access.allow = parse_list(key, value)?,
+            "deny" => server.access.deny = parse_list(key, value)?,
+            "read_timeout_ms" => server.timeouts.read = parse_millis(key, value)?,
+            "write_timeout_ms" => server.timeouts.write = parse_millis(key, value)?,
+            "idle_timeout_ms" => server.timeouts.idle = Some(parse_millis(key, value)?).filter(|d| !d.is_zero()),
+            "handshake_timeout_ms" => server.timeouts.handshake = Some(parse_millis(key, value)?).filter(|d| !d.is_zero()),
+            "shutdown_timeout_ms" => server.timeouts.shutdown = parse_millis(key, value)?,
+            "outbound_capacity" => server.outbound.capacity = parse(key, value)?,
+            "subscriber_queue" => server.subscriber_queue = parse(key, value)?,
+            "slow_consumer" => server.outbound.policy = parse_policy(value)?,
+            "on_panic" => server.on_panic = parse_panic_policy(value)?,
+            "tcp_nodelay" => server.socket.nodelay = Some(parse(key, value)?),
+            "keepalive_idle_ms" => keepalive(&mut server.socket).idle = parse_millis(key, value)?,
+            "keepalive_interval_ms" => keepalive(&mut server.socket).interval = Some(parse_millis(key, value)?),
+            "keepalive_retries" => keepalive(&mut server.socket).retries = Some(parse(key, value)?),
+            "recv_buffer_size" => server.socket.recv_buffer_size = Some(parse(key, value)?),
+            "send_buffer_size" => server.socket.send_buffer_size = Some(parse(key, value)?),
+            "linger_ms" => server.socket.linger = Some(parse_linger(key, value)?),
+            "backlog" => server.socket.backlog = parse(key, value)?,
+            "ipv6_only" => server.socket.only_v6 = Some(parse(key, value)?),
+            _ => return Err(invalid_data(format!("unknown setting `{}`", key))),
+        }
+        Ok(())
+    }
+}
+
+impl PendingListener {
+    // Applies the socket and timeout overrides on top of the server-wide settings.
+    fn resolve(mut self, server_socket: &SocketOptions, server_timeouts: &Timeouts) -> ListenerConfig {
+        if !self.socket.is_empty() {
+            let mut socket = *server_socket;
+            for setting in &self.socket {
+                match *setting {
+                    SocketOverride::Backlog(backlog) => socket.backlog = backlog,
+                    SocketOverride::NoDelay(nodelay) => socket.nodelay = Some(nodelay),
+                    SocketOverride::OnlyV6(only_v6) => socket.only_v6 = Some(only_v6),
+                    SocketOverride::KeepAliveIdle(idle) => keepalive(&mut socket).idle = idle,
+                    SocketOverride::Linger(linger) => socket.linger = Some(linger),
+                }
+            }
+            disable_zero_keepalive(&mut socket);
+            self.config.socket = Some(socket);
+        }
+        if !self.timeouts.is_empty() {
+            let mut timeouts = *server_timeouts;
+            for setting in &self.timeouts {
+                match *setting {
+                    TimeoutOverride::Read(read) => timeouts.read = read,
+                    TimeoutOverride::Write(write) => timeouts.write = write,
+                    TimeoutOverride::Idle(idle) => timeouts.idle = idle,
+                    TimeoutOverride::Handshake(handshake) => timeouts.handshake = handshake,
+                }
+            }
+            self.config.timeouts = Some(timeouts);
+        }
+        self.config
+    }
+}
+
+// Parses comma-separated endpoints that use the server-wide settings.
+fn parse_addresses(value: &str) -> io::Result<Vec<PendingListener>> {
+    let addresses: Vec<PendingListener> = value
+        .split(',')
+        .map(str::trim)
+        .filter(|address| !address.is_empty())
+        .map(|address| {
+            let address = address.parse().map_err(|e| invalid_data(format!("invalid value for `address`: {}", e)))?;
+            Ok(PendingListener { config: ListenerConfig::new(address), socket: Vec::new(), timeouts: Vec::new() })
+        })
+        .collect::<io::Result<_>>()?;
+    if addresses.is_empty() {
+        return Err(invalid_data("`address` needs at least one endpoint".to_string()));
+    }
+    Ok(addresses)
+}
+
+// Parses a `listen` value: an endpoint followed by `key=value` overrides.
+fn parse_listen(value: &str) -> io::Result<PendingListener> {
+    let mut words = value.split_whitespace();
+    let address = words.next().ok_or_else(|| invalid_data("`listen` needs an endpoint".to_string()))?;
+    let address = address.parse().map_err(|e| invalid_data(format!("invalid value for `listen`: {}", e)))?;
+    let mut pending = PendingListener { config: ListenerConfig::new(address), socket: Vec::new(), timeouts: Vec::new() };
+    for word in words {
+        let (key, value) = word
+            .split_once('=')
+            .ok_or_else(|| invalid_data(format!("expected `key=value` after the endpoint, found `{}`", word)))?;
+        match key {
+            "acceptors" => pending.config.acceptors = Some(parse(key, value)?),
+            "max_frame_len" => pending.config.max_frame_len = Some(parse(key, value)?),
+            "codec" => pending.config.codec = parse(key, value)?,
+            "backlog" => pending.socket.push(SocketOverride::Backlog(parse(key, value)?)),
+            "tcp_nodelay" => pending.socket.push(SocketOverride::NoDelay(parse(key, value)?)),
+            "ipv6_only" => pending.socket.push(SocketOverride::OnlyV6(parse(key, value)?)),
+            "keepalive_idle_ms" => pending.socket.push(SocketOverride::KeepAliveIdle(parse_millis(key, value)?)),
+            "linger_ms" => pending.socket.push(SocketOverride::Linger(parse_linger(key, value)?)),
+            "read_timeout_ms" => pending.timeouts.push(TimeoutOverride::Read(parse_millis(key, value)?)),
+            "write_timeout_ms" => pending.timeouts.push(TimeoutOverride::Write(parse_millis(key, value)?)),
+            "idle_timeout_ms" => {
+                let idle = Some(parse_millis(key, value)?).filter(|d| !d.is_zero());
+                pending.timeouts.push(TimeoutOverride::Idle(idle));
+            }
+            "handshake_timeout_ms" => {
+                let handshake = Some(parse_millis(key, value)?).filter(|d| !d.is_zero());
+                pending.timeouts.push(TimeoutOverride::Handshake(handshake));
+            }
+            _ => return Err(invalid_data(format!("unknown listener setting `{}`", key))),
+        }
+    }
+    Ok(pending)
+}
+
+// Parses a `pool` value: a name followed by `threads`, `queue` and `messages` settings.
+fn parse_pool(value: &str) -> io::Result<PoolConfig> {
+    let mut words = value.split_whitespace();
+    let name = words.next().ok_or_else(|| invalid_data("`pool` needs a name".to_string()))?;
+    let mut pool = PoolConfig::new(name, 1, 64);
+    for word in words {
+        let (key, value) = word
+            .split_once('=')
+            .ok_or_else(|| invalid_data(format!("expected `key=value` after the pool name, found `{}`", word)))?;
+        match key {
+            "threads" => pool.threads = parse(key, value)?,
+            "queue" => pool.queue_capacity = parse(key, value)?,
+            "messages" => pool.messages = parse_list(key, value)?,
+            _ => return Err(invalid_data(format!("unknown pool setting `{}`", key))),
+        }
+    }
+    Ok(pool)
+}
+
+// The load shedding keys of a file, resolved after parsing so their order does not matter.
+#[derive(Default)]
+struct PendingShedding {
+    target: Option<Duration>, // `shed_target_ms`; zero disables shedding.
+    interval: Option<Duration>, // `shed_interval_ms`.
+}
+
+impl PendingShedding {
+    // Returns the load shedding setting, or None if neither key was given. A zero target disables
+    // shedding whatever the interval; either key alone enables it with the other's default.
+    fn resolve(self) -> Option<Option<CoDelConfig>> {
+        let defaults = CoDelConfig::default();
+        match (self.target, self.interval) {
+            (None, None) => None,
+            (Some(target), _) if target.is_zero() => Some(None),
+            (target, interval) => Some(Some(CoDelConfig {
+                target: target.unwrap_or(defaults.target),
+                interval: interval.unwrap_or(defaults.interval),
+            })),
+        }
+    }
+}
+
+// Returns the keepalive settings, enabling keepalive with a 60 second idle time if it was unset
+// or off.
+fn keepalive(socket: &mut SocketOptions) -> &mut KeepAlive {
+    if !matches!(socket.keepalive, Some(Setting::On(_))) {
+        socket.keepalive = Some(Setting::On(KeepAlive { idle: Duration::from_secs(60), interval: None, retries: None }));
+    }
+    match socket.keepalive.as_mut() {
+        Some(Setting::On(keepalive)) => keepalive,
+        _ => unreachable!("keepalive was just enabled"),
+    }
+}
+
+// Turns keepalive off if its idle time is zero, whatever order the keepalive keys came in.
+fn disable_zero_keepalive(socket: &mut SocketOptions) {
+    if matches!(socket.keepalive, Some(Setting::On(keepalive)) if keepalive.idle.is_zero()) {
+        socket.keepalive = Some(Setting::Off);
+    }
+}
+
+// Parses a linger time in milliseconds, or `off` to close without lingering.
+fn parse_linger(key: &str, value: &str) -> io::Result<Setting<Duration>> {
+    match value {
+        "off" => Ok(Setting::Off),
+        _ => parse_millis(key, value).map(Setting::On),
+    }
+}
+
+// Parses a single value.
+fn parse<T: FromStr>(key: &str, value: &str) -> io::Result<T> {
+    value
+        .parse()
+        .map_err(|_| invalid_data(format!("invalid value for `{}`: {}", key, value)))
+}
+
+// Parses a duration given in milliseconds.
+fn parse_millis(key: &str, value: &str) -> io::Result<Duration> {
+    parse(key, value).map(Duration::from_millis)
+}
+
+// Parses a comma-separated list, such as address ranges.
+fn parse_list<T: FromStr>(key: &str, value: &str) -> io::Result<Vec<T>> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(|item| parse(key, item))
+        .collect()
+}
+
+// Parses a slow-consumer policy: `drop`, `disconnect` or `block:<ms>`.
+fn parse_policy(value: &str) -> io::Result<SlowConsumerPolicy> {
+    match value {
+        "drop" => Ok(SlowConsumerPolicy::Drop),
+        "disconnect" => Ok(SlowConsumerPolicy::Disconnect),
+        _ => match value.strip_prefix("block:") {
+            Some(millis) => parse_millis("slow_consumer", millis).map(SlowConsumerPolicy::Block),
+            None => Err(invalid_data(format!("invalid value for `slow_consumer`: {}", value))),
+        },
+    }
+}
+
+// Parses a panic policy: `close` or `keep_open`.
+fn parse_panic_policy(value: &str) -> io::Result<PanicPolicy> {
+    match value {
+        "close" => Ok(PanicPolicy::Close),
+        "keep_open" => Ok(PanicPolicy::KeepOpen),
+        _ => Err(invalid_data(format!("invalid value for `on_panic`: {}", value))),
+    }
+}
+
+// Builds an InvalidData error.
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, message)
+}
@@ -0,0 +1,239 @@
+use crate::config::PoolConfig; // Import executor pool settings.
+use log::error; // Import macros for structured logging.
+use std::{
+    collections::{HashMap, VecDeque}, // Message routes and queued jobs.
+    fmt, // Formatting support for errors.
+    hash::Hash, // Message kinds key the routing table.
+    io::{self, ErrorKind}, // Error type for thread spawn failures and unknown message kinds.
+    panic::{self, AssertUnwindSafe}, // Keeping workers alive when a job panics.
+    str::FromStr, // Parsing the message kinds named in pool settings.
+    sync::{Arc, Condvar, Mutex, MutexGuard}, // Synchronization primitives for the shared job queue.
+    thread::{self, JoinHandle}, // Support for spawning worker threads.
+    time::{Duration, Instant}, // Measuring how long pools stay saturated.
+};
+
+// Reasons a job could not be queued on a pool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubmitError {
+    Full, // The pool's queue is at its limit.
+    Closed, // The pool is shutting down.
+}
+
+impl fmt::Display for SubmitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let reason = match self {
+            SubmitError::Full => "executor pool queue full",
+            SubmitError::Closed => "executor pool closed",
+        };
+        f.write_str(reason)
+    }
+}
+
+impl std::error::Error for SubmitError {}
+
+// Point-in-time view of a pool's load.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PoolStats {
+    pub name: String, // Pool name from the configuration.
+    pub threads: usize, // Worker threads.
+    pub busy: usize, // Workers running a job right now.
+    pub queued: usize, // Jobs waiting for a worker.
+    pub queue_capacity: usize, // Jobs that may wait before submissions are rejected.
+    pub peak_queued: usize, // Most jobs ever waiting at once.
+    pub completed: u64, // Jobs run to completion.
+    pub rejected: u64, // Submissions refused because the queue was full.
+    pub saturated: Duration, // Total time every worker was busy.
+}
+
+// A unit of work run by a pool worker.
+type Job = Box<dyn FnOnce() + Send + 'static>;
+
+// Pool contents guarded by the mutex.
+struct State {
+    jobs: VecDeque<Job>, // Jobs waiting for a worker.
+    busy: usize, // Workers running a job.
+    peak_queued: usize, // Longest the queue has been.
+    completed: u64, // Jobs finished.
+    rejected: u64, // Jobs refused because the queue was full.
+    saturated_since: Option<Instant>, // Time every worker became busy, while they all are.
+    saturated: Duration, // Completed periods with every worker busy.
+    closed: bool, // Set once the pool is shutting down; queued jobs still run.
+}
+
+// Queue and counters shared with the worker threads.
+struct Shared {
+    state: Mutex<State>, // Pool contents.
+    available: Condvar, // Signalled when a job is queued or the pool closes.
+}
+
+impl Shared {
+    // Locks the pool, recovering from a poisoned lock.
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+// A fixed set of worker threads running jobs from a bounded queue.
+pub struct Pool {
+    name: String, // Pool name, used in thread names and metrics.
+    threads: usize, // Number of workers.
+    queue_capacity: usize, // Maximum number of waiting jobs.
+    shared: Arc<Shared>, // Queue shared with the workers.
+    workers: Vec<JoinHandle<()>>, // Worker threads, joined when the pool is dropped.
+}
+
+impl Pool {
+    // Starts a pool with the configured number of workers.
+    pub fn new(config: &PoolConfig) -> io::Result<Self> {
+        let shared = Arc::new(Shared {
+            state: Mutex::new(State {
+                jobs: VecDeque::new(),
+                busy: 0,
+                peak_queued: 0,
+                completed: 0,
+                rejected: 0,
+                saturated_since: None,
+                saturated: Duration::ZERO,
+                closed: false,
+            }),
+            available: Condvar::new(),
+        });
+        let mut pool = Pool {
+            name: config.name.clone(),
+            threads: config.threads,
+            queue_capacity: config.queue_capacity,
+            shared,
+            workers: Vec::with_capacity(config.threads),
+        };
+        for index in 0..config.threads {
+            let shared = Arc::clone(&pool.shared);
+            let threads = pool.threads;
+            let worker = thread::Builder::new()
+                .name(format!("pool-{}-{}", pool.name, index))
+                .spawn(move || work(&shared, threads))?; // Dropping `pool` stops the workers already started.
+            pool.workers.push(worker);
+        }
+        Ok(pool)
+    }
+
+    // Returns the pool name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    // Queues a job, or refuses it when the queue is full or the pool is closing.
+    pub fn submit(&self, job: impl FnOnce() + Send + 'static) -> Result<(), SubmitError> {
+        let mut state = self.shared.lock();
+        if state.closed {
+            return Err(SubmitError::Closed);
+        }
+        // Idle workers take jobs straight away, so only jobs that must wait count against the limit.
+        let waiting = (state.jobs.len() + 1 + state.busy).saturating_sub(self.threads);
+        if waiting > self.queue_capacity {
+            state.rejected += 1;
+            return Err(SubmitError::Full);
+        }
+        state.jobs.push_back(Box::new(job));
+        state.peak_queued = state.peak_queued.max(waiting);
+        self.shared.available.notify_one();
+        Ok(())
+    }
+
+    // Returns the pool's current load and counters.
+    pub fn stats(&self) -> PoolStats {
+        let state = self.shared.lock();
+        let ongoing = state.saturated_since.map(|since| since.elapsed()).unwrap_or_default();
+        PoolStats {
+            name: self.name.clone(),
+            threads: self.threads,
+            busy: state.busy,
+            queued: state.jobs.len(),
+            queue_capacity: self.queue_capacity,
+            peak_queued: state.peak_queued,
+            completed: state.completed,
+            rejected: state.rejected,
+            saturated: state.saturated + ongoing,
+        }
+    }
+}
+
+impl Drop for Pool {
+    // Runs the jobs already queued, then stops the workers.
+    fn drop(&mut self) {
+        self.shared.lock().closed = true;
+        self.shared.available.notify_all();
+        for worker in self.workers.drain(..) {
+            if worker.join().is_err() {
+                error!("Worker thread of pool {} panicked", self.name);
+            }
+        }
+    }
+}
+
+// Runs queued jobs until the pool closes and its queue is empty.
+fn work(shared: &Shared, threads: usize) {
+    let mut state = shared.lock();
+    loop {
+        let job = match state.jobs.pop_front() {
+            Some(job) => job,
+            None if state.closed => return,
+            None => {
+                state = shared.available.wait(state).unwrap_or_else(|poisoned| poisoned.into_inner());
+                continue;
+            }
+        };
+        state.busy += 1;
+        if state.busy == threads {
+            state.saturated_since = Some(Instant::now()); // The last idle worker just took a job.
+        }
+        drop(state);
+
+        if panic::catch_unwind(AssertUnwindSafe(job)).is_err() {
+            error!("Job panicked on an executor pool worker"); // Keep the worker for later jobs.
+        }
+
+        state = shared.lock();
+        if let Some(since) = state.saturated_since.take() {
+            state.saturated += since.elapsed(); // A worker is idle again.
+        }
+        state.busy -= 1;
+        state.completed += 1;
+    }
+}
+
+// The pools of a server and the message kinds assigned to each. Requests of kinds without a pool
+// are handled on their connection's thread.
+pub(crate) struct Executors<K> {
+    pools: Vec<Pool>, // Pools in configuration order.
+    routes: HashMap<K, usize>, // Index of the pool handling each assigned kind.
+}
+
+impl<K: Eq + Hash + FromStr> Executors<K> {
+    // Starts every configured pool. Fails if a pool names a message kind the protocol lacks.
+    pub(crate) fn new(configs: &[PoolConfig]) -> io::Result<Self> {
+        let mut routes = HashMap::new();
+        for (index, config) in configs.iter().enumerate() {
+            for name in &config.messages {
+                let kind = name.parse().map_err(|_| {
+                    io::Error::new(
+                        ErrorKind::InvalidInput,
+                        format!("executor pool `{}` handles unknown message type `{}`", config.name, name),
+                    )
+                })?;
+                routes.insert(kind, index);
+            }
+        }
+        let pools = configs.iter().map(Pool::new).collect::<io::Result<Vec<_>>>()?;
+        Ok(Executors { pools, routes })
+    }
+
+    // Returns the pool that handles requests of `kind`, if one is assigned.
+    pub(crate) fn route(&self, kind: K) -> Option<&Pool> {
+        self.routes.get(&kind).map(|&index| &self.pools[index])
+    }
+
+    // Returns the load of every pool, in configuration order.
+    pub(crate) fn stats(&self) -> Vec<PoolStats> {
+        self.pools.iter().map(Pool::stats).collect()
+    }
+}
@@ -0,0 +1,342 @@
+use crate::broker::{self, Broker}; // Import the topic broker.
+use crate::message::{
+    client_message, AddRequest, AddResponse, ConnectRequest, ConnectResponse, DisconnectRequest, DisconnectResponse,
+    EchoMessage, JoinRoomRequest, JoinRoomResponse, LeaveRoomRequest, LeaveRoomResponse, PingRequest,
+    PingResponse, PublishRequest, PublishResponse, RoomMembersRequest, RoomMembersResponse, RoomMessageRequest,
+    RoomMessageResponse, SubscribeRequest, SubscribeResponse, UnsubscribeRequest, UnsubscribeResponse,
+}; // Import the message types handlers exchange.
+use crate::protocol::{Messages, Protocol}; // Import the schemas handlers serve.
+use crate::router::{self, MessageKind, Router}; // Import the generated request routing.
+use crate::registry::ConnectionId; // Import connection identifiers.
+use crate::rooms::Rooms; // Import the chat rooms.
+use crate::session::Session; // Import per-connection state.
+use crate::state::AppState; // Import state shared by every handler.
+use crate::stream::Endpoint; // Import peer addresses.
+use log::info; // Import macros for structured logging.
+use std::{
+    any::Any, // Application state is looked up by type.
+    collections::HashMap, // Handlers by message type.
+    fmt, // Formatting support for handler errors.
+    sync::Arc, // Handlers shared with every connection.
+};
+
+// What a handler knows about the request it is serving.
+#[derive(Debug, Clone)]
+pub struct Context {
+    session: Arc<Session>, // State of the connection the request arrived on.
+    state: Arc<AppState>, // Application state shared by every connection.
+}
+
+impl Context {
+    // Creates the context for a request from connection `connection_id`, with a session of its own.
+    pub fn new(connection_id: ConnectionId, peer_addr: Endpoint) -> Self {
+        Self::with_session(Arc::new(Session::new(connection_id, peer_addr)))
+    }
+
+    // Creates the context for requests from the connection `session` belongs to.
+    pub fn with_session(session: Arc<Session>) -> Self {
+        Context { session, state: Arc::new(AppState::new()) }
+    }
+
+    // Gives handlers access to `state` instead of an empty container.
+    pub fn with_state(mut self, state: Arc<AppState>) -> Self {
+        self.state = state;
+        self
+    }
+
+    // Returns the registry ID of the connection, usable with `Server::send` and `Server::disconnect`.
+    pub fn connection_id(&self) -> ConnectionId {
+        self.session.id()
+    }
+
+    // Returns the address of the client.
+    pub fn peer_addr(&self) -> &Endpoint {
+        self.session.peer_addr()
+    }
+
+    // Returns the session of the connection, shared with every other request it sends.
+    pub fn session(&self) -> &Arc<Session> {
+        &self.session
+    }
+
+    // Returns the application state of type `T` registered with `Server::insert_state`, if any.
+    pub fn state<T: Any + Send + Sync>(&self) -> Option<&T> {
+        self.state.get()
+    }
+}
+
+// Why a request failed, independent of any schema. Each protocol maps the code to its own error
+// reply; the numbers match `ErrorCode` in messages.proto, and `Custom` carries a protocol's own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorCode {
+    Internal, // The server failed while handling the request.
+    Overloaded, // The server is shedding load; the request may be retried later.
+    InvalidArgument, // The request's contents cannot be accepted.
+    Unimplemented, // No handler serves the request.
+    PermissionDenied, // The client may not make the request.
+    Custom(i32), // A code defined by the protocol.
+}
+
+impl From<ErrorCode> for i32 {
+    fn from(code: ErrorCode) -> i32 {
+        match code {
+            ErrorCode::Internal => 1,
+            ErrorCode::Overloaded => 2,
+            ErrorCode::InvalidArgument => 3,
+            ErrorCode::Unimplemented => 4,
+            ErrorCode::PermissionDenied => 5,
+            ErrorCode::Custom(code) => code,
+        }
+    }
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ErrorCode::Internal => f.write_str("internal"),
+            ErrorCode::Overloaded => f.write_str("overloaded"),
+            ErrorCode::InvalidArgument => f.write_str("invalid argument"),
+            ErrorCode::Unimplemented => f.write_str("unimplemented"),
+            ErrorCode::PermissionDenied => f.write_str("permission denied"),
+            ErrorCode::Custom(code) => write!(f, "error {}", code),
+        }
+    }
+}
+
+// A failed request, answered with the protocol's error reply.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HandlerError {
+    pub code: ErrorCode, // Error code reported to the client.
+    pub message: String, // Human-readable description reported to the client.
+}
+
+impl HandlerError {
+    // Creates an error with the given code and message.
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        HandlerError { code, message: message.into() }
+    }
+
+    // Creates an error for a request whose contents the handler cannot accept.
+    pub fn invalid_argument(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::InvalidArgument, message)
+    }
+}
+
+impl fmt::Display for HandlerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.code, self.message)
+    }
+}
+
+impl std::error::Error for HandlerError {}
+
+// The reply to a request of protocol `P`: a message, no reply at all, or an error.
+pub type HandlerResult<P = Messages> = Result<Option<<P as Protocol>::Reply>, HandlerError>;
+
+// Serves requests of the message types it is registered for with `Server::register_handler`.
+// Handlers run on connection threads or executor pool workers, possibly for several connections
+// at once. A panic is answered with an `Internal` error, as decided by the server's panic policy.
+pub trait Handler<P: Protocol = Messages>: Send + Sync {
+    // Computes the reply to `request`.
+    fn handle(&self, request: P::Message, context: &Context) -> HandlerResult<P>;
+}
+
+impl<P, F> Handler<P> for F
+where
+    P: Protocol,
+    F: Fn(P::Message, &Context) -> HandlerResult<P> + Send + Sync,
+{
+    fn handle(&self, request: P::Message, context: &Context) -> HandlerResult<P> {
+        self(request, context)
+    }
+}
+
+// The built-in request logic, behind the `Echo` and `Add` handlers.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct BuiltIn;
+
+impl Router for BuiltIn {
+    fn echo_message(&self, request: EchoMessage, _context: &Context) -> Result<EchoMessage, HandlerError> {
+        info!("Received EchoMessage: {}", request.content); // Log the message content.
+        Ok(request) // Echo the message back.
+    }
+
+    fn add_request(&self, request: AddRequest, _context: &Context) -> Result<AddResponse, HandlerError> {
+        info!("Received AddRequest: a = {}, b = {}", request.a, request.b); // Log the numbers to add.
+        let result = request.a.checked_add(request.b).ok_or_else(|| HandlerError::invalid_argument("sum overflows int32"))?;
+        Ok(AddResponse { result }) // Compute the sum.
+    }
+
+    fn subscribe_request(&self, request: SubscribeRequest, context: &Context) -> Result<SubscribeResponse, HandlerError> {
+        broker::validate_filter(&request.topic).map_err(HandlerError::invalid_argument)?;
+        let (subscriptions, retained) = broker(context)?
+            .subscribe(context.connection_id(), &request.topic)
+            .map_err(|e| HandlerError::new(ErrorCode::Internal, format!("cannot deliver publications: {}", e)))?;
+        Ok(SubscribeResponse { subscriptions: subscriptions as u32, retained: retained as u32 })
+    }
+
+    fn unsubscribe_request(&self, request: UnsubscribeRequest, context: &Context) -> Result<UnsubscribeResponse, HandlerError> {
+        broker::validate_filter(&request.topic).map_err(HandlerError::invalid_argument)?;
+        let subscriptions = broker(context)?.unsubscribe(context.connection_id(), &request.topic);
+        Ok(UnsubscribeResponse { subscriptions: subscriptions as u32 })
+    }
+
+    fn publish_request(&self, request: PublishRequest, context: &Context) -> Result<PublishResponse, HandlerError> {
+        broker::validate_topic(&request.topic).map_err(HandlerError::invalid_argument)?;
+        let subscribers = broker(context)?.publish(&request.topic, request.payload, request.retain);
+        Ok(PublishResponse { subscribers: subscribers as u32 })
+    }
+
+    fn connect_request(&self, request: ConnectRequest, context: &Context) -> Result<ConnectResponse, HandlerError> {
+        let broker = broker(context)?;
+        match request.will {
+            Some(will) => {
+                broker::validate_topic(&will.topic).map_err(HandlerError::invalid_argument)?;
+                broker.set_will(context.connection_id(), will);
+            }
+            None => {
+                broker.clear_will(context.connection_id());
+            }
+        }
+        Ok(ConnectResponse {})
+    }
+
+    fn disconnect_request(&self, _request: DisconnectRequest, context: &Context) -> Result<DisconnectResponse, HandlerError> {
+        broker(context)?.clear_will(context.connection_id()); // A clean disconnect leaves no last will.
+        Ok(DisconnectResponse {})
+    }
+
+    fn join_room_request(&self, request: JoinRoomRequest, context: &Context) -> Result<JoinRoomResponse, HandlerError> {
+        validate_room(&request.room)?;
+        let identity = context.session().identity().unwrap_or_default();
+        let members = rooms(context)?.join(context.connection_id(), &identity, &request.room);
+        Ok(JoinRoomResponse { members })
+    }
+
+    fn leave_room_request(&self, request: LeaveRoomRequest, context: &Context) -> Result<LeaveRoomResponse, HandlerError> {
+        validate_room(&request.room)?;
+        let was_member = rooms(context)?.leave(context.connection_id(), &request.room);
+        Ok(LeaveRoomResponse { was_member })
+    }
+
+    fn room_members_request(&self, request: RoomMembersRequest, context: &Context) -> Result<RoomMembersResponse, HandlerError> {
+        validate_room(&request.room)?;
+        Ok(RoomMembersResponse { members: rooms(context)?.members(&request.room) })
+    }
+
+    fn room_message_request(&self, request: RoomMessageRequest, context: &Context) -> Result<RoomMessageResponse, HandlerError> {
+        validate_room(&request.room)?;
+        let recipients = rooms(context)?
+            .send(context.connection_id(), &request.room, &request.content)
+            .ok_or_else(|| HandlerError::new(ErrorCode::PermissionDenied, format!("not a member of room {}", request.room)))?;
+        Ok(RoomMessageResponse { recipients: recipients as u32 })
+    }
+
+    fn ping_request(&self, _request: PingRequest, _context: &Context) -> Result<PingResponse, HandlerError> {
+        Ok(PingResponse {}) // Reading the request already reset the idle timer.
+    }
+}
+
+// Returns the broker the server registered for the publish/subscribe requests.
+fn broker(context: &Context) -> Result<&Broker, HandlerError> {
+    context
+        .state::<Broker>()
+        .ok_or_else(|| HandlerError::new(ErrorCode::Unimplemented, "publish/subscribe is not available"))
+}
+
+// Returns the rooms the server registered for the chat room requests.
+fn rooms(context: &Context) -> Result<&Rooms, HandlerError> {
+    context
+        .state::<Rooms>()
+        .ok_or_else(|| HandlerError::new(ErrorCode::Unimplemented, "chat rooms are not available"))
+}
+
+// Rejects an empty room name.
+fn validate_room(room: &str) -> Result<(), HandlerError> {
+    if room.is_empty() {
+        return Err(HandlerError::invalid_argument("room name is empty"));
+    }
+    Ok(())
+}
+
+// Built-in handler that sends `EchoMessage` requests back unchanged.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Echo;
+
+impl Handler for Echo {
+    fn handle(&self, request: client_message::Message, context: &Context) -> HandlerResult {
+        expect(MessageKind::EchoMessage, &request)?;
+        router::route(&BuiltIn, request, context)
+    }
+}
+
+// Built-in handler that answers `AddRequest` with the sum of its operands.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Add;
+
+impl Handler for Add {
+    fn handle(&self, request: client_message::Message, context: &Context) -> HandlerResult {
+        expect(MessageKind::AddRequest, &request)?;
+        router::route(&BuiltIn, request, context)
+    }
+}
+
+// Rejects a request routed to a handler registered for another message type.
+fn expect(kind: MessageKind, request: &client_message::Message) -> Result<(), HandlerError> {
+    match MessageKind::of(request) {
+        actual if actual == kind => Ok(()),
+        actual => Err(HandlerError::invalid_argument(format!("unexpected {} request", actual))),
+    }
+}
+
+// Serves every message type with a router.
+struct Routed<R>(Arc<R>);
+
+impl<R: Router> Handler for Routed<R> {
+    fn handle(&self, request: client_message::Message, context: &Context) -> HandlerResult {
+        router::route(&*self.0, request, context)
+    }
+}
+
+// The handler registered for each message type.
+pub(crate) struct Handlers<P: Protocol> {
+    handlers: HashMap<P::Kind, Arc<dyn Handler<P>>>, // Handler by message type.
+}
+
+impl<P: Protocol> Clone for Handlers<P> {
+    fn clone(&self) -> Self {
+        Handlers { handlers: self.handlers.clone() }
+    }
+}
+
+impl<P: Protocol> Default for Handlers<P> {
+    fn default() -> Self {
+        Handlers { handlers: HashMap::new() }
+    }
+}
+
+impl<P: Protocol> Handlers<P> {
+    // Makes `handler` serve requests of `kind`, replacing the previous handler.
+    pub(crate) fn register(&mut self, kind: P::Kind, handler: impl Handler<P> + 'static) {
+        self.handlers.insert(kind, Arc::new(handler));
+    }
+
+    // Computes the reply to a request with the handler registered for its type.
+    pub(crate) fn dispatch(&self, request: P::Message, context: &Context) -> HandlerResult<P> {
+        let kind = P::kind(&request);
+        match self.handlers.get(&kind) {
+            Some(handler) => handler.handle(request, context),
+            None => Err(HandlerError::new(ErrorCode::Unimplemented, format!("no handler for {}", kind))),
+        }
+    }
+}
+
+impl Handlers<Messages> {
+    // Makes `router` serve requests of every type, replacing the previous handlers.
+    pub(crate) fn register_router<R: Router + 'static>(&mut self, router: R) {
+        let router = Arc::new(router);
+        for kind in MessageKind::ALL {
+            self.handlers.insert(kind, Arc::new(Routed(Arc::clone(&router))));
+        }
+    }
+}
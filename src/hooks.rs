@@ -0,0 +1,106 @@
+use crate::session::Session; // Import per-connection state.
+use log::error; // Import macros for structured logging.
+use std::{
+    fmt, // Formatting support for disconnect reasons.
+    panic::{self, AssertUnwindSafe}, // Keeping connection threads alive when a hook panics.
+    sync::Arc, // Hooks shared with every connection.
+};
+
+// Why a connection closed, as reported to `on_disconnect` hooks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DisconnectReason {
+    Eof, // The client closed the connection.
+    Timeout, // The handshake or idle timeout expired.
+    Error, // Reading or writing failed, or a handler panicked under the close policy.
+    Kicked, // The connection was closed with `Server::disconnect`.
+    Shutdown, // The server stopped.
+}
+
+impl fmt::Display for DisconnectReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let reason = match self {
+            DisconnectReason::Eof => "closed by client",
+            DisconnectReason::Timeout => "timed out",
+            DisconnectReason::Error => "connection error",
+            DisconnectReason::Kicked => "disconnected by server",
+            DisconnectReason::Shutdown => "server shutdown",
+        };
+        f.write_str(reason)
+    }
+}
+
+// A hook run with the session of a connection.
+type SessionHook = Arc<dyn Fn(&Session) + Send + Sync>;
+
+// A hook run with the session of a closed connection and why it closed.
+type DisconnectHook = Arc<dyn Fn(&Session, DisconnectReason) + Send + Sync>;
+
+// A hook run once the server has stopped.
+type ShutdownHook = Arc<dyn Fn() + Send + Sync>;
+
+// The lifecycle hooks registered on a server, each run in registration order. A panicking hook is
+// logged and skipped, so it cannot take a connection's cleanup down with it.
+#[derive(Clone, Default)]
+pub(crate) struct Hooks {
+    connect: Vec<SessionHook>, // Run on the connection thread before its first read.
+    handshake_complete: Vec<SessionHook>, // Run when the first request arrives, before it is handled.
+    disconnect: Vec<DisconnectHook>, // Run after the last reply, before the session is torn down.
+    server_shutdown: Vec<ShutdownHook>, // Run once every connection has closed.
+}
+
+impl Hooks {
+    // Adds a hook run when a connection is accepted.
+    pub(crate) fn on_connect(&mut self, hook: impl Fn(&Session) + Send + Sync + 'static) {
+        self.connect.push(Arc::new(hook));
+    }
+
+    // Adds a hook run when a connection completes its handshake.
+    pub(crate) fn on_handshake_complete(&mut self, hook: impl Fn(&Session) + Send + Sync + 'static) {
+        self.handshake_complete.push(Arc::new(hook));
+    }
+
+    // Adds a hook run when a connection closes.
+    pub(crate) fn on_disconnect(&mut self, hook: impl Fn(&Session, DisconnectReason) + Send + Sync + 'static) {
+        self.disconnect.push(Arc::new(hook));
+    }
+
+    // Adds a hook run when the server has stopped.
+    pub(crate) fn on_server_shutdown(&mut self, hook: impl Fn() + Send + Sync + 'static) {
+        self.server_shutdown.push(Arc::new(hook));
+    }
+
+    // Runs the connect hooks.
+    pub(crate) fn connected(&self, session: &Session) {
+        for hook in &self.connect {
+            isolated("on_connect", || hook(session));
+        }
+    }
+
+    // Runs the handshake hooks.
+    pub(crate) fn handshake_completed(&self, session: &Session) {
+        for hook in &self.handshake_complete {
+            isolated("on_handshake_complete", || hook(session));
+        }
+    }
+
+    // Runs the disconnect hooks.
+    pub(crate) fn disconnected(&self, session: &Session, reason: DisconnectReason) {
+        for hook in &self.disconnect {
+            isolated("on_disconnect", || hook(session, reason));
+        }
+    }
+
+    // Runs the shutdown hooks.
+    pub(crate) fn server_stopped(&self) {
+        for hook in &self.server_shutdown {
+            isolated("on_server_shutdown", || hook());
+        }
+    }
+}
+
+// Runs a hook, logging instead of propagating a panic.
+fn isolated(event: &str, hook: impl FnOnce()) {
+    if panic::catch_unwind(AssertUnwindSafe(hook)).is_err() {
+        error!("An {} hook panicked", event);
+    }
+}
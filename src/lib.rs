@@ -1,5 +1,32 @@
+pub mod access;
+#[cfg(unix)]
+pub mod activation;
+pub mod broker;
+pub mod codec;
+pub mod config;
+pub mod executor;
+pub mod handler;
+pub mod hooks;
+pub mod listener;
+pub mod middleware;
+#[cfg(unix)]
+pub mod notify;
+pub mod outbound;
+pub mod protocol;
+pub mod registry;
+pub mod rooms;
 pub mod server;
+pub mod session;
+pub mod shedding;
+pub mod socket;
+pub mod state;
+pub mod stream;
+pub mod tls;
 
 pub mod message {
     include!(concat!(env!("OUT_DIR"), "/messages.rs"));
 }
+
+pub mod router {
+    include!(concat!(env!("OUT_DIR"), "/router.rs"));
+}
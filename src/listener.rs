@@ -0,0 +1,320 @@
+use crate::socket::SocketOptions; // Import socket options applied before listening.
+use crate::stream::{Endpoint, Stream}; // Import the connection types listeners produce.
+#[cfg(unix)]
+use log::warn; // Import macros for structured logging.
+use socket2::{Domain, Protocol, Socket, Type}; // Low-level socket construction for options std does not expose.
+use std::{
+    fmt, // Formatting support for listen addresses.
+    io::{self, ErrorKind}, // Error type for bind failures.
+    net::{SocketAddr, TcpListener, ToSocketAddrs}, // Network primitives for resolving and listening.
+    str::FromStr, // Parsing listen addresses from configuration text.
+};
+#[cfg(unix)]
+use std::{
+    fs, // Removing stale and finished Unix socket files.
+    os::unix::{
+        fs::{FileTypeExt, MetadataExt}, // Telling stale sockets from other files, and ours from replacements.
+        io::{AsFd, AsRawFd, BorrowedFd, OwnedFd, RawFd}, // Descriptor access for handing listeners to another process.
+        net::{UnixListener, UnixStream}, // Unix domain sockets.
+    },
+    path::{Path, PathBuf}, // Unix socket paths.
+    sync::{
+        atomic::{AtomicBool, Ordering}, // Removing a socket file only once.
+        Arc, // Sharing a socket file between the acceptors of an endpoint.
+    },
+};
+
+// Prefix that marks a listen address as a Unix domain socket path.
+const UNIX_PREFIX: &str = "unix:";
+
+// Address a listener binds: `host:port` for TCP, or `unix:<path>` for a Unix domain socket.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ListenAddr {
+    Tcp(String), // Host and port, resolved when binding.
+    #[cfg(unix)]
+    Unix(PathBuf), // Filesystem path of the socket.
+}
+
+impl FromStr for ListenAddr {
+    type Err = io::Error;
+
+    fn from_str(s: &str) -> io::Result<Self> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Err(io::Error::new(ErrorKind::InvalidInput, "empty listen address"));
+        }
+        match s.strip_prefix(UNIX_PREFIX) {
+            #[cfg(unix)]
+            Some(path) if !path.is_empty() => Ok(ListenAddr::Unix(PathBuf::from(path))),
+            Some(_) => Err(io::Error::new(ErrorKind::InvalidInput, format!("invalid Unix socket address: {}", s))),
+            None => Ok(ListenAddr::Tcp(s.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for ListenAddr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ListenAddr::Tcp(addr) => f.write_str(addr),
+            #[cfg(unix)]
+            ListenAddr::Unix(path) => write!(f, "{}{}", UNIX_PREFIX, path.display()),
+        }
+    }
+}
+
+// A bound listening socket, over TCP or a Unix domain socket.
+#[derive(Debug)]
+pub enum Listener {
+    Tcp(TcpListener), // TCP listener.
+    #[cfg(unix)]
+    Unix(UnixListener), // Unix domain socket listener.
+}
+
+impl Listener {
+    // Accepts a connection, returning it with the peer's address.
+    pub fn accept(&self) -> io::Result<(Stream, Endpoint)> {
+        match self {
+            Listener::Tcp(listener) => {
+                let (stream, addr) = listener.accept()?;
+                Ok((Stream::Tcp(stream), Endpoint::Tcp(addr)))
+            }
+            #[cfg(unix)]
+            Listener::Unix(listener) => {
+                let (stream, addr) = listener.accept()?;
+                Ok((Stream::Unix(stream), Endpoint::Unix(addr.as_pathname().map(Path::to_path_buf))))
+            }
+        }
+    }
+
+    // Returns the address the listener is bound to.
+    pub fn local_addr(&self) -> io::Result<Endpoint> {
+        match self {
+            Listener::Tcp(listener) => listener.local_addr().map(Endpoint::Tcp),
+            #[cfg(unix)]
+            Listener::Unix(listener) => {
+                let addr = listener.local_addr()?;
+                Ok(Endpoint::Unix(addr.as_pathname().map(Path::to_path_buf)))
+            }
+        }
+    }
+
+    // Moves the listener into or out of non-blocking mode.
+    pub fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+        match self {
+            Listener::Tcp(listener) => listener.set_nonblocking(nonblocking),
+            #[cfg(unix)]
+            Listener::Unix(listener) => listener.set_nonblocking(nonblocking),
+        }
+    }
+
+    // Returns another handle to the same listening socket.
+    #[cfg(unix)]
+    fn try_clone(&self) -> io::Result<Listener> {
+        match self {
+            Listener::Tcp(listener) => listener.try_clone().map(Listener::Tcp),
+            Listener::Unix(listener) => listener.try_clone().map(Listener::Unix),
+        }
+    }
+}
+
+impl From<TcpListener> for Listener {
+    fn from(listener: TcpListener) -> Self {
+        Listener::Tcp(listener)
+    }
+}
+
+#[cfg(unix)]
+impl From<UnixListener> for Listener {
+    fn from(listener: UnixListener) -> Self {
+        Listener::Unix(listener)
+    }
+}
+
+#[cfg(unix)]
+impl AsFd for Listener {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        match self {
+            Listener::Tcp(listener) => listener.as_fd(),
+            Listener::Unix(listener) => listener.as_fd(),
+        }
+    }
+}
+
+#[cfg(unix)]
+impl AsRawFd for Listener {
+    fn as_raw_fd(&self) -> RawFd {
+        self.as_fd().as_raw_fd()
+    }
+}
+
+// Listeners bound for one endpoint.
+pub(crate) struct Bound {
+    pub(crate) listeners: Vec<Listener>, // One per acceptor.
+    #[cfg(unix)]
+    pub(crate) socket_file: Option<Arc<SocketFile>>, // The Unix socket file created by binding; None for TCP.
+}
+
+// A Unix socket file created by binding, removed when the server stops or the last listener on it
+// is dropped. Only the file that was bound is removed, so a socket another process has since bound
+// at the same path survives.
+#[cfg(unix)]
+#[derive(Debug)]
+pub(crate) struct SocketFile {
+    path: PathBuf, // Where the socket was bound.
+    id: (u64, u64), // Device and inode of the bound socket file.
+    owned: AtomicBool, // Whether the file is still this process's to remove.
+}
+
+#[cfg(unix)]
+impl SocketFile {
+    // Records the socket file just bound at `path`.
+    fn new(path: &Path) -> io::Result<Self> {
+        let metadata = fs::symlink_metadata(path)?;
+        Ok(SocketFile { path: path.to_path_buf(), id: (metadata.dev(), metadata.ino()), owned: AtomicBool::new(true) })
+    }
+
+    // Leaves the file in place, for a process that inherited the listening socket.
+    pub(crate) fn keep(&self) {
+        self.owned.store(false, Ordering::Relaxed);
+    }
+
+    // Removes the file if it is still ours and still the socket that was bound.
+    pub(crate) fn remove(&self) {
+        if !self.owned.swap(false, Ordering::Relaxed) {
+            return;
+        }
+        match fs::symlink_metadata(&self.path) {
+            Ok(metadata) if (metadata.dev(), metadata.ino()) == self.id => {
+                if let Err(e) = fs::remove_file(&self.path) {
+                    warn!("Failed to remove socket file {}: {}", self.path.display(), e);
+                }
+            }
+            _ => {} // Already removed or replaced.
+        }
+    }
+}
+
+#[cfg(unix)]
+impl Drop for SocketFile {
+    fn drop(&mut self) {
+        self.remove();
+    }
+}
+
+// Resolves `addr` to the first socket address it names.
+fn resolve(addr: &str) -> io::Result<SocketAddr> {
+    addr.to_socket_addrs()?
+        .next()
+        .ok_or_else(|| io::Error::new(ErrorKind::InvalidInput, format!("{} did not resolve to an address", addr)))
+}
+
+// Binds every endpoint in `endpoints`, each as `count` listeners with the given socket options,
+// and returns the listeners per endpoint. IPv6 endpoints that leave IPV6_V6ONLY unset are made
+// IPv6-only when an IPv4 endpoint is also bound, so `0.0.0.0:P` and `[::]:P` can coexist.
+pub(crate) fn bind_all(endpoints: &[(ListenAddr, usize, SocketOptions)]) -> io::Result<Vec<Bound>> {
+    let mut resolved = Vec::new();
+    for (addr, count, options) in endpoints {
+        let target = match addr {
+            ListenAddr::Tcp(host) => Some(resolve(host)?),
+            #[cfg(unix)]
+            ListenAddr::Unix(_) => None,
+        };
+        resolved.push((addr, target, *count, *options));
+    }
+    let has_ipv4 = resolved.iter().any(|(_, target, _, _)| target.is_some_and(|target| target.is_ipv4()));
+
+    let mut bound = Vec::new();
+    for (addr, target, count, mut options) in resolved {
+        let endpoint = match (target, addr) {
+            (Some(target), _) => {
+                if target.is_ipv6() && has_ipv4 && options.only_v6.is_none() {
+                    options.only_v6 = Some(true); // Leave IPv4 traffic to the IPv4 listener.
+                }
+                Bound {
+                    listeners: bind_listeners(target, count, &options)?,
+                    #[cfg(unix)]
+                    socket_file: None,
+                }
+            }
+            #[cfg(unix)]
+            (None, ListenAddr::Unix(path)) => bind_unix(path, count, &options)?,
+            (None, _) => unreachable!("TCP endpoints are always resolved"),
+        };
+        bound.push(endpoint); // Socket files of endpoints bound so far are removed if a later one fails.
+    }
+    Ok(bound)
+}
+
+// Binds `count` listeners to `addr` with the given socket options. More than one listener
+// requires SO_REUSEPORT, which lets the kernel spread incoming connections across them.
+fn bind_listeners(addr: SocketAddr, count: usize, options: &SocketOptions) -> io::Result<Vec<Listener>> {
+    let reuse_port = count > 1;
+    let first = bind(addr, reuse_port, options)?;
+    let bound = first.local_addr()?; // Reuse the resolved port when binding to port 0.
+    let mut listeners = vec![Listener::Tcp(first)];
+    for _ in 1..count {
+        listeners.push(Listener::Tcp(bind(bound, reuse_port, options)?));
+    }
+    Ok(listeners)
+}
+
+// Binds one listener, applying the socket options before it starts listening.
+fn bind(addr: SocketAddr, reuse_port: bool, options: &SocketOptions) -> io::Result<TcpListener> {
+    let socket = Socket::new(Domain::for_address(addr), Type::STREAM, Some(Protocol::TCP))?;
+    #[cfg(unix)]
+    socket.set_reuse_address(true)?; // Match std, which allows rebinding addresses in TIME_WAIT.
+    if reuse_port {
+        set_reuse_port(&socket)?; // Allow sibling listeners on the same address.
+    }
+    if let (true, Some(only_v6)) = (addr.is_ipv6(), options.only_v6) {
+        socket.set_only_v6(only_v6)?;
+    }
+    options.apply_to_listener(&socket)?; // Accepted streams inherit these options.
+    socket.bind(&addr.into())?;
+    socket.listen(options.backlog)?;
+    Ok(socket.into())
+}
+
+// Binds a Unix domain socket at `path`, replacing a stale socket file left by a process that
+// exited without removing it. A socket is stale when connecting to it is refused; anything else
+// at the path is left alone and fails the bind. Extra acceptors share the one listening socket.
+#[cfg(unix)]
+fn bind_unix(path: &Path, count: usize, options: &SocketOptions) -> io::Result<Bound> {
+    match fs::symlink_metadata(path) {
+        Ok(metadata) if !metadata.file_type().is_socket() => {
+            return Err(io::Error::new(ErrorKind::AddrInUse, format!("{} exists and is not a socket", path.display())));
+        }
+        Ok(_) => match UnixStream::connect(path) {
+            Ok(_) => {} // A live socket; binding reports the address in use.
+            Err(e) if matches!(e.kind(), ErrorKind::ConnectionRefused | ErrorKind::NotFound) => {
+                fs::remove_file(path)?; // Nothing is listening on it any more.
+            }
+            Err(e) => return Err(e), // Possibly live, for example behind a full backlog.
+        },
+        Err(e) if e.kind() == ErrorKind::NotFound => {}
+        Err(e) => return Err(e),
+    }
+    let socket = Socket::new(Domain::UNIX, Type::STREAM, None)?;
+    socket.bind(&socket2::SockAddr::unix(path)?)?;
+    let socket_file = Arc::new(SocketFile::new(path)?); // Removed again if listening fails.
+    socket.listen(options.backlog)?;
+    let first = Listener::Unix(UnixListener::from(OwnedFd::from(socket)));
+    let mut listeners = Vec::with_capacity(count);
+    for _ in 1..count {
+        listeners.push(first.try_clone()?);
+    }
+    listeners.insert(0, first);
+    Ok(Bound { listeners, socket_file: Some(socket_file) })
+}
+
+// Enables SO_REUSEPORT on the socket.
+#[cfg(unix)]
+fn set_reuse_port(socket: &Socket) -> io::Result<()> {
+    socket.set_reuse_port(true)
+}
+
+// SO_REUSEPORT is only available on Unix platforms.
+#[cfg(not(unix))]
+fn set_reuse_port(_socket: &Socket) -> io::Result<()> {
+    Err(io::Error::new(ErrorKind::Unsupported, "multiple acceptors require SO_REUSEPORT"))
+}
@@ -1,19 +1,335 @@
-use embedded_recruitment_task::server::Server;
-use log::{info, debug, error, warn};
+#[cfg(unix)]
+use embedded_recruitment_task::{activation, notify::Notifier};
+use embedded_recruitment_task::{
+    config::{ConfigFile, ListenerConfig},
+    listener::Listener,
+    server::Server,
+};
+use env_logger::Env;
+use log::{error, info, warn, LevelFilter, Log, Metadata, Record};
+use signal_hook::consts::{SIGINT, SIGTERM};
+#[cfg(unix)]
+use signal_hook::{
+    consts::{SIGHUP, SIGUSR2},
+    iterator::Signals,
+};
+#[cfg(unix)]
+use std::process::Command;
+#[cfg(not(unix))]
+use std::{sync::atomic::AtomicUsize, time::Duration};
+use std::{
+    env,
+    path::PathBuf,
+    process,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, OnceLock,
+    },
+    thread,
+};
 
-fn main() {
-    // Initialize logging
-    env_logger::init();
-
-    // Create and run the server
-    let addr = "127.0.0.1:8080"; // this is test address 
-    match Server::new(addr) {
-        Ok(server) => {
-            println!("Server running on {}", addr);
-            if let Err(e) = server.run() {
-                eprintln!("Server encountered an error: {}", e);
+// Logger shared with configuration reloads.
+static LOGGER: OnceLock<Logger> = OnceLock::new();
+
+// Exit code when the server stopped cleanly.
+const EXIT_OK: i32 = 0;
+// Exit code when the server failed to start or did not shut down cleanly.
+const EXIT_FAILURE: i32 = 1;
+
+// Command-line options: `embedded-recruitment-task [--config PATH] [ADDR...]`.
+struct Options {
+    config_path: Option<PathBuf>, // Configuration file, reloaded on SIGHUP.
+    addresses: Vec<String>, // Listen addresses; override the ones in the configuration file.
+}
+
+// Parses the command line.
+fn parse_args() -> Result<Options, String> {
+    let mut options = Options { config_path: None, addresses: Vec::new() };
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--config" => {
+                let path = args.next().ok_or("--config requires a path")?;
+                options.config_path = Some(PathBuf::from(path));
             }
+            _ if arg.starts_with("--") => return Err(format!("unexpected argument: {}", arg)),
+            _ => options.addresses.push(arg),
+        }
+    }
+    Ok(options)
+}
+
+// Logs through env_logger as configured by RUST_LOG, `info` by default. While the configuration
+// file sets `log_level`, that level replaces RUST_LOG's default level, and RUST_LOG's per-module
+// filters still apply up to it.
+struct Logger {
+    from_env: env_logger::Logger, // RUST_LOG alone.
+    configured: env_logger::Logger, // RUST_LOG's module filters; the level is capped by the configured one.
+    overridden: AtomicBool, // Whether the configuration file sets the level.
+}
+
+impl Logger {
+    // Returns the logger in charge.
+    fn current(&self) -> &env_logger::Logger {
+        if self.overridden.load(Ordering::Relaxed) {
+            &self.configured
+        } else {
+            &self.from_env
+        }
+    }
+}
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        self.current().enabled(metadata)
+    }
+
+    fn log(&self, record: &Record) {
+        self.current().log(record);
+    }
+
+    fn flush(&self) {
+        self.current().flush();
+    }
+}
+
+// Installs the logger, leaving the level to RUST_LOG until the configuration sets one.
+fn init_logging() {
+    let logger = LOGGER.get_or_init(|| Logger {
+        from_env: env_logger::Builder::from_env(Env::default().default_filter_or("info")).build(),
+        configured: env_logger::Builder::from_default_env().filter_level(LevelFilter::Trace).build(),
+        overridden: AtomicBool::new(false),
+    });
+    if log::set_logger(logger).is_ok() {
+        log::set_max_level(logger.from_env.filter());
+    }
+}
+
+// Applies the `log_level` of the configuration file, or returns the level to RUST_LOG when the
+// file does not set it.
+fn set_log_level(level: Option<LevelFilter>) {
+    if let Some(logger) = LOGGER.get() {
+        logger.overridden.store(level.is_some(), Ordering::Relaxed);
+        log::set_max_level(level.unwrap_or_else(|| logger.from_env.filter()));
+    }
+}
+
+// Loads the configuration file, or the defaults when none was given.
+fn load_config(options: &Options) -> std::io::Result<ConfigFile> {
+    let mut config = match &options.config_path {
+        Some(path) => ConfigFile::load(path)?,
+        None => ConfigFile::default(),
+    };
+    if !options.addresses.is_empty() {
+        // The command line wins over the file.
+        config.listeners = options
+            .addresses
+            .iter()
+            .map(|address| address.parse().map(ListenerConfig::new))
+            .collect::<std::io::Result<_>>()?;
+    }
+    Ok(config)
+}
+
+// Re-reads the configuration file and applies the reloadable settings to the running server.
+#[cfg(unix)]
+fn reload(server: &Server, options: &Options, listeners: &[ListenerConfig]) {
+    if options.config_path.is_none() {
+        warn!("Received SIGHUP but no configuration file was given; nothing to reload");
+        return;
+    }
+    let config = match load_config(options) {
+        Ok(config) => config,
+        Err(e) => {
+            error!("Configuration reload failed, keeping the current settings: {}", e);
+            return;
         }
-        Err(e) => eprintln!("Failed to start server: {}", e),
+    };
+    if let Err(e) = server.reload(config.server.clone()) {
+        error!("Configuration reload failed, keeping the current settings: {}", e);
+        return;
     }
+    if config.listeners != listeners {
+        warn!("Changing listeners requires a restart; keeping the current ones");
+    }
+    set_log_level(config.log_level);
+    info!("Configuration reloaded from {}", options.config_path.as_ref().unwrap().display());
+}
+
+// Starts a new copy of this binary that inherits the listening sockets. The new process accepts
+// from the same sockets, so no connection is refused while this one drains.
+#[cfg(unix)]
+fn upgrade(server: &Server) -> std::io::Result<()> {
+    let mut command = Command::new(env::current_exe()?);
+    command.args(env::args_os().skip(1)); // Same options as this process.
+    command.env_remove("WATCHDOG_PID"); // The new process takes over the watchdog pings.
+    let listeners = server.listeners();
+    let child = activation::spawn_with_listeners(&mut command, &listeners)?;
+    info!("Started upgraded server process {}", child.id());
+    server.keep_socket_files(); // The new process accepts on them after this one stops.
+    if let Some(notifier) = server.notifier() {
+        // Make the new process the service's main process, so the manager ignores this one's STOPPING=1.
+        if let Err(e) = notifier.notify(&format!("MAINPID={}", child.id())) {
+            warn!("Failed to report the new main process to the service manager: {}", e);
+        }
+    }
+    Ok(())
+}
+
+// Handles process signals. SIGHUP reloads the configuration. SIGUSR2 hands the listeners to a new
+// process and drains this one. The first SIGINT/SIGTERM starts a graceful shutdown; a second one
+// exits immediately with the conventional 128 + signal number code.
+#[cfg(unix)]
+fn install_signal_handler(server: Arc<Server>, options: Options, listeners: Vec<ListenerConfig>) -> std::io::Result<()> {
+    let mut signals = Signals::new([SIGHUP, SIGUSR2, SIGINT, SIGTERM])?;
+    thread::Builder::new()
+        .name("signals".to_string())
+        .spawn(move || {
+            let mut stopping = false;
+            for signal in signals.forever() {
+                match signal {
+                    SIGHUP => reload(&server, &options, &listeners),
+                    SIGUSR2 if !stopping => match upgrade(&server) {
+                        Ok(()) => {
+                            info!("Draining connections before handing over to the new process");
+                            stopping = true;
+                            server.stop();
+                        }
+                        Err(e) => error!("Upgrade failed, continuing to serve: {}", e),
+                    },
+                    SIGUSR2 => warn!("Ignoring upgrade request during shutdown"),
+                    _ if !stopping => {
+                        info!("Received signal {}, shutting down gracefully", signal);
+                        stopping = true;
+                        server.stop();
+                    }
+                    _ => {
+                        warn!("Received signal {} during shutdown, exiting immediately", signal);
+                        process::exit(128 + signal);
+                    }
+                }
+            }
+        })?;
+    Ok(())
+}
+
+// Handles process signals where only SIGINT and SIGTERM exist, so reloads and upgrades are not
+// available. The first one starts a graceful shutdown; a second one exits immediately.
+#[cfg(not(unix))]
+fn install_signal_handler(server: Arc<Server>, _options: Options, _listeners: Vec<ListenerConfig>) -> std::io::Result<()> {
+    let received = Arc::new(AtomicUsize::new(0)); // Last signal received and not yet handled; 0 when none.
+    for signal in [SIGINT, SIGTERM] {
+        signal_hook::flag::register_usize(signal, Arc::clone(&received), signal as usize)?;
+    }
+    thread::Builder::new()
+        .name("signals".to_string())
+        .spawn(move || {
+            let mut stopping = false;
+            loop {
+                thread::sleep(Duration::from_millis(100));
+                match received.swap(0, Ordering::SeqCst) as i32 {
+                    0 => {}
+                    signal if !stopping => {
+                        info!("Received signal {}, shutting down gracefully", signal);
+                        stopping = true;
+                        server.stop();
+                    }
+                    signal => {
+                        warn!("Received signal {} during shutdown, exiting immediately", signal);
+                        process::exit(128 + signal);
+                    }
+                }
+            }
+        })?;
+    Ok(())
+}
+
+// Makes the server report readiness and health to the service manager that started it, if any.
+#[cfg(unix)]
+fn attach_notifier(server: &mut Server) {
+    match Notifier::from_env() {
+        Ok(Some(notifier)) => server.set_notifier(notifier),
+        Ok(None) => {}
+        Err(e) => warn!("Ignoring service manager notification settings: {}", e),
+    }
+}
+
+// Service manager notifications are only available on Unix platforms.
+#[cfg(not(unix))]
+fn attach_notifier(_server: &mut Server) {}
+
+// Returns the listeners inherited through socket activation, if any.
+#[cfg(unix)]
+fn inherited_listeners() -> std::io::Result<Option<Vec<Listener>>> {
+    activation::listeners_from_env()
+}
+
+// Socket activation is only available on Unix platforms.
+#[cfg(not(unix))]
+fn inherited_listeners() -> std::io::Result<Option<Vec<Listener>>> {
+    Ok(None)
+}
+
+fn main() {
+    // Initialize logging from RUST_LOG until the configuration is loaded
+    init_logging();
+
+    let options = match parse_args() {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{}", e);
+            eprintln!("usage: embedded-recruitment-task [--config PATH] [ADDR...]");
+            process::exit(EXIT_FAILURE);
+        }
+    };
+    let config = match load_config(&options) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("Failed to load configuration: {}", e);
+            process::exit(EXIT_FAILURE);
+        }
+    };
+    set_log_level(config.log_level);
+
+    // Create and run the server, on inherited sockets when started through socket activation
+    let server = match inherited_listeners() {
+        Ok(Some(listeners)) => {
+            info!("Using {} inherited listening socket(s) instead of the configured listeners", listeners.len());
+            Server::from_listeners(listeners, config.server.clone())
+        }
+        Ok(None) => Server::bind(&config.listeners, config.server.clone()),
+        Err(e) => Err(e),
+    };
+    let mut server = match server {
+        Ok(server) => server,
+        Err(e) => {
+            eprintln!("Failed to start server: {}", e);
+            process::exit(EXIT_FAILURE);
+        }
+    };
+
+    // Report readiness and health when run under a service manager
+    attach_notifier(&mut server);
+    let server = Arc::new(server);
+
+    let addresses: Vec<String> = match server.local_addrs() {
+        Ok(local_addrs) => local_addrs.iter().map(ToString::to_string).collect(),
+        Err(_) => config.listeners.iter().map(|listener| listener.address.to_string()).collect(),
+    };
+    if let Err(e) = install_signal_handler(Arc::clone(&server), options, config.listeners) {
+        eprintln!("Failed to install signal handlers: {}", e);
+        process::exit(EXIT_FAILURE);
+    }
+
+    println!("Server running on {} (pid {})", addresses.join(", "), process::id());
+
+    let code = match server.run() {
+        Ok(()) => EXIT_OK,
+        Err(e) => {
+            error!("Server did not shut down cleanly: {}", e);
+            eprintln!("Server encountered an error: {}", e);
+            EXIT_FAILURE
+        }
+    };
+    process::exit(code);
 }
@@ -0,0 +1,74 @@
+use crate::handler::{Context, HandlerResult, Handlers}; // Import request handlers and what they know.
+use crate::protocol::{Messages, Protocol}; // Import the schemas layers serve.
+use std::sync::Arc; // Layers shared with every connection.
+
+// Wraps request handling, in the style of tower layers. A layer sees each request with its
+// connection's context before the handler does, and the handler's reply after it. It can change
+// the request before passing it on with `next.run`, change or replace the reply, or answer with
+// an error without calling `next` at all. Layers are added with `Server::add_layer`; the first
+// one added is the outermost.
+pub trait Layer<P: Protocol = Messages>: Send + Sync {
+    // Handles `request`, usually by passing it to the rest of the chain with `next.run`.
+    fn handle(&self, request: P::Message, context: &Context, next: Next<'_, P>) -> HandlerResult<P>;
+}
+
+impl<P, F> Layer<P> for F
+where
+    P: Protocol,
+    F: Fn(P::Message, &Context, Next<'_, P>) -> HandlerResult<P> + Send + Sync,
+{
+    fn handle(&self, request: P::Message, context: &Context, next: Next<'_, P>) -> HandlerResult<P> {
+        self(request, context, next)
+    }
+}
+
+// The rest of a chain: the layers inside the current one, then the handler.
+pub struct Next<'a, P: Protocol = Messages> {
+    layers: &'a [Arc<dyn Layer<P>>], // Layers still to run, outermost first.
+    handlers: &'a Handlers<P>, // Handlers at the end of the chain.
+}
+
+impl<P: Protocol> Next<'_, P> {
+    // Passes `request` to the next layer, or to its handler after the innermost one.
+    pub fn run(self, request: P::Message, context: &Context) -> HandlerResult<P> {
+        match self.layers.split_first() {
+            Some((layer, layers)) => layer.handle(request, context, Next { layers, handlers: self.handlers }),
+            None => self.handlers.dispatch(request, context),
+        }
+    }
+}
+
+// The layers and handlers a request goes through.
+pub(crate) struct Chain<P: Protocol> {
+    layers: Vec<Arc<dyn Layer<P>>>, // Layers, outermost first.
+    handlers: Handlers<P>, // Handler for each message type.
+}
+
+impl<P: Protocol> Clone for Chain<P> {
+    fn clone(&self) -> Self {
+        Chain { layers: self.layers.clone(), handlers: self.handlers.clone() }
+    }
+}
+
+impl<P: Protocol> Default for Chain<P> {
+    fn default() -> Self {
+        Chain { layers: Vec::new(), handlers: Handlers::default() }
+    }
+}
+
+impl<P: Protocol> Chain<P> {
+    // Adds `layer` inside the layers added before it.
+    pub(crate) fn add_layer(&mut self, layer: impl Layer<P> + 'static) {
+        self.layers.push(Arc::new(layer));
+    }
+
+    // Returns the handlers at the end of the chain.
+    pub(crate) fn handlers_mut(&mut self) -> &mut Handlers<P> {
+        &mut self.handlers
+    }
+
+    // Computes the reply to a request through every layer and its handler.
+    pub(crate) fn dispatch(&self, request: P::Message, context: &Context) -> HandlerResult<P> {
+        Next { layers: &self.layers, handlers: &self.handlers }.run(request, context)
+    }
+}
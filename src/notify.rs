@@ -0,0 +1,91 @@
+use std::{
+    env, // The NOTIFY_SOCKET and WATCHDOG_* variables set by the service manager.
+    io::{self, ErrorKind}, // Error type for malformed notification settings.
+    os::unix::net::{SocketAddr, UnixDatagram}, // The datagram socket notifications are sent over.
+    path::Path, // Filesystem socket addresses.
+    time::Duration, // Watchdog intervals.
+};
+
+// Sends sd_notify state messages, such as READY=1, to a service manager.
+#[derive(Debug)]
+pub struct Notifier {
+    socket: UnixDatagram, // Unbound socket used to send notifications.
+    addr: SocketAddr, // Socket the service manager listens on.
+    watchdog: Option<Duration>, // Interval within which the manager expects a WATCHDOG=1 ping.
+}
+
+impl Notifier {
+    // Creates a notifier sending to the Unix datagram socket at `path`, with no watchdog.
+    pub fn new(path: impl AsRef<Path>) -> io::Result<Self> {
+        let addr = SocketAddr::from_pathname(path)?;
+        Ok(Notifier { socket: UnixDatagram::unbound()?, addr, watchdog: None })
+    }
+
+    // Returns a notifier for the socket named by NOTIFY_SOCKET, or None when the process is not
+    // supervised. Names starting with `@` refer to the Linux abstract namespace. WATCHDOG_USEC
+    // enables the watchdog unless WATCHDOG_PID names another process. The variables are kept so
+    // that an upgraded process started from this one can notify the same manager.
+    pub fn from_env() -> io::Result<Option<Self>> {
+        let path = match env::var("NOTIFY_SOCKET") {
+            Ok(path) if !path.is_empty() => path,
+            _ => return Ok(None),
+        };
+        let mut notifier = match path.strip_prefix('@') {
+            Some(name) => Self::abstract_name(name)?,
+            None => Self::new(&path)?,
+        };
+        notifier.watchdog = watchdog_from_env()?;
+        Ok(Some(notifier))
+    }
+
+    // Creates a notifier for a socket in the Linux abstract namespace.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    fn abstract_name(name: &str) -> io::Result<Self> {
+        #[cfg(target_os = "android")]
+        use std::os::android::net::SocketAddrExt;
+        #[cfg(target_os = "linux")]
+        use std::os::linux::net::SocketAddrExt;
+        let addr = SocketAddr::from_abstract_name(name)?;
+        Ok(Notifier { socket: UnixDatagram::unbound()?, addr, watchdog: None })
+    }
+
+    // The abstract namespace only exists on Linux.
+    #[cfg(not(any(target_os = "linux", target_os = "android")))]
+    fn abstract_name(name: &str) -> io::Result<Self> {
+        Err(io::Error::new(ErrorKind::Unsupported, format!("abstract socket @{} is not supported", name)))
+    }
+
+    // Sets the interval within which the manager expects watchdog pings; None disables them.
+    pub fn set_watchdog(&mut self, interval: Option<Duration>) {
+        self.watchdog = interval;
+    }
+
+    // Returns the watchdog interval, if the manager expects pings.
+    pub fn watchdog(&self) -> Option<Duration> {
+        self.watchdog
+    }
+
+    // Sends newline-separated `KEY=VALUE` assignments, such as "READY=1", in one datagram.
+    pub fn notify(&self, state: &str) -> io::Result<()> {
+        self.socket.send_to_addr(state.as_bytes(), &self.addr)?;
+        Ok(())
+    }
+}
+
+// Reads the watchdog interval from WATCHDOG_USEC, honouring WATCHDOG_PID when it is set.
+fn watchdog_from_env() -> io::Result<Option<Duration>> {
+    let usec = match env::var("WATCHDOG_USEC") {
+        Ok(usec) => usec,
+        Err(_) => return Ok(None),
+    };
+    if let Ok(pid) = env::var("WATCHDOG_PID") {
+        if pid.parse::<u32>().ok() != Some(std::process::id()) {
+            return Ok(None); // Meant for another process.
+        }
+    }
+    match usec.parse::<u64>() {
+        Ok(0) => Ok(None),
+        Ok(usec) => Ok(Some(Duration::from_micros(usec))),
+        Err(_) => Err(io::Error::new(ErrorKind::InvalidInput, format!("invalid WATCHDOG_USEC: {}", usec))),
+    }
+}
@@ -0,0 +1,279 @@
+use crate::codec::Codec; // Import framing for outbound messages.
+use crate::message::ServerMessage; // Import the message type delivered to clients.
+use crate::registry::{ConnectionId, ConnectionStats}; // Import connection tracking.
+use crate::stream::Stream; // Import connection handles.
+use log::{debug, error, warn}; // Import macros for structured logging.
+use prost::Message; // Import Protobuf support for encoding messages.
+use std::{
+    collections::VecDeque, // FIFO storage for queued messages.
+    fmt, // Formatting support for error types.
+    io::Write, // Import IO traits for stream handling.
+    net::Shutdown, // Closing connections.
+    sync::{Arc, Condvar, Mutex, MutexGuard}, // Synchronization primitives for the shared queue.
+    thread::{self, JoinHandle}, // Support for spawning the writer thread.
+    time::{Duration, Instant}, // Support for push deadlines.
+};
+
+// What to do when a server push finds a connection's outbound queue full.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SlowConsumerPolicy {
+    Drop, // Discard the pushed message.
+    Disconnect, // Close the connection.
+    Block(Duration), // Wait up to the given time for space, then discard the message.
+}
+
+// Reasons a message could not be queued for a connection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PushError {
+    UnknownConnection, // No live connection has the requested ID.
+    Closed, // The connection is shutting down.
+    Dropped, // The queue was full and the message was discarded.
+    TimedOut, // The queue stayed full for the whole blocking timeout.
+    Disconnected, // The queue was full and the connection was closed.
+}
+
+impl fmt::Display for PushError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let reason = match self {
+            PushError::UnknownConnection => "unknown connection",
+            PushError::Closed => "connection closed",
+            PushError::Dropped => "outbound queue full, message dropped",
+            PushError::TimedOut => "outbound queue full, timed out waiting for space",
+            PushError::Disconnected => "outbound queue full, slow consumer disconnected",
+        };
+        f.write_str(reason)
+    }
+}
+
+impl std::error::Error for PushError {}
+
+// A message waiting to be written.
+struct Queued<M> {
+    message: M, // Message to write.
+    reply: bool, // Whether it answers a request counted as in flight.
+}
+
+// Queue contents guarded by the mutex.
+struct State<M> {
+    messages: VecDeque<Queued<M>>, // Messages waiting to be written.
+    in_flight: usize, // Requests being handled or whose replies are not yet written.
+    closed: bool, // Set once the connection is closing; no further pushes are accepted.
+}
+
+// Bounded queue of messages waiting to be written to one connection.
+pub struct OutboundQueue<M = ServerMessage> {
+    state: Mutex<State<M>>, // Queue contents.
+    not_empty: Condvar, // Signalled when a message is queued or the queue closes.
+    not_full: Condvar, // Signalled when the writer takes a message or the queue closes.
+    request_done: Condvar, // Signalled when an in-flight request completes or the queue closes.
+    capacity: usize, // Queued messages beyond which pushes apply the policy; replies may exceed it.
+    policy: SlowConsumerPolicy, // Behaviour of `push` when the queue is full.
+    stream: Stream, // Handle used to disconnect slow consumers.
+}
+
+impl<M> OutboundQueue<M> {
+    // Creates an empty queue for the connection behind `stream`.
+    pub(crate) fn new(capacity: usize, policy: SlowConsumerPolicy, stream: Stream) -> Self {
+        OutboundQueue {
+            state: Mutex::new(State { messages: VecDeque::new(), in_flight: 0, closed: false }),
+            not_empty: Condvar::new(),
+            not_full: Condvar::new(),
+            request_done: Condvar::new(),
+            capacity: capacity.max(1),
+            policy,
+            stream,
+        }
+    }
+
+    // Queues an unsolicited message, applying the slow-consumer policy if the queue is full.
+    pub fn push(&self, message: M) -> Result<(), PushError> {
+        let mut state = self.lock();
+        if state.closed {
+            return Err(PushError::Closed);
+        }
+        if state.messages.len() >= self.capacity {
+            match self.policy {
+                SlowConsumerPolicy::Drop => return Err(PushError::Dropped),
+                SlowConsumerPolicy::Disconnect => {
+                    drop(state);
+                    warn!("Disconnecting slow consumer: outbound queue full");
+                    self.close();
+                    let _ = self.stream.shutdown(Shutdown::Both); // Wake the reader so the connection ends.
+                    return Err(PushError::Disconnected);
+                }
+                SlowConsumerPolicy::Block(timeout) => {
+                    state = self.wait_for_space(state, Instant::now() + timeout)?;
+                }
+            }
+        }
+        state.messages.push_back(Queued { message, reply: false });
+        self.not_empty.notify_one();
+        Ok(())
+    }
+
+    // Queues the reply to a request started with `begin_request`. Replies are already bounded by
+    // the in-flight limit, so they never wait for space and may exceed the capacity: a pool worker
+    // answering a client that stopped reading moves on at once. The request stays in flight until
+    // the writer has written the reply, which is what pauses the reader.
+    pub(crate) fn push_reply(&self, message: M) -> Result<(), PushError> {
+        let mut state = self.lock();
+        if state.closed {
+            return Err(PushError::Closed);
+        }
+        state.messages.push_back(Queued { message, reply: true });
+        self.not_empty.notify_one();
+        Ok(())
+    }
+
+    // Counts a new request as in flight, first waiting while `limit` requests already are.
+    // Returns how long the caller was throttled.
+    pub(crate) fn begin_request(&self, limit: usize) -> Result<Duration, PushError> {
+        let started = Instant::now();
+        let mut state = self.lock();
+        let throttled = state.in_flight >= limit;
+        while state.in_flight >= limit && !state.closed {
+            state = self
+                .request_done
+                .wait(state)
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+        }
+        if state.closed {
+            return Err(PushError::Closed);
+        }
+        state.in_flight += 1;
+        Ok(if throttled { started.elapsed() } else { Duration::ZERO })
+    }
+
+    // Completes an in-flight request, once its reply is written or when it has no reply.
+    pub(crate) fn finish_request(&self) {
+        let mut state = self.lock();
+        state.in_flight = state.in_flight.saturating_sub(1);
+        self.request_done.notify_one();
+    }
+
+    // Waits up to `timeout` for every in-flight request to complete, for example for requests
+    // handled on executor pools before the connection closes. Returns false if some are left.
+    pub(crate) fn wait_requests(&self, timeout: Duration) -> bool {
+        let deadline = Instant::now() + timeout;
+        let mut state = self.lock();
+        while state.in_flight > 0 && !state.closed {
+            let now = Instant::now();
+            if now >= deadline {
+                return false;
+            }
+            state = self
+                .request_done
+                .wait_timeout(state, deadline - now)
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                .0;
+        }
+        state.in_flight == 0
+    }
+
+    // Shuts down the read half of the connection, so its reader sees EOF once the requests it
+    // already read are answered.
+    pub(crate) fn shutdown_read(&self) {
+        let _ = self.stream.shutdown(Shutdown::Read);
+    }
+
+    // Returns the number of requests in flight.
+    pub fn in_flight(&self) -> usize {
+        self.lock().in_flight
+    }
+
+    // Returns the number of queued messages.
+    pub fn len(&self) -> usize {
+        self.lock().messages.len()
+    }
+
+    // Returns true when nothing is waiting to be written.
+    pub fn is_empty(&self) -> bool {
+        self.lock().messages.is_empty()
+    }
+
+    // Stops accepting messages. Messages already queued are still written.
+    pub(crate) fn close(&self) {
+        self.lock().closed = true;
+        self.not_empty.notify_all();
+        self.not_full.notify_all();
+        self.request_done.notify_all();
+    }
+
+    // Blocks until the queue has space, it closes, or the deadline passes.
+    fn wait_for_space<'a>(
+        &self,
+        mut state: MutexGuard<'a, State<M>>,
+        deadline: Instant,
+    ) -> Result<MutexGuard<'a, State<M>>, PushError> {
+        while state.messages.len() >= self.capacity && !state.closed {
+            let now = Instant::now();
+            if now >= deadline {
+                return Err(PushError::TimedOut);
+            }
+            state = self
+                .not_full
+                .wait_timeout(state, deadline - now)
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                .0;
+        }
+        if state.closed {
+            return Err(PushError::Closed);
+        }
+        Ok(state)
+    }
+
+    // Takes the next message to write, blocking until one is queued. Returns None once the
+    // queue is closed and drained.
+    fn pop(&self) -> Option<Queued<M>> {
+        let mut state = self.lock();
+        loop {
+            if let Some(message) = state.messages.pop_front() {
+                self.not_full.notify_one();
+                return Some(message);
+            }
+            if state.closed {
+                return None;
+            }
+            state = self
+                .not_empty
+                .wait(state)
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+        }
+    }
+
+    // Locks the queue, recovering from a poisoned lock.
+    fn lock(&self) -> MutexGuard<'_, State<M>> {
+        self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+// Spawns the thread that drains `queue` into `stream`, framed with `codec`, until the queue closes
+// or a write fails.
+pub(crate) fn spawn_writer<M: Message + Send + 'static>(
+    id: ConnectionId,
+    queue: Arc<OutboundQueue<M>>,
+    mut stream: Stream,
+    codec: Codec,
+    stats: Arc<ConnectionStats>,
+) -> std::io::Result<JoinHandle<()>> {
+    thread::Builder::new()
+        .name(format!("conn-{}-writer", id))
+        .spawn(move || {
+            while let Some(queued) = queue.pop() { // Wait for the next queued message.
+                let payload = codec.encode(&queued.message); // Frame the message for the wire.
+                if let Err(e) = stream.write_all(&payload).and_then(|_| stream.flush()) {
+                    error!("Error writing to client {}: {}", id, e); // Log the failed write.
+                    queue.close(); // Reject further pushes.
+                    let _ = stream.shutdown(Shutdown::Both); // Wake the reader so the connection ends.
+                    break;
+                }
+                stats.record_write(payload.len()); // Account for the outbound traffic.
+                if queued.reply {
+                    queue.finish_request(); // Let the reader take another request.
+                } else {
+                    stats.record_push(); // Delivered pushes keep the connection from idling out.
+                }
+            }
+            debug!("Writer for client {} finished.", id);
+        })
+}
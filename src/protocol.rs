@@ -0,0 +1,87 @@
+use crate::broker::Broker; // Import the topic broker behind the built-in publish/subscribe handlers.
+use crate::handler::HandlerError; // Import the errors reported to clients.
+use crate::message::{client_message, server_message, ClientMessage, ErrorResponse, ServerMessage}; // Import the default schema.
+use crate::router::MessageKind; // Import the request variants of the default schema.
+use crate::rooms::Rooms; // Import the chat rooms behind the built-in room handlers.
+use crate::server::ProtocolServer; // Import the server built-in handlers are registered on.
+use std::{
+    fmt, // Message types are named in logs and errors.
+    hash::Hash, // Message types key the handler and pool tables.
+    str::FromStr, // Message types are named in pool settings.
+    time::Duration, // Retry-after hints of overload errors.
+};
+
+// A request/response schema served by a `ProtocolServer`. Every inbound frame is decoded as a
+// `Request` envelope and every outbound frame encodes a `Response` envelope. Handlers and layers
+// work on the `Message` an envelope carries and answer with a `Reply`, and are registered by the
+// message's `Kind`, which pool settings name through `FromStr`.
+pub trait Protocol: Send + Sync + Sized + 'static {
+    type Request: prost::Message + Default; // Envelope decoded from each inbound frame.
+    type Response: prost::Message + Clone + 'static; // Envelope encoded into each outbound frame.
+    type Message: Send + 'static; // Request carried by an envelope, as handlers see it.
+    type Reply: Send + 'static; // Reply computed by handlers.
+    type Kind: Copy + Eq + Hash + fmt::Display + FromStr + Send + Sync + 'static; // Message types handlers and pools are registered for.
+
+    // Unwraps the request carried by an envelope. Envelopes without one are ignored.
+    fn open(request: Self::Request) -> Option<Self::Message>;
+
+    // Returns the type of a request.
+    fn kind(message: &Self::Message) -> Self::Kind;
+
+    // Wraps a handler's reply in an envelope.
+    fn reply(reply: Self::Reply) -> Self::Response;
+
+    // Builds the envelope reporting `error`, with a hint for when to retry for overload errors.
+    fn error(error: &HandlerError, retry_after: Option<Duration>) -> Self::Response;
+
+    // Registers the handlers and layers every new server for this protocol starts with. None by
+    // default, so requests are answered with `Unimplemented` until handlers are registered.
+    fn register_built_ins(_server: &mut ProtocolServer<Self>) {}
+}
+
+// The default protocol: `ClientMessage` requests and `ServerMessage` replies from messages.proto,
+// served by the built-in handlers.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Messages;
+
+impl Protocol for Messages {
+    type Request = ClientMessage;
+    type Response = ServerMessage;
+    type Message = client_message::Message;
+    type Reply = server_message::Message;
+    type Kind = MessageKind;
+
+    fn open(request: ClientMessage) -> Option<client_message::Message> {
+        request.message
+    }
+
+    fn kind(message: &client_message::Message) -> MessageKind {
+        MessageKind::of(message)
+    }
+
+    fn reply(reply: server_message::Message) -> ServerMessage {
+        ServerMessage { message: Some(reply) }
+    }
+
+    fn error(error: &HandlerError, retry_after: Option<Duration>) -> ServerMessage {
+        let error = ErrorResponse {
+            code: error.code.into(),
+            message: error.message.clone(),
+            retry_after_ms: retry_after.map_or(0, |retry_after| retry_after.as_millis().clamp(1, u32::MAX.into()) as u32),
+        };
+        ServerMessage { message: Some(server_message::Message::ErrorResponse(error)) }
+    }
+
+    fn register_built_ins(server: &mut ProtocolServer<Self>) {
+        server.register_router(crate::handler::BuiltIn);
+        let broker = Broker::new(std::sync::Arc::clone(server.registry()), server.config().subscriber_queue);
+        let rooms = Rooms::new(broker.clone());
+        server.insert_state(broker.clone()); // Found by the handlers through `Context::state`.
+        server.insert_state(rooms.clone());
+        server.on_disconnect(move |session, reason| {
+            rooms.leave_all(session.id()); // Tell the rooms it joined, before its mailbox goes away.
+            broker.disconnected(session.id(), reason); // Publish last wills.
+        });
+    }
+}
+
@@ -0,0 +1,255 @@
+use crate::hooks::DisconnectReason; // Import why connections close.
+use crate::message::ServerMessage; // Import the message type pushed to clients.
+use crate::outbound::{OutboundQueue, PushError}; // Import per-connection outbound queues.
+use crate::session::Session; // Import per-connection state.
+use crate::stream::{Endpoint, Stream}; // Import connection handles and addresses.
+use log::{info, warn}; // Import macros for structured logging.
+use std::{
+    collections::HashMap, // Map from connection ID to its registry entry.
+    io, // Errors cloning connection sockets.
+    net::Shutdown, // Closing connections.
+    sync::{
+        atomic::{AtomicU64, Ordering}, // Lock-free counters updated from connection threads.
+        Arc, Mutex, // Shared ownership and mutual exclusion for the connection table.
+    },
+    time::{Duration, SystemTime}, // Throttled time and the wall-clock time a connection was accepted.
+};
+
+// Unique identifier assigned to every accepted connection.
+pub type ConnectionId = u64;
+
+// Traffic counters for a single connection, updated by its handler thread.
+#[derive(Debug, Default)]
+pub struct ConnectionStats {
+    bytes_in: AtomicU64, // Total bytes read from the client.
+    bytes_out: AtomicU64, // Total bytes written to the client.
+    requests: AtomicU64, // Number of requests successfully decoded.
+    pushes: AtomicU64, // Number of pushes written to the client.
+    throttled_nanos: AtomicU64, // Time spent waiting for the in-flight limit, in nanoseconds.
+}
+
+impl ConnectionStats {
+    // Records bytes read from the client.
+    pub fn record_read(&self, bytes: usize) {
+        self.bytes_in.fetch_add(bytes as u64, Ordering::Relaxed);
+    }
+
+    // Records bytes written to the client.
+    pub fn record_write(&self, bytes: usize) {
+        self.bytes_out.fetch_add(bytes as u64, Ordering::Relaxed);
+    }
+
+    // Records a decoded request.
+    pub fn record_request(&self) {
+        self.requests.fetch_add(1, Ordering::Relaxed);
+    }
+
+    // Records a push written to the client.
+    pub fn record_push(&self) {
+        self.pushes.fetch_add(1, Ordering::Relaxed);
+    }
+
+    // Returns the number of pushes written to the client.
+    pub fn pushes(&self) -> u64 {
+        self.pushes.load(Ordering::Relaxed)
+    }
+
+    // Records time the reader spent waiting for the in-flight limit instead of reading.
+    pub fn record_throttled(&self, duration: Duration) {
+        self.throttled_nanos.fetch_add(duration.as_nanos() as u64, Ordering::Relaxed);
+    }
+}
+
+// Point-in-time view of a connection, returned to operators.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConnectionInfo {
+    pub id: ConnectionId, // Identifier used to address the connection.
+    pub peer_addr: Endpoint, // Remote address of the client.
+    pub connected_at: SystemTime, // Time the connection was accepted.
+    pub bytes_in: u64, // Bytes read from the client so far.
+    pub bytes_out: u64, // Bytes written to the client so far.
+    pub requests: u64, // Requests handled so far.
+    pub in_flight: usize, // Requests being handled or whose replies are not yet written.
+    pub throttled: Duration, // Time reading was paused because of the in-flight limit.
+}
+
+// Registry bookkeeping for a live connection.
+struct Entry<M> {
+    session: Arc<Session>, // State shared with the connection's handlers.
+    connected_at: SystemTime, // Time the connection was accepted.
+    stats: Arc<ConnectionStats>, // Counters shared with the handler thread.
+    stream: Stream, // Cloned handle used to force the connection closed.
+    outbound: Arc<OutboundQueue<M>>, // Queue drained by the connection's writer thread.
+}
+
+// Tracks every connection the server has accepted and not yet released.
+pub struct ConnectionRegistry<M = ServerMessage> {
+    next_id: AtomicU64, // Source of unique connection IDs.
+    connections: Mutex<HashMap<ConnectionId, Entry<M>>>, // Live connections keyed by ID.
+}
+
+impl<M> Default for ConnectionRegistry<M> {
+    fn default() -> Self {
+        ConnectionRegistry { next_id: AtomicU64::new(0), connections: Mutex::new(HashMap::new()) }
+    }
+}
+
+impl<M> ConnectionRegistry<M> {
+    // Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Records a newly accepted connection and returns its session and shared counters.
+    pub(crate) fn register(
+        &self,
+        stream: Stream,
+        peer_addr: Endpoint,
+        outbound: Arc<OutboundQueue<M>>,
+    ) -> (Arc<Session>, Arc<ConnectionStats>) {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed) + 1; // IDs start at 1.
+        let session = Arc::new(Session::new(id, peer_addr));
+        let stats = Arc::new(ConnectionStats::default());
+        let entry = Entry {
+            session: Arc::clone(&session),
+            connected_at: SystemTime::now(),
+            stats: Arc::clone(&stats),
+            stream,
+            outbound,
+        };
+        self.lock().insert(id, entry);
+        (session, stats)
+    }
+
+    // Removes a connection once its handler thread has finished.
+    pub(crate) fn unregister(&self, id: ConnectionId) {
+        self.lock().remove(&id);
+    }
+
+    // Returns a snapshot of every live connection, ordered by ID.
+    pub fn list(&self) -> Vec<ConnectionInfo> {
+        let mut connections: Vec<ConnectionInfo> = self
+            .lock()
+            .iter()
+            .map(|(id, entry)| entry.info(*id))
+            .collect();
+        connections.sort_by_key(|info| info.id);
+        connections
+    }
+
+    // Returns a snapshot of a single connection, if it is still live.
+    pub fn get(&self, id: ConnectionId) -> Option<ConnectionInfo> {
+        self.lock().get(&id).map(|entry| entry.info(id))
+    }
+
+    // Returns the session of a connection, if it is still live.
+    pub fn session(&self, id: ConnectionId) -> Option<Arc<Session>> {
+        self.lock().get(&id).map(|entry| Arc::clone(&entry.session))
+    }
+
+    // Returns another handle to the socket of a connection, for example to inspect its options, if
+    // it is still live.
+    pub fn stream(&self, id: ConnectionId) -> io::Result<Option<Stream>> {
+        self.lock().get(&id).map(|entry| entry.stream.try_clone()).transpose()
+    }
+
+    // Returns the number of live connections.
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    // Returns true when no connections are live.
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    // Pushes an unsolicited message to a connection, subject to its slow-consumer policy.
+    pub fn send(&self, id: ConnectionId, message: M) -> Result<(), PushError> {
+        let outbound = match self.lock().get(&id) {
+            Some(entry) => Arc::clone(&entry.outbound),
+            None => return Err(PushError::UnknownConnection),
+        };
+        outbound.push(message) // Push outside the lock; a blocking policy may wait for space.
+    }
+
+    // Pushes a message to every live connection. Returns the number of connections it was queued for.
+    pub fn broadcast(&self, message: &M) -> usize
+    where
+        M: Clone,
+    {
+        let queues: Vec<(ConnectionId, Arc<OutboundQueue<M>>)> = self
+            .lock()
+            .iter()
+            .map(|(id, entry)| (*id, Arc::clone(&entry.outbound)))
+            .collect();
+        queues
+            .into_iter()
+            .filter(|(id, outbound)| match outbound.push(message.clone()) {
+                Ok(()) => true,
+                Err(e) => {
+                    warn!("Broadcast to client {} failed: {}", id, e);
+                    false
+                }
+            })
+            .count()
+    }
+
+    // Forcibly closes a connection by ID. Returns false if no such connection exists.
+    pub fn disconnect(&self, id: ConnectionId) -> bool {
+        match self.lock().get(&id) {
+            Some(entry) => {
+                info!("Disconnecting client {} ({})", id, entry.session.peer_addr());
+                entry.session.closed_by(DisconnectReason::Kicked);
+                if let Err(e) = entry.stream.shutdown(Shutdown::Both) {
+                    // Shutting down unblocks the handler thread, which then unregisters itself.
+                    warn!("Error shutting down connection {}: {}", id, e);
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    // Stops reading from every connection so each handler finishes its queued replies and exits.
+    pub(crate) fn shutdown_reads(&self) {
+        for (id, entry) in self.lock().iter() {
+            if let Err(e) = entry.stream.shutdown(Shutdown::Read) {
+                warn!("Error shutting down reads on connection {}: {}", id, e);
+            }
+        }
+    }
+
+    // Forcibly closes every connection. Returns the number of connections closed.
+    pub fn disconnect_all(&self) -> usize {
+        let connections = self.lock();
+        for (id, entry) in connections.iter() {
+            if let Err(e) = entry.stream.shutdown(Shutdown::Both) {
+                warn!("Error shutting down connection {}: {}", id, e);
+            }
+        }
+        connections.len()
+    }
+
+    // Locks the connection table, recovering from a poisoned lock.
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<ConnectionId, Entry<M>>> {
+        self.connections
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl<M> Entry<M> {
+    // Builds an operator-facing snapshot of this entry.
+    fn info(&self, id: ConnectionId) -> ConnectionInfo {
+        ConnectionInfo {
+            id,
+            peer_addr: self.session.peer_addr().clone(),
+            connected_at: self.connected_at,
+            bytes_in: self.stats.bytes_in.load(Ordering::Relaxed),
+            bytes_out: self.stats.bytes_out.load(Ordering::Relaxed),
+            requests: self.stats.requests.load(Ordering::Relaxed),
+            in_flight: self.outbound.in_flight(),
+            throttled: Duration::from_nanos(self.stats.throttled_nanos.load(Ordering::Relaxed)),
+        }
+    }
+}
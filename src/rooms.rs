@@ -0,0 +1,131 @@
+use crate::broker::Broker; // Import the mailboxes room traffic is delivered through.
+use crate::message::{server_message, RoomEvent, RoomEventKind, RoomMember, RoomMessage}; // Import the messages pushed to members.
+use crate::registry::ConnectionId; // Import connection identifiers.
+use log::{debug, warn}; // Import macros for structured logging.
+use std::{
+    collections::{btree_map::Entry, BTreeMap, HashMap}, // Members by connection and rooms by name.
+    fmt, // Formatting support for the rooms.
+    sync::{Arc, Mutex, MutexGuard}, // Rooms shared by every connection.
+};
+
+// Members of one room, ordered by connection.
+type Members = BTreeMap<ConnectionId, RoomMember>;
+
+// Named chat rooms that connections join and leave. A `RoomMessage` sent to a room is pushed to
+// every other member, and members are told with a `RoomEvent` when someone joins or leaves.
+// Traffic is queued in the members' broker mailboxes while the rooms are locked, so every member
+// sees the messages and events of a room in the same order, and senders never wait for slow
+// members: a member whose mailbox is full misses the message. Cloning the rooms yields another
+// handle to the same rooms.
+#[derive(Clone)]
+pub struct Rooms {
+    broker: Broker, // Delivers pushes to members.
+    rooms: Arc<Mutex<HashMap<String, Members>>>, // Members by room; empty rooms are removed.
+}
+
+impl Rooms {
+    // Creates an empty set of rooms delivering through `broker`.
+    pub fn new(broker: Broker) -> Self {
+        Rooms { broker, rooms: Arc::new(Mutex::new(HashMap::new())) }
+    }
+
+    // Adds connection `id` to `room` under `identity`, creating the room if needed, and tells the
+    // other members. Returns the members after joining, including the new one.
+    pub fn join(&self, id: ConnectionId, identity: &str, room: &str) -> Vec<RoomMember> {
+        let mut rooms = self.lock();
+        let members = rooms.entry(room.to_string()).or_default();
+        if let Entry::Vacant(entry) = members.entry(id) {
+            let member = RoomMember { connection_id: id, identity: identity.to_string() };
+            entry.insert(member.clone());
+            debug!("Client {} joined room {}", id, room);
+            self.notify(room, members, member, RoomEventKind::Joined);
+        }
+        members.values().cloned().collect()
+    }
+
+    // Removes connection `id` from `room` and tells the remaining members. Returns false if it
+    // was not a member.
+    pub fn leave(&self, id: ConnectionId, room: &str) -> bool {
+        let mut rooms = self.lock();
+        let Some(members) = rooms.get_mut(room) else {
+            return false;
+        };
+        let Some(member) = members.remove(&id) else {
+            return false;
+        };
+        debug!("Client {} left room {}", id, room);
+        self.notify(room, members, member, RoomEventKind::Left);
+        if members.is_empty() {
+            rooms.remove(room);
+        }
+        true
+    }
+
+    // Removes connection `id` from every room it joined, as when it disconnects.
+    pub fn leave_all(&self, id: ConnectionId) {
+        for room in self.rooms_of(id) {
+            self.leave(id, &room);
+        }
+    }
+
+    // Pushes `content` from member `id` to the other members of `room`. Returns the number of
+    // members it was queued for, or None if `id` is not a member.
+    pub fn send(&self, id: ConnectionId, room: &str, content: &str) -> Option<usize> {
+        let rooms = self.lock();
+        let members = rooms.get(room)?;
+        let sender = members.get(&id)?.clone();
+        let message = RoomMessage { room: room.to_string(), sender: Some(sender), content: content.to_string() };
+        Some(self.push(members, id, server_message::Message::RoomMessage(message)))
+    }
+
+    // Returns the members of `room`, ordered by connection.
+    pub fn members(&self, room: &str) -> Vec<RoomMember> {
+        self.lock()
+            .get(room)
+            .map(|members| members.values().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    // Returns the rooms connection `id` has joined, in order.
+    pub fn rooms_of(&self, id: ConnectionId) -> Vec<String> {
+        let mut rooms: Vec<String> = self
+            .lock()
+            .iter()
+            .filter(|(_, members)| members.contains_key(&id))
+            .map(|(room, _)| room.clone())
+            .collect();
+        rooms.sort_unstable();
+        rooms
+    }
+
+    // Tells the members of `room` other than `member` that it joined or left.
+    fn notify(&self, room: &str, members: &Members, member: RoomMember, kind: RoomEventKind) {
+        let id = member.connection_id;
+        let event = RoomEvent { room: room.to_string(), member: Some(member), kind: kind.into() };
+        self.push(members, id, server_message::Message::RoomEvent(event));
+    }
+
+    // Queues `message` for every member except `from`. Returns the number of members it was
+    // queued for.
+    fn push(&self, members: &Members, from: ConnectionId, message: server_message::Message) -> usize {
+        let mut queued = 0;
+        for id in members.keys().copied().filter(|id| *id != from) {
+            match self.broker.deliver(id, message.clone()) {
+                Ok(delivered) => queued += usize::from(delivered),
+                Err(e) => warn!("Cannot deliver room traffic to client {}: {}", id, e),
+            }
+        }
+        queued
+    }
+
+    // Locks the rooms, recovering from a poisoned lock.
+    fn lock(&self) -> MutexGuard<'_, HashMap<String, Members>> {
+        self.rooms.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl fmt::Debug for Rooms {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Rooms").field("rooms", &self.lock().len()).finish()
+    }
+}
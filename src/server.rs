@@ -1,110 +1,574 @@
-use crate::message::{AddRequest, EchoMessage}; // Import custom message structures for decoding and encoding client messages.
+use crate::broker::Broker; // Import the topic broker.
+use crate::codec::{Codec, FrameReader}; // Import framing for requests and replies.
+use crate::config::{ListenerConfig, PanicPolicy, ServerConfig, Timeouts}; // Import server settings.
+use crate::executor::{Executors, PoolStats}; // Import executor pools for assigned message types.
+use crate::handler::{Context, ErrorCode, Handler, HandlerError}; // Import request handlers and the codes of their errors.
+use crate::hooks::{DisconnectReason, Hooks}; // Import connection lifecycle hooks.
+use crate::listener::{self, Listener}; // Import listener binding helpers.
+#[cfg(unix)]
+use crate::listener::SocketFile; // Import cleanup of bound Unix socket files.
+use crate::middleware::{Chain, Layer}; // Import the layers wrapping request handling.
+#[cfg(unix)]
+use crate::notify::Notifier; // Import service manager notifications.
+use crate::outbound::{self, OutboundQueue, PushError}; // Import per-connection outbound queues.
+use crate::registry::{ConnectionId, ConnectionInfo, ConnectionRegistry, ConnectionStats}; // Import connection tracking.
+use crate::rooms::Rooms; // Import the chat rooms.
+use crate::protocol::{Messages, Protocol}; // Import the schemas the server can serve.
+use crate::router::Router; // Import the generated request routing.
+use crate::session::Session; // Import per-connection state.
+use crate::shedding::{CoDel, LoadShedder, NoShedding}; // Import load shedding policies.
+use crate::socket::SocketOptions; // Import socket options applied to accepted streams.
+use crate::state::AppState; // Import state shared by every handler.
+use crate::stream::{Endpoint, Stream}; // Import connection handles and addresses.
+use crate::tls::TlsConfig; // Import the security of each endpoint.
 use log::{error, info, warn}; // Import macros for structured logging.
 use prost::Message; // Import Protobuf support for encoding and decoding messages.
 use std::{
-    io::{self, ErrorKind, Read, Write}, // Import IO traits for stream handling.
-    net::{TcpListener, TcpStream}, // Import network primitives for TCP communication.
+    any::Any, // Payloads of caught panics.
+    io::{self, ErrorKind, Read}, // Import IO traits for stream handling.
+    net::{Shutdown, SocketAddr}, // Import network primitives for closing connections and reporting addresses.
+    panic::{self, AssertUnwindSafe}, // Isolation of panicking request handlers.
     sync::{
-        atomic::{AtomicBool, Ordering}, // Atomic types for thread-safe shared state.
-        Arc, // Atomic Reference Counter for shared ownership.
+        atomic::{AtomicBool, AtomicU64, Ordering}, // Atomic types for thread-safe shared state.
+        Arc, RwLock, RwLockReadGuard, // Shared ownership and reloadable settings.
     },
-    thread, // Support for spawning threads.
-    time::Duration, // Support for specifying time intervals.
+    thread::{self, JoinHandle, Scope}, // Support for spawning threads.
+    time::{Duration, Instant}, // Support for specifying time intervals and deadlines.
 };
 
+// Server-wide counters shared with every connection thread.
+#[derive(Default)]
+struct Metrics {
+    panics: AtomicU64, // Request handlers that panicked.
+    throttled_nanos: AtomicU64, // Time connections spent paused at their in-flight limit, in nanoseconds.
+    shed: AtomicU64, // Requests rejected by the load shedder.
+}
+
 // Represents a single connected client.
-struct Client {
-    stream: TcpStream, // TCP stream for communication with the client.
+struct Client<P: Protocol> {
+    id: ConnectionId, // Registry ID of this connection.
+    stream: Stream, // Stream the client's requests are read from.
+    responder: Responder<P>, // Answers requests through the outbound queue, drained by the writer thread.
+    frames: FrameReader, // Reassembles requests split across reads.
+    stats: Arc<ConnectionStats>, // Traffic counters shared with the registry.
+    executors: Arc<Executors<P::Kind>>, // Pools that handle assigned message types off this thread.
+    hooks: Arc<Hooks>, // Lifecycle hooks, for the handshake.
+    max_in_flight: usize, // Requests handled or awaiting their reply before reading pauses.
+    endpoint_timeouts: Option<Timeouts>, // Timeouts of the endpoint it was accepted on; the server-wide ones when None.
+    timeouts: Timeouts, // Timeouts applied to this connection.
+    connected_at: Instant, // Time the connection was accepted.
+    last_activity: Instant, // Time of the last inbound data or delivered push.
+    pushes_seen: u64, // Pushes delivered as of the last idle check.
+    handshake_complete: bool, // Whether the client has sent its first request.
 }
 
-impl Client {
-    // Creates a new Client instance, setting a read timeout for the TCP stream.
-    pub fn new(stream: TcpStream) -> io::Result<Self> {
-        stream.set_read_timeout(Some(Duration::from_secs(10)))?; // Set a 10-second timeout for read operations.
-        Ok(Client { stream })
+impl<P: Protocol> Client<P> {
+    // Creates a new Client instance reading from `stream` and replying through `responder`.
+    pub fn new(
+        stream: Stream,
+        responder: Responder<P>,
+        stats: Arc<ConnectionStats>,
+        executors: Arc<Executors<P::Kind>>,
+        hooks: Arc<Hooks>,
+        frames: FrameReader,
+        endpoint_timeouts: Option<Timeouts>,
+    ) -> io::Result<Self> {
+        let (timeouts, max_in_flight) = {
+            let config = read_config(&responder.config);
+            (endpoint_timeouts.unwrap_or(config.timeouts), config.max_in_flight)
+        };
+        let now = Instant::now();
+        Ok(Client {
+            id: responder.id,
+            stream,
+            responder,
+            frames,
+            stats,
+            executors,
+            hooks,
+            max_in_flight,
+            endpoint_timeouts,
+            timeouts,
+            connected_at: now,
+            last_activity: now,
+            pushes_seen: 0,
+            handshake_complete: false,
+        })
     }
 
-    // Handles communication with the client.
-    pub fn handle(&mut self) -> io::Result<()> {
-        let mut buffer = [0; 512]; // Buffer to store incoming data.
+    // Returns the deadline after which the connection is closed for inactivity, if any.
+    fn deadline(&self) -> Option<(Instant, &'static str)> {
+        if !self.handshake_complete {
+            if let Some(handshake) = self.timeouts.handshake {
+                return Some((self.connected_at + handshake, "handshake"));
+            }
+        }
+        self.timeouts
+            .idle
+            .map(|idle| (self.last_activity + idle, "idle"))
+    }
+
+    // Handles communication with the client. Returns why the connection closed once it has.
+    pub fn handle(&mut self) -> io::Result<Option<DisconnectReason>> {
+        let mut buffer = [0; 4096]; // Buffer to store incoming data.
+
+        self.timeouts = self.endpoint_timeouts.unwrap_or_else(|| read_config(&self.responder.config).timeouts); // Pick up reloaded timeouts.
+        let pushes = self.stats.pushes();
+        if pushes != self.pushes_seen { // Pushes delivered since the last check count as activity.
+            self.pushes_seen = pushes;
+            self.last_activity = Instant::now();
+        }
+        let mut read_timeout = self.timeouts.read; // Wait no longer than the read timeout...
+        if let Some((deadline, reason)) = self.deadline() {
+            let now = Instant::now();
+            if now >= deadline { // ...and close the connection once its deadline has passed.
+                info!("Client {} exceeded the {} timeout, closing connection.", self.id, reason);
+                self.stream.shutdown(Shutdown::Both)?; // Close cleanly so the client sees EOF.
+                return Ok(Some(DisconnectReason::Timeout));
+            }
+            read_timeout = read_timeout.min(deadline - now); // Wake up in time to enforce the deadline.
+        }
+        self.stream.set_read_timeout(Some(read_timeout.max(Duration::from_millis(1))))?;
 
         match self.stream.read(&mut buffer) { // Read data from the TCP stream.
             Ok(0) => { // Client has disconnected.
-                info!("Client disconnected.");
-                return Ok(());
+                if self.frames.has_partial_frame() {
+                    warn!("Client {} disconnected in the middle of a request; discarding the partial frame.", self.id);
+                }
+                info!("Client {} disconnected.", self.id);
+                return Ok(Some(DisconnectReason::Eof));
             }
             Ok(bytes_read) => { // Successfully read data from the client.
-                if let Ok(echo_message) = EchoMessage::decode(&buffer[..bytes_read]) { // Try decoding an EchoMessage.
-                    info!("Received EchoMessage: {}", echo_message.content); // Log the message content.
-                    let payload = echo_message.encode_to_vec(); // Encode the message to send back.
-                    self.stream.write_all(&payload)?; // Send the message back to the client (echo).
-                    self.stream.flush()?; // Ensure all data is sent.
-                } else if let Ok(add_request) = AddRequest::decode(&buffer[..bytes_read]) { // Try decoding an AddRequest.
-                    info!("Received AddRequest: a = {}, b = {}", add_request.a, add_request.b); // Log the numbers to add.
-                    let sum = add_request.a + add_request.b; // Compute the sum.
-
-                    let response = AddRequest { a: sum, b: 0 }; // Reuse the AddRequest structure for the response.
-                    let payload = response.encode_to_vec(); // Encode the sum as a response.
-                    self.stream.write_all(&payload)?; // Send the response back to the client.
-                    self.stream.flush()?; // Ensure all data is sent.
-                } else {
-                    warn!("Received invalid or unknown message format"); // Log an error if the message format is unrecognized.
+                self.stats.record_read(bytes_read); // Account for the inbound traffic.
+                self.last_activity = Instant::now(); // Reset the idle timer.
+                self.frames.extend(&buffer[..bytes_read]); // Queue the bytes for reassembly.
+                while let Some(frame) = self.frames.next_frame()? { // Handle every complete request.
+                    match P::Request::decode(frame.as_slice()) { // Decode the client envelope.
+                        Ok(request) => {
+                            self.stats.record_request(); // Count the decoded request.
+                            if !self.handshake_complete {
+                                self.handshake_complete = true; // The first request completes the handshake.
+                                self.hooks.handshake_completed(self.responder.context.session());
+                            }
+                            let admitted = self.responder.shedder.admit(); // Decide on arrival, before any waiting.
+                            match self.responder.outbound.begin_request(self.max_in_flight) { // Stop reading while too many requests are in flight.
+                                Ok(throttled) if !throttled.is_zero() => {
+                                    self.stats.record_throttled(throttled);
+                                    self.responder.metrics.throttled_nanos.fetch_add(throttled.as_nanos() as u64, Ordering::Relaxed);
+                                }
+                                Ok(_) => {}
+                                Err(_) => return Ok(Some(DisconnectReason::Error)), // The writer has shut the connection down.
+                            }
+                            let received = Instant::now(); // Queue delay is measured from here, not counting the throttle.
+                            if let Err(retry_after) = admitted {
+                                if self.responder.shed(retry_after).is_err() { // Reject without handling it.
+                                    return Ok(Some(DisconnectReason::Error)); // The writer has shut the connection down.
+                                }
+                                continue;
+                            }
+                            let request = P::open(request); // Empty envelopes carry no request.
+                            let kind = request.as_ref().map(P::kind);
+                            match kind.and_then(|kind| self.executors.route(kind)) {
+                                Some(pool) => { // Hand the request to its pool; the worker queues the reply.
+                                    let responder = self.responder.clone();
+                                    let submitted = pool.submit(move || {
+                                        if let Ok(false) = responder.respond(request, received) {
+                                            info!("Closing client {} after a handler panic.", responder.id);
+                                            responder.context.session().closed_by(DisconnectReason::Error);
+                                            responder.outbound.shutdown_read(); // Stop reading; queued replies are still written.
+                                        }
+                                    });
+                                    if let Err(e) = submitted {
+                                        warn!("Rejecting request from client {}: pool {}: {}", self.id, pool.name(), e);
+                                        if self.responder.outbound.push_reply(overloaded_response::<P>(None)).is_err() {
+                                            return Ok(Some(DisconnectReason::Error)); // The writer has shut the connection down.
+                                        }
+                                    }
+                                }
+                                None => match self.responder.respond(request, received) { // Handle the request on this thread.
+                                    Ok(true) => {}
+                                    Ok(false) => {
+                                        info!("Closing client {} after a handler panic.", self.id);
+                                        return Ok(Some(DisconnectReason::Error)); // The writer still delivers the error response.
+                                    }
+                                    Err(_) => return Ok(Some(DisconnectReason::Error)), // The writer has shut the connection down.
+                                },
+                            }
+                        }
+                        Err(e) => {
+                            warn!("Received invalid or unknown message format: {}", e); // Log an error if the message format is unrecognized.
+                        }
+                    }
                 }
             }
-            Err(e) if e.kind() == ErrorKind::WouldBlock => { // Handle non-blocking read timeout.
-                thread::sleep(Duration::from_millis(100)); // Sleep briefly before retrying.
+            Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => { // Handle read timeout.
+                // Nothing arrived; the next call re-checks the deadlines and server state.
             }
             Err(e) => { // Handle other read errors.
                 error!("Error reading from client stream: {}", e); // Log the error.
+                return Err(e);
             }
         }
 
-        Ok(())
+        Ok(None)
+    }
+}
+
+// Handles the in-flight requests of a connection and queues their replies, on the connection
+// thread or a pool worker.
+struct Responder<P: Protocol> {
+    id: ConnectionId, // Registry ID of the connection.
+    outbound: Arc<OutboundQueue<P::Response>>, // Queue for the reply.
+    config: Arc<RwLock<ServerConfig>>, // Live settings, for the panic policy.
+    metrics: Arc<Metrics>, // Server-wide counters.
+    shedder: Arc<dyn LoadShedder>, // Rejects requests while the server is overloaded.
+    chain: Arc<Chain<P>>, // Layers and handlers that compute replies.
+    context: Context, // What handlers know about the connection.
+}
+
+impl<P: Protocol> Clone for Responder<P> {
+    fn clone(&self) -> Self {
+        Responder {
+            id: self.id,
+            outbound: Arc::clone(&self.outbound),
+            config: Arc::clone(&self.config),
+            metrics: Arc::clone(&self.metrics),
+            shedder: Arc::clone(&self.shedder),
+            chain: Arc::clone(&self.chain),
+            context: self.context.clone(),
+        }
     }
 }
 
-// Represents the server that listens for and manages client connections.
-pub struct Server {
-    listener: TcpListener, // TCP listener to accept incoming connections.
+impl<P: Protocol> Responder<P> {
+    // Dispatches a request counted as in flight and received at `received`, and hands its reply
+    // to the writer. Returns whether the connection stays open, as decided by the panic policy, or
+    // an error if the writer has shut the connection down.
+    fn respond(&self, request: Option<P::Message>, received: Instant) -> Result<bool, PushError> {
+        if let Err(retry_after) = self.shedder.start(received.elapsed()) {
+            return self.shed(retry_after).map(|_| true); // Waited too long to be worth handling.
+        }
+        let started = Instant::now();
+        let (response, keep_open) = self.dispatch_isolated(request); // Compute the reply, if any.
+        self.shedder.finish(started.elapsed());
+        match response {
+            Some(response) => self.outbound.push_reply(response)?,
+            None => self.outbound.finish_request(), // Nothing to wait for.
+        }
+        Ok(keep_open)
+    }
+
+    // Answers a request counted as in flight with an `Overloaded` error instead of handling it.
+    fn shed(&self, retry_after: Duration) -> Result<(), PushError> {
+        self.metrics.shed.fetch_add(1, Ordering::Relaxed); // Count the rejection for operators.
+        self.outbound.push_reply(overloaded_response::<P>(Some(retry_after)))
+    }
+
+    // Dispatches a request through the layers to its handler, turning an error into an error
+    // response and a panic into an `Internal` one. Returns the reply and whether the connection
+    // stays open, as decided by the panic policy.
+    fn dispatch_isolated(&self, request: Option<P::Message>) -> (Option<P::Response>, bool) {
+        let Some(message) = request else {
+            return (None, true); // Ignore empty envelopes.
+        };
+        match panic::catch_unwind(AssertUnwindSafe(|| self.chain.dispatch(message, &self.context))) {
+            Ok(Ok(response)) => (response.map(P::reply), true),
+            Ok(Err(e)) => {
+                warn!("Request from client {} failed: {}", self.id, e);
+                (Some(P::error(&e, None)), true)
+            }
+            Err(payload) => {
+                self.metrics.panics.fetch_add(1, Ordering::Relaxed); // Count the panic for operators.
+                error!("Handler panicked while serving client {}: {}", self.id, panic_message(payload.as_ref()));
+                let keep_open = read_config(&self.config).on_panic == PanicPolicy::KeepOpen;
+                // Panic details stay in the server log.
+                (Some(P::error(&HandlerError::new(ErrorCode::Internal, "internal error"), None)), keep_open)
+            }
+        }
+    }
+}
+
+// Builds the reply to a rejected request, with a hint for when to retry if there is one.
+fn overloaded_response<P: Protocol>(retry_after: Option<Duration>) -> P::Response {
+    P::error(&HandlerError::new(ErrorCode::Overloaded, "server overloaded"), retry_after)
+}
+
+// Extracts the message from a panic payload.
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message
+    } else {
+        "non-string panic payload"
+    }
+}
+
+// Locks the shared settings for reading, recovering from a poisoned lock.
+fn read_config(config: &RwLock<ServerConfig>) -> RwLockReadGuard<'_, ServerConfig> {
+    config.read().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+// A listening socket served by one acceptor thread, with its endpoint's overrides.
+struct Acceptor {
+    listener: Listener, // Socket connections are accepted from.
+    socket: Option<SocketOptions>, // Options for accepted streams; the server-wide ones when None.
+    max_frame_len: Option<usize>, // Largest request accepted; the server-wide limit when None.
+    timeouts: Option<Timeouts>, // Connection timeouts; the server-wide ones when None.
+    codec: Codec, // Framing of requests and replies.
+    tls: Option<TlsConfig>, // Secures connections before they are registered; plaintext when None.
+    #[cfg(unix)]
+    socket_file: Option<Arc<SocketFile>>, // Unix socket file to remove once the server stops; None when not bound here.
+}
+
+// Represents the server that listens for and manages client connections speaking protocol `P`.
+// Downstream crates serve their own schema with `ProtocolServer<TheirProtocol>`.
+pub struct ProtocolServer<P: Protocol> {
+    acceptors: Vec<Acceptor>, // Listeners to accept incoming connections, one per acceptor thread.
+    accepted: Vec<AtomicU64>, // Connections accepted by each acceptor.
+    heartbeats: Vec<AtomicU64>, // Accept loop iterations per acceptor, checked by the watchdog.
     is_running: Arc<AtomicBool>, // Shared state indicating if the server is running.
+    registry: Arc<ConnectionRegistry<P::Response>>, // Live connections accepted by this server.
+    config: Arc<RwLock<ServerConfig>>, // Settings applied to accepted connections; reloadable.
+    metrics: Arc<Metrics>, // Counters across all connections.
+    executors: Arc<Executors<P::Kind>>, // Pools for message types assigned to them.
+    shedder: Arc<dyn LoadShedder>, // Rejects requests while the server is overloaded.
+    chain: Arc<Chain<P>>, // Layers and the handler for each message type.
+    state: Arc<AppState>, // Application state passed to every handler.
+    hooks: Arc<Hooks>, // Connection lifecycle hooks.
+    #[cfg(unix)]
+    notifier: Option<Notifier>, // Service manager to report readiness and health to.
 }
 
-impl Server {
-    // Creates a new Server instance bound to the specified address.
+// The server for the default protocol, `ClientMessage` and `ServerMessage` from messages.proto.
+pub type Server = ProtocolServer<Messages>;
+
+impl<P: Protocol> ProtocolServer<P> {
+    // Creates a new Server instance bound to the specified address, using the default settings.
     pub fn new(addr: &str) -> io::Result<Self> {
-        let listener = TcpListener::bind(addr)?; // Bind the listener to the address.
-        let is_running = Arc::new(AtomicBool::new(false)); // Initialize the server state.
-        Ok(Server { listener, is_running })
+        Self::with_config(addr, ServerConfig::default())
+    }
+
+    // Creates a new Server instance bound to the specified address with the given settings. The
+    // address is `host:port`, or `unix:<path>` for a Unix domain socket.
+    pub fn with_config(addr: &str, config: ServerConfig) -> io::Result<Self> {
+        Self::bind(&[ListenerConfig::new(addr.parse()?)], config)
+    }
+
+    // Creates a new Server instance listening on every endpoint in `listeners`. All endpoints share
+    // the connection registry, settings and shutdown; each may override the acceptor count, socket
+    // options, request size limit and connection timeouts, and chooses its codec and TLS settings.
+    pub fn bind(listeners: &[ListenerConfig], config: ServerConfig) -> io::Result<Self> {
+        config.validate()?; // Reject unusable settings before binding.
+        for listener in listeners {
+            listener.validate()?;
+        }
+        let endpoints: Vec<_> = listeners
+            .iter()
+            .map(|listener| {
+                let count = listener.acceptors.unwrap_or(config.acceptors);
+                (listener.address.clone(), count, listener.socket.unwrap_or(config.socket))
+            })
+            .collect();
+        let bound = listener::bind_all(&endpoints)?; // Bind one listener per acceptor.
+        let acceptors = listeners
+            .iter()
+            .zip(bound)
+            .flat_map(|(settings, bound)| {
+                #[cfg(unix)]
+                let socket_file = bound.socket_file;
+                bound.listeners.into_iter().map(move |listener| Acceptor {
+                    listener,
+                    socket: settings.socket,
+                    max_frame_len: settings.max_frame_len,
+                    timeouts: settings.timeouts,
+                    codec: settings.codec,
+                    tls: settings.tls.clone(),
+                    #[cfg(unix)]
+                    socket_file: socket_file.clone(),
+                })
+            })
+            .collect();
+        Self::from_acceptors(acceptors, config)
     }
 
-    // Runs the server, accepting and handling client connections.
+    // Creates a new Server instance that accepts on already-bound listeners, such as sockets
+    // inherited through socket activation. Each listener gets its own acceptor thread and the
+    // server-wide settings, and speaks the default codec without TLS. Unix socket files of
+    // inherited listeners belong to whoever bound them and are never removed.
+    pub fn from_listeners(listeners: Vec<Listener>, config: ServerConfig) -> io::Result<Self> {
+        config.validate()?; // Reject unusable settings before accepting.
+        let acceptors = listeners
+            .into_iter()
+            .map(|listener| Acceptor {
+                listener,
+                socket: None,
+                max_frame_len: None,
+                timeouts: None,
+                codec: Codec::default(),
+                tls: None,
+                #[cfg(unix)]
+                socket_file: None,
+            })
+            .collect();
+        Self::from_acceptors(acceptors, config)
+    }
+
+    // Creates a new Server instance around bound acceptors.
+    fn from_acceptors(acceptors: Vec<Acceptor>, config: ServerConfig) -> io::Result<Self> {
+        if acceptors.is_empty() {
+            return Err(io::Error::new(ErrorKind::InvalidInput, "at least one listener is required"));
+        }
+        let accepted = acceptors.iter().map(|_| AtomicU64::new(0)).collect();
+        let heartbeats = acceptors.iter().map(|_| AtomicU64::new(0)).collect();
+        let is_running = Arc::new(AtomicBool::new(true)); // Running until stopped, so a `stop` issued before `run` is not lost.
+        let registry = Arc::new(ConnectionRegistry::new()); // Start with no tracked connections.
+        let executors = Arc::new(Executors::new(&config.pools)?); // Start the executor pools.
+        let shedder: Arc<dyn LoadShedder> = match config.load_shedding {
+            Some(shedding) => Arc::new(CoDel::new(shedding)),
+            None => Arc::new(NoShedding),
+        };
+        let config = Arc::new(RwLock::new(config)); // Share the settings with connection threads.
+        let mut server = ProtocolServer {
+            acceptors,
+            accepted,
+            heartbeats,
+            is_running,
+            registry,
+            config,
+            metrics: Arc::new(Metrics::default()),
+            executors,
+            shedder,
+            chain: Arc::new(Chain::default()),
+            state: Arc::new(AppState::new()),
+            hooks: Arc::new(Hooks::default()),
+            #[cfg(unix)]
+            notifier: None,
+        };
+        P::register_built_ins(&mut server); // Start with the protocol's built-in handlers.
+        Ok(server)
+    }
+
+    // Runs the server on a new thread and returns a handle for stopping and waiting for it.
+    // Bind to port 0 and read the chosen port from `ServerHandle::local_addr`.
+    pub fn start(self) -> io::Result<ServerHandle<P>> {
+        let server = Arc::new(self);
+        let runner = Arc::clone(&server);
+        let thread = thread::Builder::new()
+            .name("server".to_string())
+            .spawn(move || runner.run())?;
+        Ok(ServerHandle { server, thread })
+    }
+
+    // Runs the server, accepting and handling client connections until it is stopped.
     pub fn run(&self) -> io::Result<()> {
-        self.is_running.store(true, Ordering::SeqCst); // Set the server state to running.
-        info!("Server is running on {}", self.listener.local_addr()?); // Log the server address.
+        let addrs: Vec<String> = self.local_addrs()?.iter().map(Endpoint::to_string).collect();
+        info!("Server is running on {} with {} acceptor(s)", addrs.join(", "), self.acceptors.len()); // Log the server addresses.
+
+        for acceptor in &self.acceptors {
+            acceptor.listener.set_nonblocking(true)?; // Set the listeners to non-blocking mode.
+        }
+
+        thread::scope(|scope| { // Run one accept loop per listener; all of them end when the server stops.
+            for (index, acceptor) in self.acceptors.iter().enumerate() {
+                let spawned = thread::Builder::new()
+                    .name(format!("acceptor-{}", index))
+                    .spawn_scoped(scope, move || self.accept_loop(scope, index, acceptor));
+                if let Err(e) = spawned {
+                    error!("Failed to start acceptor {}: {}", index, e); // Keep serving on the remaining acceptors.
+                }
+            }
+            self.notify("READY=1"); // The listeners are bound and being accepted from.
+            if let Some(interval) = self.watchdog_interval() {
+                let spawned = thread::Builder::new()
+                    .name("watchdog".to_string())
+                    .spawn_scoped(scope, move || self.watchdog_loop(interval));
+                if let Err(e) = spawned {
+                    error!("Failed to start the watchdog: {}", e); // The manager will restart an unresponsive server.
+                }
+            }
+        });
+
+        self.remove_socket_files(); // Nothing accepts on them any more.
+        self.notify("STOPPING=1"); // Report the shutdown while connections drain.
+        let drained = self.drain(); // Wait for in-flight connections to finish.
+        info!("Server stopped."); // Log server shutdown.
+        self.hooks.server_stopped();
+        drained
+    }
+
+    // Waits up to the shutdown timeout for every connection to close, then forcibly closes the rest.
+    // Returns an error if any connection had to be forced.
+    fn drain(&self) -> io::Result<()> {
+        let deadline = Instant::now() + read_config(&self.config).timeouts.shutdown;
+        while !self.registry.is_empty() && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(10)); // Poll until the connection threads unregister.
+        }
+        if self.registry.is_empty() {
+            return Ok(());
+        }
+
+        let remaining = self.registry.disconnect_all(); // Give up on a clean drain.
+        warn!("{} connection(s) did not close within the shutdown timeout", remaining);
+        Err(io::Error::new(
+            ErrorKind::TimedOut,
+            format!("{} connection(s) did not close within the shutdown timeout", remaining),
+        ))
+    }
+
+    // Sends a WATCHDOG=1 ping every half `interval` while every acceptor keeps polling. Pings are
+    // withheld while an acceptor is stalled, so the service manager restarts a server that stopped
+    // accepting. Intervals shorter than the accept poll period read as stalls.
+    fn watchdog_loop(&self, interval: Duration) {
+        let period = interval / 2;
+        let mut last = self.heartbeats();
+        while self.is_running() {
+            let deadline = Instant::now() + period;
+            while self.is_running() && Instant::now() < deadline {
+                thread::sleep(period.min(Duration::from_millis(50))); // Exit promptly once stopped.
+            }
+            if !self.is_running() {
+                break;
+            }
+            let current = self.heartbeats();
+            let stalled: Vec<usize> = (0..current.len()).filter(|&index| current[index] == last[index]).collect();
+            if stalled.is_empty() {
+                self.notify("WATCHDOG=1");
+            } else {
+                warn!("Acceptor(s) {:?} stalled; withholding the watchdog ping", stalled);
+            }
+            last = current;
+        }
+    }
 
-        self.listener.set_nonblocking(true)?; // Set the listener to non-blocking mode.
+    // Returns the accept loop iteration count of each acceptor.
+    fn heartbeats(&self) -> Vec<u64> {
+        self.heartbeats.iter().map(|count| count.load(Ordering::Relaxed)).collect()
+    }
 
+    // Accepts connections on one listener while the server is running. TLS handshakes run on
+    // threads of `scope`, so a slow client cannot hold up the accept loop.
+    fn accept_loop<'scope, 'env>(&'env self, scope: &'scope Scope<'scope, 'env>, index: usize, acceptor: &'env Acceptor) {
         while self.is_running.load(Ordering::SeqCst) { // Loop while the server is running.
-            match self.listener.accept() { // Accept new client connections.
+            self.heartbeats[index].fetch_add(1, Ordering::Relaxed); // Show the watchdog this acceptor is alive.
+            match acceptor.listener.accept() { // Accept new client connections.
                 Ok((stream, addr)) => {
-                    info!("New client connected: {}", addr); // Log the client's address.
-
-                    let is_running = Arc::clone(&self.is_running); // Clone the shared running state.
-                    thread::spawn(move || { // Spawn a thread to handle the client.
-                        match Client::new(stream) {
-                            Ok(mut client) => {
-                                while is_running.load(Ordering::SeqCst) { // Handle the client while the server is running.
-                                    if let Err(e) = client.handle() { // Process client messages.
-                                        error!("Error handling client: {}", e); // Log any errors.
-                                        break;
-                                    }
-                                }
-                            }
-                            Err(e) => {
-                                error!("Failed to initialize client: {}", e); // Log errors during client initialization.
+                    info!("New client connected: {} (acceptor {})", addr, index); // Log the client's address.
+                    self.accepted[index].fetch_add(1, Ordering::Relaxed); // Count the connection for this acceptor.
+                    if !self.admit(&addr) { // Apply the access list and connection limit.
+                        continue;
+                    }
+                    match &acceptor.tls {
+                        Some(tls) => {
+                            let spawned = thread::Builder::new()
+                                .name(format!("tls-{}", index))
+                                .spawn_scoped(scope, move || self.secure_connection(stream, addr, acceptor, tls));
+                            if let Err(e) = spawned {
+                                error!("Failed to start the TLS handshake: {}", e); // Drop the connection.
                             }
                         }
-                    });
+                        None => self.spawn_connection(stream, addr, acceptor),
+                    }
                 }
                 Err(ref e) if e.kind() == ErrorKind::WouldBlock => { // Handle non-blocking accept timeout.
                     thread::sleep(Duration::from_millis(100)); // Sleep briefly before retrying.
@@ -114,21 +578,448 @@ impl Server {
                 }
             }
         }
+    }
+
+    // Returns true if a newly accepted peer may stay connected. Refused connections are dropped.
+    // The access list applies to TCP peers; Unix socket peers are governed by file permissions.
+    fn admit(&self, addr: &Endpoint) -> bool {
+        let config = read_config(&self.config);
+        if addr.tcp().is_some_and(|tcp| !config.access.permits(tcp.ip())) {
+            warn!("Refusing connection from {}: denied by the access list", addr);
+            return false;
+        }
+        if let Some(max) = config.max_connections {
+            if self.registry.len() >= max {
+                warn!("Refusing connection from {}: {} connection limit reached", addr, max);
+                return false;
+            }
+        }
+        true
+    }
 
-        info!("Server stopped."); // Log server shutdown.
-        Ok(())
+    // Runs the TLS handshake on an accepted connection within the handshake timeout, then starts
+    // serving its plaintext side.
+    fn secure_connection(&self, stream: Stream, addr: Endpoint, acceptor: &Acceptor, tls: &TlsConfig) {
+        let (socket, timeouts) = {
+            let config = read_config(&self.config);
+            (acceptor.socket.unwrap_or(config.socket), acceptor.timeouts.unwrap_or(config.timeouts))
+        };
+        let handshake_timeout = timeouts.handshake.unwrap_or(timeouts.read);
+        let prepared = stream.set_nonblocking(false).and_then(|_| {
+            if let Some(tcp) = stream.as_tcp() {
+                socket.apply_to_stream(tcp)?; // The plaintext side may not be a TCP stream.
+            }
+            stream.set_read_timeout(Some(handshake_timeout))?;
+            stream.set_write_timeout(Some(handshake_timeout))
+        });
+        match prepared.and_then(|_| tls.accept(stream)) {
+            Ok(secured) => self.spawn_connection(secured, addr, acceptor),
+            Err(e) => warn!("TLS handshake with {} failed: {}", addr, e), // Dropping the stream closes it.
+        }
+    }
+
+    // Registers an accepted connection and starts its reader and writer threads.
+    fn spawn_connection(&self, stream: Stream, addr: Endpoint, acceptor: &Acceptor) {
+        let config = read_config(&self.config).clone(); // Snapshot the settings for this connection.
+        let socket = acceptor.socket.unwrap_or(config.socket); // Endpoint overrides win.
+        let max_frame_len = acceptor.max_frame_len.unwrap_or(config.max_frame_len);
+        let endpoint_timeouts = acceptor.timeouts;
+        let write_timeout = endpoint_timeouts.unwrap_or(config.timeouts).write;
+        let (registry_stream, queue_stream, writer_stream) = match Self::prepare_stream(&stream, write_timeout, &socket) {
+            Ok(streams) => streams,
+            Err(e) => {
+                error!("Failed to prepare client stream: {}", e); // Drop connections we cannot set up.
+                return;
+            }
+        };
+        let outbound = Arc::new(OutboundQueue::new( // Create the connection's outbound queue.
+            config.outbound.capacity,
+            config.outbound.policy,
+            queue_stream,
+        ));
+        let (session, stats) = self.registry.register(registry_stream, addr, Arc::clone(&outbound)); // Track the connection.
+        let id = session.id();
+        let writer = match outbound::spawn_writer(id, Arc::clone(&outbound), writer_stream, acceptor.codec, Arc::clone(&stats)) {
+            Ok(writer) => writer,
+            Err(e) => {
+                error!("Failed to start writer for client {}: {}", id, e); // Drop connections without a writer.
+                self.registry.unregister(id);
+                return;
+            }
+        };
+
+        let is_running = Arc::clone(&self.is_running); // Clone the shared running state.
+        let registry = Arc::clone(&self.registry); // Clone the registry for cleanup.
+        let config = Arc::clone(&self.config); // Share the live settings with the connection thread.
+        let metrics = Arc::clone(&self.metrics); // Share the server-wide counters with the connection thread.
+        let executors = Arc::clone(&self.executors); // Share the executor pools with the connection thread.
+        let shedder = Arc::clone(&self.shedder); // Share the load shedder with the connection thread.
+        let chain = Arc::clone(&self.chain); // Share the layers and handlers with the connection thread.
+        let state = Arc::clone(&self.state); // Share the application state with the connection thread.
+        let hooks = Arc::clone(&self.hooks); // Share the lifecycle hooks with the connection thread.
+        let frames = FrameReader::with_codec(acceptor.codec, max_frame_len); // Reassembles the client's requests.
+        thread::spawn(move || { // Spawn a thread to handle the client.
+            let shutdown_timeout = read_config(&config).timeouts.shutdown;
+            let context = Context::with_session(Arc::clone(&session)).with_state(state);
+            let responder = Responder { id, outbound: Arc::clone(&outbound), config, metrics, shedder, chain, context };
+            hooks.connected(&session);
+            let mut reason = DisconnectReason::Shutdown; // Unless the connection ends first, the server does.
+            match Client::new(stream, responder, stats, executors, Arc::clone(&hooks), frames, endpoint_timeouts) {
+                Ok(mut client) => {
+                    while is_running.load(Ordering::SeqCst) { // Handle the client while the server is running.
+                        match client.handle() { // Process client messages.
+                            Ok(None) => {}
+                            Ok(Some(closed)) => { // Client disconnected or was closed.
+                                reason = closed;
+                                break;
+                            }
+                            Err(e) => {
+                                error!("Error handling client {}: {}", id, e); // Log any errors.
+                                reason = DisconnectReason::Error;
+                                break;
+                            }
+                        }
+                    }
+                }
+                Err(e) => {
+                    error!("Failed to initialize client: {}", e); // Log errors during client initialization.
+                    reason = DisconnectReason::Error;
+                }
+            }
+            if !is_running.load(Ordering::SeqCst) && matches!(reason, DisconnectReason::Eof | DisconnectReason::Error) {
+                reason = DisconnectReason::Shutdown; // Stopping the server closed the stream under the client.
+            }
+            if !outbound.wait_requests(shutdown_timeout) { // Let pool workers answer requests already read.
+                warn!("Closing client {} with {} request(s) unanswered", id, outbound.in_flight());
+            }
+            hooks.disconnected(&session, session.close_reason().unwrap_or(reason)); // Before the session is torn down.
+            session.close(); // Tear down what handlers stored for the connection.
+            outbound.close(); // Let the writer flush queued messages and exit.
+            if writer.join().is_err() {
+                error!("Writer thread for client {} panicked", id);
+            }
+            registry.unregister(id); // Release the connection from the registry.
+        });
+    }
+
+    // Configures an accepted stream and returns the clones used by the registry, the outbound
+    // queue and the writer thread.
+    fn prepare_stream(stream: &Stream, write_timeout: Duration, socket: &SocketOptions) -> io::Result<(Stream, Stream, Stream)> {
+        stream.set_nonblocking(false)?; // Accepted streams may inherit the listener's non-blocking mode.
+        stream.set_write_timeout(Some(write_timeout))?; // Bound writes to clients that stop reading.
+        if let Some(tcp) = stream.as_tcp() {
+            socket.apply_to_stream(tcp)?; // Apply options the platform does not inherit from the listener.
+        }
+        Ok((stream.try_clone()?, stream.try_clone()?, stream.try_clone()?))
+    }
+
+    // Returns the address of the first TCP endpoint the server is listening on.
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        for acceptor in &self.acceptors {
+            if let Some(addr) = acceptor.listener.local_addr()?.tcp() {
+                return Ok(addr);
+            }
+        }
+        Err(io::Error::new(ErrorKind::NotFound, "the server has no TCP listener"))
+    }
+
+    // Returns every endpoint the server is listening on, once each.
+    pub fn local_addrs(&self) -> io::Result<Vec<Endpoint>> {
+        let mut addrs: Vec<Endpoint> = Vec::new();
+        for acceptor in &self.acceptors {
+            let addr = acceptor.listener.local_addr()?;
+            if !addrs.contains(&addr) { // Acceptors of one endpoint share its address.
+                addrs.push(addr);
+            }
+        }
+        Ok(addrs)
     }
 
-    // Stops the server gracefully.
+    // Returns the listeners the server accepts on, for example to hand them to a new process.
+    pub fn listeners(&self) -> Vec<&Listener> {
+        self.acceptors.iter().map(|acceptor| &acceptor.listener).collect()
+    }
+
+    // Leaves the Unix socket files this server bound in place when it stops, for a process that
+    // inherited its listeners.
+    #[cfg(unix)]
+    pub fn keep_socket_files(&self) {
+        self.acceptors.iter().filter_map(|acceptor| acceptor.socket_file.as_ref()).for_each(|file| file.keep());
+    }
+
+    // Removes the Unix socket files this server bound, unless they are kept.
+    #[cfg(unix)]
+    fn remove_socket_files(&self) {
+        self.acceptors.iter().filter_map(|acceptor| acceptor.socket_file.as_ref()).for_each(|file| file.remove());
+    }
+
+    // Unix socket files are only bound on Unix platforms.
+    #[cfg(not(unix))]
+    fn remove_socket_files(&self) {}
+
+    // Returns the number of connections accepted by each acceptor, indexed by acceptor.
+    pub fn accepted_per_acceptor(&self) -> Vec<u64> {
+        self.accepted.iter().map(|count| count.load(Ordering::Relaxed)).collect()
+    }
+
+    // Returns the number of requests whose handler panicked since the server started.
+    pub fn handler_panics(&self) -> u64 {
+        self.metrics.panics.load(Ordering::Relaxed)
+    }
+
+    // Returns the load and saturation of each executor pool, in configuration order.
+    pub fn pool_stats(&self) -> Vec<PoolStats> {
+        self.executors.stats()
+    }
+
+    // Returns the number of requests rejected by the load shedder since the server started.
+    pub fn shed_requests(&self) -> u64 {
+        self.metrics.shed.load(Ordering::Relaxed)
+    }
+
+    // Returns the total time connections spent with reading paused at their in-flight limit.
+    pub fn throttled_time(&self) -> Duration {
+        Duration::from_nanos(self.metrics.throttled_nanos.load(Ordering::Relaxed))
+    }
+
+    // Returns a copy of the settings currently in effect.
+    pub fn config(&self) -> ServerConfig {
+        read_config(&self.config).clone()
+    }
+
+    // Applies new settings to the running server without dropping connections. Timeouts reach
+    // open connections on their next read, except on endpoints that override them; limits, access
+    // lists and queue, frame and in-flight sizes apply to connections accepted from now on. Returns
+    // the names of changed settings that need a restart, which keep their current values.
+    pub fn reload(&self, mut config: ServerConfig) -> io::Result<Vec<&'static str>> {
+        config.validate()?; // Leave the running settings untouched if the new ones are unusable.
+        let mut current = self.config.write().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let restart_required = current.restart_required_changes(&config);
+        config.acceptors = current.acceptors; // Listeners are already bound.
+        config.socket = current.socket;
+        config.pools = current.pools.clone(); // Pools are already running.
+        config.load_shedding = current.load_shedding; // The shedder keeps its state.
+        config.subscriber_queue = current.subscriber_queue; // Subscribers keep their mailboxes.
+        *current = config;
+        info!("Server configuration reloaded"); // Log the reload.
+        for setting in &restart_required {
+            warn!("Changing {} requires a restart; keeping the current value", setting);
+        }
+        Ok(restart_required)
+    }
+
+    // Makes `handler` serve requests of `kind`, replacing the built-in or previously registered
+    // handler. Takes effect for connections accepted from now on, so register handlers before `run`.
+    pub fn register_handler(&mut self, kind: P::Kind, handler: impl Handler<P> + 'static) {
+        Arc::make_mut(&mut self.chain).handlers_mut().register(kind, handler);
+    }
+
+    // Wraps request handling in `layer`, inside the layers added before it: the first layer added
+    // sees requests first and replies last. Takes effect for connections accepted from now on, so
+    // add layers before `run`.
+    pub fn add_layer(&mut self, layer: impl Layer<P> + 'static) {
+        Arc::make_mut(&mut self.chain).add_layer(layer);
+    }
+
+    // Makes `value` available to every handler and layer through `Context::state`, replacing the
+    // state of the same type. Takes effect for connections accepted from now on, so insert state
+    // before `run`.
+    pub fn insert_state<T: Any + Send + Sync>(&mut self, value: T) {
+        Arc::make_mut(&mut self.state).insert(value);
+    }
+
+    // Returns the application state of type `T`, if any.
+    pub fn state<T: Any + Send + Sync>(&self) -> Option<&T> {
+        self.state.get()
+    }
+
+    // Runs `hook` on the connection thread when a connection is accepted, before its first request
+    // is read. Takes effect for connections accepted from now on, so register hooks before `run`.
+    pub fn on_connect(&mut self, hook: impl Fn(&Session) + Send + Sync + 'static) {
+        Arc::make_mut(&mut self.hooks).on_connect(hook);
+    }
+
+    // Runs `hook` when a connection sends its first request, before the request is handled. Takes
+    // effect for connections accepted from now on, so register hooks before `run`.
+    pub fn on_handshake_complete(&mut self, hook: impl Fn(&Session) + Send + Sync + 'static) {
+        Arc::make_mut(&mut self.hooks).on_handshake_complete(hook);
+    }
+
+    // Runs `hook` with the reason when a connection closes, once its requests are answered and
+    // while its session still holds what handlers stored. Takes effect for connections accepted
+    // from now on, so register hooks before `run`.
+    pub fn on_disconnect(&mut self, hook: impl Fn(&Session, DisconnectReason) + Send + Sync + 'static) {
+        Arc::make_mut(&mut self.hooks).on_disconnect(hook);
+    }
+
+    // Runs `hook` when the server has stopped and its connections have drained.
+    pub fn on_server_shutdown(&mut self, hook: impl Fn() + Send + Sync + 'static) {
+        Arc::make_mut(&mut self.hooks).on_server_shutdown(hook);
+    }
+
+    // Replaces the load shedding policy chosen by the configuration. Takes effect for connections
+    // accepted from now on, so set it before `run`.
+    pub fn set_load_shedder(&mut self, shedder: impl LoadShedder + 'static) {
+        self.shedder = Arc::new(shedder);
+    }
+
+    // Reports readiness, shutdown and watchdog pings to a service manager from now on.
+    #[cfg(unix)]
+    pub fn set_notifier(&mut self, notifier: Notifier) {
+        self.notifier = Some(notifier);
+    }
+
+    // Returns the service manager notifier, if one was set.
+    #[cfg(unix)]
+    pub fn notifier(&self) -> Option<&Notifier> {
+        self.notifier.as_ref()
+    }
+
+    // Sends a state message to the service manager, if there is one.
+    #[cfg(unix)]
+    fn notify(&self, state: &str) {
+        if let Some(notifier) = &self.notifier {
+            if let Err(e) = notifier.notify(state) {
+                warn!("Failed to notify the service manager of {}: {}", state, e);
+            }
+        }
+    }
+
+    // Service manager notifications are only available on Unix platforms.
+    #[cfg(not(unix))]
+    fn notify(&self, _state: &str) {}
+
+    // Returns the interval within which the service manager expects watchdog pings, if any.
+    #[cfg(unix)]
+    fn watchdog_interval(&self) -> Option<Duration> {
+        self.notifier.as_ref().and_then(Notifier::watchdog)
+    }
+
+    // Service manager notifications are only available on Unix platforms.
+    #[cfg(not(unix))]
+    fn watchdog_interval(&self) -> Option<Duration> {
+        None
+    }
+
+    // Returns the registry of live connections.
+    pub fn registry(&self) -> &Arc<ConnectionRegistry<P::Response>> {
+        &self.registry
+    }
+
+    // Lists the connections currently accepted by the server.
+    pub fn connections(&self) -> Vec<ConnectionInfo> {
+        self.registry.list()
+    }
+
+    // Returns the session of a live connection.
+    pub fn session(&self, id: ConnectionId) -> Option<Arc<Session>> {
+        self.registry.session(id)
+    }
+
+    // Returns another handle to the socket of a live connection, for example to inspect the
+    // socket options it was accepted with.
+    pub fn connection_stream(&self, id: ConnectionId) -> io::Result<Option<Stream>> {
+        self.registry.stream(id)
+    }
+
+    // Pushes an unsolicited message to a connection, subject to its slow-consumer policy.
+    pub fn send(&self, id: ConnectionId, message: P::Response) -> Result<(), PushError> {
+        self.registry.send(id, message)
+    }
+
+    // Forcibly closes a connection by ID. Returns false if no such connection exists.
+    pub fn disconnect(&self, id: ConnectionId) -> bool {
+        self.registry.disconnect(id)
+    }
+
+    // Returns false once `stop` has been called.
+    pub fn is_running(&self) -> bool {
+        self.is_running.load(Ordering::SeqCst)
+    }
+
+    // Stops the server gracefully: no new connections are accepted, and open connections stop
+    // reading requests but still deliver their queued replies before `run` returns.
     pub fn stop(&self) {
         if self.is_running.load(Ordering::SeqCst) { // Check if the server is running.
             self.is_running.store(false, Ordering::SeqCst); // Set the server state to stopped.
-            info!("Shutdown signal sent."); // Log the shutdown signal.
-            if let Err(e) = self.listener.try_clone() { // Attempt to clone the listener.
-                error!("Error while cloning listener during shutdown: {}", e); // Log cloning errors.
-            }
+            self.registry.shutdown_reads(); // Wake connection threads blocked in read.
+            info!("Shutdown signal sent."); // Log the shutdown signal; the accept loops exit on their next poll.
         } else {
             warn!("Server was already stopped or not running."); // Warn if the server was already stopped.
         }
     }
 }
+
+impl ProtocolServer<Messages> {
+    // Makes `router` serve requests of every type, replacing the built-in and previously registered
+    // handlers. Takes effect for connections accepted from now on, so register it before `run`.
+    pub fn register_router(&mut self, router: impl Router + 'static) {
+        Arc::make_mut(&mut self.chain).handlers_mut().register_router(router);
+    }
+
+    // Returns the broker behind the built-in publish/subscribe handlers, for example to publish
+    // from the server side.
+    pub fn broker(&self) -> Option<&Broker> {
+        self.state()
+    }
+
+    // Returns the chat rooms behind the built-in room handlers.
+    pub fn rooms(&self) -> Option<&Rooms> {
+        self.state()
+    }
+}
+
+// Lifecycle state of a server started with `Server::start`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ServerStatus {
+    Running, // Accepting and serving connections.
+    Stopping, // No longer accepting; open connections are draining.
+    Stopped, // `run` has returned; `ServerHandle::wait` reports how.
+}
+
+// Handle to a server running on its own thread, returned by `Server::start`.
+pub struct ServerHandle<P: Protocol = Messages> {
+    server: Arc<ProtocolServer<P>>, // The running server, for pushes, connection listings and reloads.
+    thread: JoinHandle<io::Result<()>>, // Thread running `Server::run`.
+}
+
+impl<P: Protocol> ServerHandle<P> {
+    // Returns the address of the first TCP endpoint, including the port chosen for port 0.
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.server.local_addr()
+    }
+
+    // Returns every endpoint the server is listening on.
+    pub fn local_addrs(&self) -> io::Result<Vec<Endpoint>> {
+        self.server.local_addrs()
+    }
+
+    // Returns the running server.
+    pub fn server(&self) -> &Arc<ProtocolServer<P>> {
+        &self.server
+    }
+
+    // Stops the server gracefully; `wait` returns once open connections have drained.
+    pub fn stop(&self) {
+        self.server.stop();
+    }
+
+    // Returns whether the server is running, draining or has stopped.
+    pub fn status(&self) -> ServerStatus {
+        if self.thread.is_finished() {
+            ServerStatus::Stopped
+        } else if self.server.is_running() {
+            ServerStatus::Running
+        } else {
+            ServerStatus::Stopping
+        }
+    }
+
+    // Blocks until the server has stopped. Returns an error if connections had to be forced
+    // closed during shutdown or the server thread panicked.
+    pub fn wait(self) -> io::Result<()> {
+        self.thread
+            .join()
+            .unwrap_or_else(|_| Err(io::Error::other("server thread panicked")))
+    }
+}
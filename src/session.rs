@@ -0,0 +1,155 @@
+use crate::hooks::DisconnectReason; // Import why connections close.
+use crate::registry::ConnectionId; // Import connection identifiers.
+use crate::stream::Endpoint; // Import peer addresses.
+use std::{
+    any::{Any, TypeId}, // Keys and values of the extension map.
+    collections::{BTreeSet, HashMap}, // Negotiated features and extensions by type.
+    fmt, // Formatting support for sessions.
+    sync::{Mutex, MutexGuard}, // Guarding state shared by handlers of the same connection.
+    time::Instant, // Time the session was opened.
+};
+
+// Session state guarded by the mutex.
+#[derive(Default)]
+struct State {
+    open: bool, // Whether the connection is still being served.
+    identity: Option<String>, // Who the client authenticated as.
+    features: BTreeSet<String>, // Features negotiated with the client.
+    extensions: HashMap<TypeId, Box<dyn Any + Send + Sync>>, // Values stored by handlers, one per type.
+}
+
+// What the server knows about a connection, shared by every handler and layer serving it. Requests
+// of the same connection may be handled concurrently on pool workers, so the state behind the
+// stable ID and peer address is guarded by a lock. The session is torn down when the connection
+// closes: the identity, features and extensions are dropped and `is_open` returns false.
+pub struct Session {
+    id: ConnectionId, // Registry ID of the connection.
+    peer_addr: Endpoint, // Address of the client.
+    opened_at: Instant, // Time the connection was accepted.
+    state: Mutex<State>, // Mutable session state.
+    closed_by: Mutex<Option<DisconnectReason>>, // Why the server closed the connection, if it did.
+}
+
+impl Session {
+    // Opens the session of connection `id`.
+    pub fn new(id: ConnectionId, peer_addr: Endpoint) -> Self {
+        let state = State { open: true, ..State::default() };
+        Session { id, peer_addr, opened_at: Instant::now(), state: Mutex::new(state), closed_by: Mutex::new(None) }
+    }
+
+    // Returns the registry ID of the connection.
+    pub fn id(&self) -> ConnectionId {
+        self.id
+    }
+
+    // Returns the address of the client.
+    pub fn peer_addr(&self) -> &Endpoint {
+        &self.peer_addr
+    }
+
+    // Returns the time the connection was accepted.
+    pub fn opened_at(&self) -> Instant {
+        self.opened_at
+    }
+
+    // Returns whether the connection is still being served.
+    pub fn is_open(&self) -> bool {
+        self.lock().open
+    }
+
+    // Returns who the client authenticated as, if anyone.
+    pub fn identity(&self) -> Option<String> {
+        self.lock().identity.clone()
+    }
+
+    // Records who the client authenticated as, replacing any previous identity.
+    pub fn set_identity(&self, identity: impl Into<String>) {
+        self.lock().identity = Some(identity.into());
+    }
+
+    // Forgets the client's identity.
+    pub fn clear_identity(&self) {
+        self.lock().identity = None;
+    }
+
+    // Returns whether `feature` was negotiated with the client.
+    pub fn has_feature(&self, feature: &str) -> bool {
+        self.lock().features.contains(feature)
+    }
+
+    // Returns the negotiated features, in order.
+    pub fn features(&self) -> Vec<String> {
+        self.lock().features.iter().cloned().collect()
+    }
+
+    // Records `feature` as negotiated. Returns false if it already was.
+    pub fn enable_feature(&self, feature: impl Into<String>) -> bool {
+        self.lock().features.insert(feature.into())
+    }
+
+    // Withdraws `feature`. Returns false if it was not negotiated.
+    pub fn disable_feature(&self, feature: &str) -> bool {
+        self.lock().features.remove(feature)
+    }
+
+    // Stores `value` as the session's extension of type `T`, returning the previous one.
+    pub fn insert<T: Any + Send + Sync>(&self, value: T) -> Option<T> {
+        let previous = self.lock().extensions.insert(TypeId::of::<T>(), Box::new(value));
+        previous.and_then(|previous| previous.downcast().ok()).map(|previous| *previous)
+    }
+
+    // Returns a copy of the session's extension of type `T`, if there is one.
+    pub fn get<T: Any + Send + Sync + Clone>(&self) -> Option<T> {
+        self.lock().extensions.get(&TypeId::of::<T>()).and_then(|value| value.downcast_ref::<T>()).cloned()
+    }
+
+    // Calls `f` with the session's extension of type `T`, inserting `T::default()` first if there is
+    // none, and returns its result. The session stays locked while `f` runs.
+    pub fn update<T: Any + Send + Sync + Default, R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+        let mut state = self.lock();
+        let value = state.extensions.entry(TypeId::of::<T>()).or_insert_with(|| Box::new(T::default()));
+        f(value.downcast_mut::<T>().expect("extensions are keyed by their type"))
+    }
+
+    // Removes and returns the session's extension of type `T`.
+    pub fn remove<T: Any + Send + Sync>(&self) -> Option<T> {
+        let removed = self.lock().extensions.remove(&TypeId::of::<T>());
+        removed.and_then(|removed| removed.downcast().ok()).map(|removed| *removed)
+    }
+
+    // Records why the server is closing the connection, unless a reason was already recorded, so
+    // the disconnect hooks are not told the client went first.
+    pub(crate) fn closed_by(&self, reason: DisconnectReason) {
+        self.closed_by.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).get_or_insert(reason);
+    }
+
+    // Returns why the server closed the connection, if it did.
+    pub(crate) fn close_reason(&self) -> Option<DisconnectReason> {
+        *self.closed_by.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    // Tears the session down once its connection has closed, dropping what handlers stored in it.
+    pub(crate) fn close(&self) {
+        let state = std::mem::take(&mut *self.lock()); // Drop the old state outside the lock.
+        drop(state);
+    }
+
+    // Locks the session state, recovering from a poisoned lock.
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl fmt::Debug for Session {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.lock();
+        f.debug_struct("Session")
+            .field("id", &self.id)
+            .field("peer_addr", &self.peer_addr)
+            .field("open", &state.open)
+            .field("identity", &state.identity)
+            .field("features", &state.features)
+            .field("extensions", &state.extensions.len())
+            .finish()
+    }
+}
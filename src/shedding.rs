@@ -0,0 +1,112 @@
+use crate::config::CoDelConfig; // Import queue delay shedding settings.
+use log::{info, warn}; // Import macros for structured logging.
+use std::{
+    sync::{Mutex, MutexGuard}, // Guarding the controller state.
+    time::{Duration, Instant}, // Queue delays and shedding intervals.
+};
+
+// Decides which requests to reject while the server is overloaded. The connection thread asks
+// `admit` as each request arrives; requests that are admitted report how long they waited before
+// their handler ran through `start`, and how long the handler took through `finish`. A rejection
+// carries how long the client should wait before retrying; the client receives it as an
+// `Overloaded` error.
+pub trait LoadShedder: Send + Sync {
+    // Called when a request arrives, before it waits for a handler. Returns the retry-after hint
+    // to reject it straight away.
+    fn admit(&self) -> Result<(), Duration> {
+        Ok(())
+    }
+
+    // Called when an admitted request is about to be handled, after waiting `queued`. Returns
+    // the retry-after hint to reject it instead of handling it.
+    fn start(&self, queued: Duration) -> Result<(), Duration>;
+
+    // Called when the handler of a started request has returned, after running for `service`.
+    fn finish(&self, _service: Duration) {}
+}
+
+// Admits every request.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NoShedding;
+
+impl LoadShedder for NoShedding {
+    fn start(&self, _queued: Duration) -> Result<(), Duration> {
+        Ok(())
+    }
+}
+
+// Controller state guarded by the mutex.
+#[derive(Debug)]
+struct State {
+    above_since: Option<Instant>, // Time the queue delay went above target, while it stays there.
+    dropping: bool, // Whether requests are being shed.
+    last_start: Option<Instant>, // Time a request last reached `start`.
+}
+
+// Sheds load based on queue delay, in the style of CoDel. Short bursts queue normally; once
+// requests have been waiting longer than `target` for a whole `interval`, requests that waited
+// too long are rejected, and so are new arrivals, so the queue drains quickly. Shedding stops as
+// soon as a request is handled within `target`. Rejections ask clients to retry after `interval`.
+#[derive(Debug)]
+pub struct CoDel {
+    config: CoDelConfig, // Target delay and interval.
+    state: Mutex<State>, // Controller state.
+}
+
+impl CoDel {
+    // Creates a controller with the given settings.
+    pub fn new(config: CoDelConfig) -> Self {
+        CoDel { config, state: Mutex::new(State { above_since: None, dropping: false, last_start: None }) }
+    }
+
+    // Returns whether requests are being shed.
+    pub fn is_dropping(&self) -> bool {
+        self.lock().dropping
+    }
+
+    // Locks the controller, recovering from a poisoned lock.
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl LoadShedder for CoDel {
+    fn admit(&self) -> Result<(), Duration> {
+        let state = self.lock();
+        // Once nothing has reached a handler for an interval the backlog is gone; admit requests
+        // again so that their delay can end the shedding.
+        let backlogged = state.last_start.is_some_and(|last| last.elapsed() < self.config.interval);
+        if state.dropping && backlogged {
+            Err(self.config.interval)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn start(&self, queued: Duration) -> Result<(), Duration> {
+        let now = Instant::now();
+        let mut state = self.lock();
+        state.last_start = Some(now);
+        if queued < self.config.target {
+            if state.dropping {
+                info!("Queue delay back under {:?}; no longer shedding load", self.config.target);
+            }
+            state.above_since = None;
+            state.dropping = false;
+            return Ok(());
+        }
+        // A request that waited `queued` shows the delay has been above target since it arrived plus `target`.
+        let arrived_above = now.checked_sub(queued - self.config.target).unwrap_or(now);
+        let since = state.above_since.map_or(arrived_above, |since| since.min(arrived_above));
+        state.above_since = Some(since);
+        if !state.dropping && now.duration_since(since) >= self.config.interval {
+            warn!("Queue delay above {:?} for {:?}; shedding load", self.config.target, self.config.interval);
+            state.dropping = true;
+        }
+        if state.dropping {
+            Err(self.config.interval)
+        } else {
+            Ok(())
+        }
+    }
+}
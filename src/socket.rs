@@ -0,0 +1,125 @@
+use socket2::{SockRef, Socket, TcpKeepalive}; // Low-level access to socket options std does not expose.
+use std::{
+    io::{self, ErrorKind}, // Error type used to reject invalid settings.
+    net::TcpStream, // Streams the options are applied to.
+    time::Duration, // Support for specifying time intervals.
+};
+
+// TCP keepalive probe settings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeepAlive {
+    pub idle: Duration, // Idle time before the first probe (TCP_KEEPIDLE).
+    pub interval: Option<Duration>, // Time between probes (TCP_KEEPINTVL); system default when None.
+    pub retries: Option<u32>, // Unanswered probes before the connection is dropped (TCP_KEEPCNT); system default when None.
+}
+
+// An option that is either switched off or on with the given settings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Setting<T> {
+    Off, // Disabled, overriding the system default.
+    On(T), // Enabled with these settings.
+}
+
+// TCP socket options applied to listeners and streams. `None` leaves the system default in place,
+// so endpoint overrides turn an option off with `Setting::Off`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SocketOptions {
+    pub nodelay: Option<bool>, // TCP_NODELAY: disable Nagle's algorithm.
+    pub keepalive: Option<Setting<KeepAlive>>, // SO_KEEPALIVE and its probe settings.
+    pub recv_buffer_size: Option<usize>, // SO_RCVBUF in bytes.
+    pub send_buffer_size: Option<usize>, // SO_SNDBUF in bytes.
+    pub linger: Option<Setting<Duration>>, // SO_LINGER: how long close waits to flush unsent data.
+    pub backlog: i32, // Listen backlog; only used for listeners.
+    pub only_v6: Option<bool>, // IPV6_V6ONLY: whether IPv6 listeners refuse IPv4 clients; only used for IPv6 listeners.
+}
+
+impl Default for SocketOptions {
+    fn default() -> Self {
+        SocketOptions {
+            nodelay: None,
+            keepalive: None,
+            recv_buffer_size: None,
+            send_buffer_size: None,
+            linger: None,
+            backlog: 128,
+            only_v6: None,
+        }
+    }
+}
+
+impl SocketOptions {
+    // Rejects values the socket API cannot use.
+    pub fn validate(&self) -> io::Result<()> {
+        if self.backlog <= 0 {
+            return Err(invalid("listen backlog must be greater than zero"));
+        }
+        if self.recv_buffer_size == Some(0) || self.send_buffer_size == Some(0) {
+            return Err(invalid("socket buffer sizes must be greater than zero"));
+        }
+        if let Some(Setting::On(keepalive)) = self.keepalive {
+            if keepalive.idle.is_zero() || keepalive.interval.is_some_and(|interval| interval.is_zero()) {
+                return Err(invalid("keepalive durations must be greater than zero"));
+            }
+            if keepalive.retries == Some(0) {
+                return Err(invalid("keepalive retries must be greater than zero"));
+            }
+        }
+        Ok(())
+    }
+
+    // Applies the options to a connected stream.
+    pub fn apply_to_stream(&self, stream: &TcpStream) -> io::Result<()> {
+        self.apply(&SockRef::from(stream))
+    }
+
+    // Applies the options to a socket before it binds, so accepted streams inherit them.
+    pub(crate) fn apply_to_listener(&self, socket: &Socket) -> io::Result<()> {
+        self.apply(&SockRef::from(socket))
+    }
+
+    // Applies every configured option to the socket.
+    fn apply(&self, socket: &SockRef<'_>) -> io::Result<()> {
+        if let Some(nodelay) = self.nodelay {
+            socket.set_nodelay(nodelay)?;
+        }
+        match self.keepalive {
+            Some(Setting::On(keepalive)) => socket.set_tcp_keepalive(&keepalive.to_tcp_keepalive())?, // Also enables SO_KEEPALIVE.
+            Some(Setting::Off) => socket.set_keepalive(false)?,
+            None => {}
+        }
+        if let Some(size) = self.recv_buffer_size {
+            socket.set_recv_buffer_size(size)?;
+        }
+        if let Some(size) = self.send_buffer_size {
+            socket.set_send_buffer_size(size)?;
+        }
+        match self.linger {
+            Some(Setting::On(linger)) => socket.set_linger(Some(linger))?,
+            Some(Setting::Off) => socket.set_linger(None)?,
+            None => {}
+        }
+        Ok(())
+    }
+}
+
+impl KeepAlive {
+    // Converts the settings to socket2's representation, skipping fields the platform lacks.
+    fn to_tcp_keepalive(self) -> TcpKeepalive {
+        #[allow(unused_mut)] // Interval and retries are only applied on some platforms.
+        let mut keepalive = TcpKeepalive::new().with_time(self.idle);
+        #[cfg(any(target_os = "linux", target_os = "android", target_os = "freebsd", target_os = "macos", target_os = "ios", target_os = "windows"))]
+        if let Some(interval) = self.interval {
+            keepalive = keepalive.with_interval(interval);
+        }
+        #[cfg(any(target_os = "linux", target_os = "android", target_os = "freebsd", target_os = "macos", target_os = "ios"))]
+        if let Some(retries) = self.retries {
+            keepalive = keepalive.with_retries(retries);
+        }
+        keepalive
+    }
+}
+
+// Builds an InvalidInput error.
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(ErrorKind::InvalidInput, message.to_string())
+}
@@ -0,0 +1,49 @@
+use std::{
+    any::{Any, TypeId}, // Keys and values of the container.
+    collections::HashMap, // Values by type.
+    fmt, // Formatting support for the container.
+    sync::Arc, // Values shared with every connection.
+};
+
+// Application state shared by every handler and layer, such as database handles, counters or
+// settings, holding at most one value of each type. Values are registered with
+// `Server::insert_state` before the server runs and read through `Context::state`; they are
+// shared by every connection and pool worker at once, so a value that handlers change needs its
+// own synchronisation, such as atomics or a mutex.
+#[derive(Clone, Default)]
+pub struct AppState {
+    values: HashMap<TypeId, Arc<dyn Any + Send + Sync>>, // Value by type.
+}
+
+impl AppState {
+    // Creates an empty container.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Stores `value` as the state of type `T`, replacing any previous one.
+    pub fn insert<T: Any + Send + Sync>(&mut self, value: T) {
+        self.values.insert(TypeId::of::<T>(), Arc::new(value));
+    }
+
+    // Returns the state of type `T`, if there is one.
+    pub fn get<T: Any + Send + Sync>(&self) -> Option<&T> {
+        self.values.get(&TypeId::of::<T>()).and_then(|value| value.downcast_ref::<T>())
+    }
+
+    // Returns the number of values stored.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    // Returns true when no values are stored.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+}
+
+impl fmt::Debug for AppState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AppState").field("values", &self.values.len()).finish()
+    }
+}
@@ -0,0 +1,60 @@
+use crate::stream::Stream; // Import connection handles.
+use std::{
+    fmt, // Formatting support for the settings.
+    io, // Error type for failed handshakes.
+    sync::Arc, // Acceptors shared by every connection of a listener.
+};
+
+// Secures the connections accepted on a listener, typically with TLS. The server calls `accept` on
+// a thread of its own for each connection, after applying the listener's socket options and with
+// the stream's read and write timeouts set to the handshake timeout. `accept` runs the handshake
+// and returns the stream the server reads plaintext requests from and writes plaintext replies to,
+// such as one end of a socket pair whose other end the TLS session relays. An error closes the
+// connection before it is registered.
+pub trait TlsAcceptor: Send + Sync {
+    // Runs the handshake on an accepted stream and returns its plaintext side.
+    fn accept(&self, stream: Stream) -> io::Result<Stream>;
+}
+
+impl<F> TlsAcceptor for F
+where
+    F: Fn(Stream) -> io::Result<Stream> + Send + Sync,
+{
+    fn accept(&self, stream: Stream) -> io::Result<Stream> {
+        self(stream)
+    }
+}
+
+// TLS settings of a listener. The certificates, protocol versions and other session settings
+// belong to the acceptor, so any TLS library can be plugged in. Two settings are equal when they
+// share the same acceptor.
+#[derive(Clone)]
+pub struct TlsConfig {
+    acceptor: Arc<dyn TlsAcceptor>, // Handshakes new connections.
+}
+
+impl TlsConfig {
+    // Creates settings that hand every accepted connection to `acceptor`.
+    pub fn new(acceptor: impl TlsAcceptor + 'static) -> Self {
+        TlsConfig { acceptor: Arc::new(acceptor) }
+    }
+
+    // Runs the handshake on an accepted stream and returns its plaintext side.
+    pub(crate) fn accept(&self, stream: Stream) -> io::Result<Stream> {
+        self.acceptor.accept(stream)
+    }
+}
+
+impl fmt::Debug for TlsConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TlsConfig").finish_non_exhaustive()
+    }
+}
+
+impl PartialEq for TlsConfig {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.acceptor, &other.acceptor)
+    }
+}
+
+impl Eq for TlsConfig {}
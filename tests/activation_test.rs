@@ -0,0 +1,107 @@
+#![cfg(unix)]
+
+use embedded_recruitment_task::{
+    activation, // For passing listening sockets the way a service manager does
+    message::{client_message, EchoMessage}, // Importing message types for client-server communication
+};
+use std::{
+    net::TcpListener, // For sockets bound by the test instead of the server
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering}, // For the background client loop
+        Arc,
+    },
+    thread, // For clients running during the handoff
+    time::{Duration, Instant}, // For deadlines while waiting on processes
+};
+
+#[allow(dead_code)] // Not every client helper is used by this suite
+mod client; // Declares a client module for client-related operations
+mod process; // Declares a module for driving the server binary
+
+use process::{attach, is_alive, send_signal, server_command, spawn_server_with_args, wait_for_exit};
+
+/// Utility function to run one echo round trip on a fresh connection.
+fn echo_once(port: u16, content: &str) -> Result<(), String> {
+    let mut client = client::Client::new("127.0.0.1", port.into(), 2000);
+    client.connect().map_err(|e| format!("connect failed: {}", e))?;
+    let echo_message = EchoMessage { content: content.to_string() };
+    client
+        .send(client_message::Message::EchoMessage(echo_message))
+        .map_err(|e| format!("send failed: {}", e))?;
+    client.receive().map_err(|e| format!("receive failed: {}", e))?;
+    client.disconnect().map_err(|e| format!("disconnect failed: {}", e))
+}
+
+/// Test to validate that the server serves sockets passed through LISTEN_FDS instead of binding its own.
+#[test]
+fn test_socket_activation_uses_inherited_listener() {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("Failed to bind a listener");
+    let port = listener.local_addr().unwrap().port();
+
+    // The address argument is ignored in favour of the inherited socket
+    let mut command = server_command(&["127.0.0.1:0"]);
+    let child = activation::spawn_with_listeners(&mut command, &[&listener]).expect("Failed to start the server binary");
+    let mut server = attach(child);
+    assert_eq!(server.port, port, "Expected the server to report the inherited socket");
+    drop(listener); // The server holds its own copy
+
+    assert_eq!(echo_once(port, "activated"), Ok(()));
+
+    send_signal(server.pid, "TERM");
+    let status = wait_for_exit(&mut server.child, Duration::from_secs(5)).expect("Server did not exit after SIGTERM");
+    assert_eq!(status.code(), Some(0), "Expected a clean exit, got {}", status);
+}
+
+/// Test to validate that SIGUSR2 hands the listener to a new process without refusing any client.
+#[test]
+fn test_upgrade_hands_over_listener_without_refusing_clients() {
+    let mut server = spawn_server_with_args(&["127.0.0.1:0"]);
+    let port = server.port;
+    assert_eq!(echo_once(port, "before upgrade"), Ok(()));
+
+    // Keep clients arriving throughout the handoff
+    let running = Arc::new(AtomicBool::new(true));
+    let served = Arc::new(AtomicUsize::new(0));
+    let background = {
+        let running = Arc::clone(&running);
+        let served = Arc::clone(&served);
+        thread::spawn(move || {
+            let mut failures = Vec::new();
+            while running.load(Ordering::SeqCst) {
+                match echo_once(port, "during upgrade") {
+                    Ok(()) => {
+                        served.fetch_add(1, Ordering::SeqCst);
+                    }
+                    Err(e) => failures.push(e),
+                }
+            }
+            failures
+        })
+    };
+
+    send_signal(server.pid, "USR2");
+    let (new_port, new_pid) = server.next_startup_line();
+    assert_eq!(new_port, port, "Expected the new process to serve the same port");
+    assert_ne!(new_pid, server.pid, "Expected a new process");
+
+    let status = wait_for_exit(&mut server.child, Duration::from_secs(5)).expect("Old server did not exit after the upgrade");
+    assert_eq!(status.code(), Some(0), "Expected the old server to drain cleanly, got {}", status);
+
+    // Let clients reach the new process alone for a while
+    let served_at_exit = served.load(Ordering::SeqCst);
+    let deadline = Instant::now() + Duration::from_secs(2);
+    while served.load(Ordering::SeqCst) < served_at_exit + 10 && Instant::now() < deadline {
+        thread::sleep(Duration::from_millis(20));
+    }
+    running.store(false, Ordering::SeqCst);
+    let failures = background.join().unwrap();
+    assert!(failures.is_empty(), "Expected no client to fail during the upgrade: {:?}", failures);
+    assert!(served.load(Ordering::SeqCst) > served_at_exit, "Expected the new process to serve clients");
+
+    send_signal(new_pid, "TERM");
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while is_alive(new_pid) && Instant::now() < deadline {
+        thread::sleep(Duration::from_millis(20));
+    }
+    assert!(!is_alive(new_pid), "New server did not exit after SIGTERM");
+}
@@ -1,10 +1,14 @@
-use embedded_recruitment_task::message::{client_message, ServerMessage};
+use embedded_recruitment_task::{
+    message::{client_message, ClientMessage, ServerMessage},
+    socket::SocketOptions,
+};
 use log::{error, info, warn};
 use prost::Message;
 use std::{
     io::{self, Read, Write},
     net::{SocketAddr, TcpStream, ToSocketAddrs},
-    time::Duration,
+    thread,
+    time::{Duration, Instant},
 };
 
 pub struct Client {
@@ -12,6 +16,8 @@ pub struct Client {
     port: u32,
     timeout: Duration,
     stream: Option<TcpStream>,
+    buffer: Vec<u8>,
+    socket_options: SocketOptions,
 }
 
 impl Client {
@@ -22,9 +28,21 @@ impl Client {
             port,
             timeout: Duration::from_millis(timeout_ms),
             stream: None,
+            buffer: Vec::new(),
+            socket_options: SocketOptions::default(),
         }
     }
 
+    /// Sets the TCP socket options applied on the next `connect`.
+    pub fn set_socket_options(&mut self, options: SocketOptions) {
+        self.socket_options = options;
+    }
+
+    /// Returns the underlying stream while connected.
+    pub fn stream(&self) -> Option<&TcpStream> {
+        self.stream.as_ref()
+    }
+
     /// Connects the client to the server.
     pub fn connect(&mut self) -> io::Result<()> {
         info!("Connecting to {}:{}", self.ip, self.port);
@@ -42,8 +60,10 @@ impl Client {
         let stream = TcpStream::connect_timeout(&socket_addrs[0], self.timeout)?;
         stream.set_read_timeout(Some(self.timeout))?;
         stream.set_write_timeout(Some(self.timeout))?;
+        self.socket_options.apply_to_stream(&stream)?;
 
         self.stream = Some(stream);
+        self.buffer.clear();
         info!("Connected to the server!");
         Ok(())
     }
@@ -60,10 +80,10 @@ impl Client {
     /// Sends a message to the server.
     pub fn send(&mut self, message: client_message::Message) -> io::Result<()> {
         if let Some(ref mut stream) = self.stream {
-            let mut buffer = Vec::new();
-            // Assuming `encode` does not return a Result
-            message.encode(&mut buffer);
-            
+            // Wrap the message in its envelope and frame it with a length prefix
+            let envelope = ClientMessage { message: Some(message.clone()) };
+            let buffer = envelope.encode_length_delimited_to_vec();
+
             // If you need to handle errors related to the encoding, you can check it manually
             if buffer.is_empty() {
                 return Err(io::Error::new(io::ErrorKind::InvalidData, "Encoding error"));
@@ -86,18 +106,26 @@ impl Client {
     pub fn receive(&mut self) -> io::Result<ServerMessage> {
         if let Some(ref mut stream) = self.stream {
             info!("Receiving message from the server");
-            let mut buffer = vec![0u8; 1024];
-            let bytes_read = stream.read(&mut buffer)?;
-
-            if bytes_read == 0 {
-                warn!("Server disconnected or no data received.");
-                return Err(io::Error::new(
-                    io::ErrorKind::ConnectionAborted,
-                    "Server disconnected",
-                ));
-            }
+            // Read until a complete length-delimited frame is buffered
+            let frame_len = loop {
+                if let Some(frame_len) = complete_frame_len(&self.buffer) {
+                    break frame_len;
+                }
 
-            ServerMessage::decode(&buffer[..bytes_read]).map_err(|e| {
+                let mut chunk = vec![0u8; 4096];
+                let bytes_read = stream.read(&mut chunk)?;
+                if bytes_read == 0 {
+                    warn!("Server disconnected or no data received.");
+                    return Err(io::Error::new(
+                        io::ErrorKind::ConnectionAborted,
+                        "Server disconnected",
+                    ));
+                }
+                self.buffer.extend_from_slice(&chunk[..bytes_read]);
+            };
+
+            let frame: Vec<u8> = self.buffer.drain(..frame_len).collect();
+            ServerMessage::decode_length_delimited(frame.as_slice()).map_err(|e| {
                 error!("Failed to decode ServerMessage: {}", e);
                 io::Error::new(
                     io::ErrorKind::InvalidData,
@@ -113,3 +141,22 @@ impl Client {
         }
     }
 }
+
+/// Returns the size of the first frame in `buffer` (prefix included) once it has fully arrived.
+fn complete_frame_len(buffer: &[u8]) -> Option<usize> {
+    let payload_len = prost::decode_length_delimiter(buffer).ok()?;
+    let frame_len = prost::length_delimiter_len(payload_len) + payload_len;
+    (buffer.len() >= frame_len).then_some(frame_len)
+}
+
+/// Polls a condition until it holds or a deadline passes, returning whether it held.
+pub fn wait_until(timeout: Duration, mut condition: impl FnMut() -> bool) -> bool {
+    let deadline = Instant::now() + timeout;
+    while Instant::now() < deadline {
+        if condition() {
+            return true;
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+    condition()
+}
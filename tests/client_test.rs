@@ -1,17 +1,29 @@
 use embedded_recruitment_task::{
-    message::{client_message, server_message, AddRequest, EchoMessage}, // Importing message types for client-server communication
-    server::Server, // Importing server functionalities
+    handler::{Context, Echo, Handler, HandlerResult}, // Importing request handlers
+    message::{client_message, server_message, AddRequest, ClientMessage, EchoMessage, ErrorCode, ServerMessage}, // Importing message types for client-server communication
+    access::{AccessList, IpNet}, // Importing peer access control
+    config::{ConfigFile, ListenerConfig, OutboundConfig, PanicPolicy, ServerConfig, Timeouts}, // Importing server settings
+    listener::{ListenAddr, Listener}, // Importing listen addresses and listening sockets
+    outbound::{PushError, SlowConsumerPolicy}, // Importing server push types
+    router::MessageKind, // Importing message types handlers are registered for
+    server::{Server, ServerStatus}, // Importing server functionalities
+    socket::{KeepAlive, Setting, SocketOptions}, // Importing TCP socket tuning
 };
-use log::{debug, error, info, warn}; // Logging macros for debug, error, info, and warning levels
+use log::{debug, error}; // Logging macros for debug and error levels
+use prost::Message; // For framing raw requests
 use std::{
     env, // Provides access to environment variables
-    net::TcpListener, // Used to create and manage a TCP listener
+    io::{ErrorKind, Write}, // For inspecting client-side errors and writing raw frames
+    net::TcpStream, // Used for raw clients
     sync::Arc, // For shared ownership of server instances between threads
     thread::{self, JoinHandle}, // For thread creation and management
+    time::{Duration, Instant}, // For timing server behaviour
 };
 
 mod client; // Declares a client module for client-related operations
 
+use client::wait_until; // Polls for asynchronous server effects
+
 /// Utility function to set up a server in a separate thread.
 ///
 /// # Arguments
@@ -32,11 +44,17 @@ fn setup_server_thread(server: Arc<Server>) -> JoinHandle<()> {
 /// # Returns
 /// - A tuple containing the `Arc`-wrapped server instance and the port number it is bound to.
 fn create_server() -> (Arc<Server>, u16) {
-    let listener = TcpListener::bind("localhost:0").expect("Failed to bind to an available port"); // Bind to an available port
-    let port = listener.local_addr().unwrap().port(); // Retrieve the assigned port
-    drop(listener); // Release the port for use by the server
-    let server = Arc::new(Server::new(&format!("localhost:{}", port)).expect("Failed to start server")); // Initialize the server
-    (server, port)
+    create_server_with_config(ServerConfig::default())
+}
+
+/// Utility function to create a new server instance with custom settings on a unique port.
+///
+/// # Returns
+/// - A tuple containing the `Arc`-wrapped server instance and the port number it is bound to.
+fn create_server_with_config(config: ServerConfig) -> (Arc<Server>, u16) {
+    let server = Server::with_config("localhost:0", config).expect("Failed to start server"); // Bind to an available port
+    let port = server.local_addr().unwrap().port(); // Retrieve the assigned port
+    (Arc::new(server), port)
 }
 
 /// Test to validate basic client connection and disconnection behavior.
@@ -72,6 +90,7 @@ fn test_client_connection() {
 
 /// Test to validate server handling of `EchoMessage`.
 #[test]
+#[allow(clippy::field_reassign_with_default)] // The baseline test builds its request field by field
 fn test_client_echo_message() {
     env::set_var("RUST_LOG", "debug");
     let _ = env_logger::builder().is_test(true).try_init();
@@ -110,6 +129,7 @@ fn test_client_echo_message() {
 
 /// Test to validate server handling of multiple `EchoMessage` instances sequentially.
 #[test]
+#[allow(clippy::field_reassign_with_default)] // The baseline test builds its request field by field
 fn test_multiple_echo_messages() {
     env::set_var("RUST_LOG", "debug");
     let _ = env_logger::builder().is_test(true).try_init();
@@ -150,6 +170,7 @@ fn test_multiple_echo_messages() {
 
 /// Test to validate server handling of multiple clients simultaneously.
 #[test]
+#[allow(clippy::field_reassign_with_default)] // The baseline test builds its request field by field
 fn test_multiple_clients() {
     env::set_var("RUST_LOG", "debug");
     let _ = env_logger::builder().is_test(true).try_init();
@@ -201,6 +222,7 @@ fn test_multiple_clients() {
 
 /// Test to validate server handling of `AddRequest` messages.
 #[test]
+#[allow(clippy::field_reassign_with_default, clippy::clone_on_copy)] // The baseline test builds its request field by field and clones it
 fn test_client_add_request() {
     env::set_var("RUST_LOG", "debug");
     let _ = env_logger::builder().is_test(true).try_init();
@@ -237,3 +259,724 @@ fn test_client_add_request() {
     server.stop();
     assert!(handle.join().is_ok(), "Server thread panicked or failed to join");
 }
+
+/// Test to validate that the connection registry tracks live clients and their traffic.
+#[test]
+fn test_connection_registry_lists_clients() {
+    env::set_var("RUST_LOG", "debug");
+    let _ = env_logger::builder().is_test(true).try_init();
+
+    let (server, port) = create_server();
+    let handle = setup_server_thread(server.clone());
+
+    let mut first = client::Client::new("localhost", port.into(), 1000);
+    let mut second = client::Client::new("localhost", port.into(), 1000);
+    assert!(first.connect().is_ok(), "Failed to connect to the server");
+    assert!(second.connect().is_ok(), "Failed to connect to the server");
+    assert!(
+        wait_until(Duration::from_secs(2), || server.connections().len() == 2),
+        "Expected two registered connections"
+    );
+
+    // Send one request from the first client so its counters move
+    let echo_message = EchoMessage { content: "ping".to_string() };
+    assert!(first.send(client_message::Message::EchoMessage(echo_message)).is_ok(), "Failed to send message");
+    assert!(first.receive().is_ok(), "Failed to receive response for EchoMessage");
+
+    // The writer counts outbound bytes after the reply has gone out, so give it a moment
+    assert!(
+        wait_until(Duration::from_secs(2), || server.connections().iter().any(|info| info.bytes_out > 0)),
+        "Expected outbound bytes to be recorded"
+    );
+    let connections = server.connections();
+    assert_ne!(connections[0].id, connections[1].id, "Connection IDs must be unique");
+    let active = connections.iter().find(|info| info.requests == 1).expect("Expected one connection with a request");
+    assert!(active.bytes_in > 0, "Expected inbound bytes to be recorded");
+    assert!(active.bytes_out > 0, "Expected outbound bytes to be recorded");
+    assert_eq!(server.registry().get(active.id).as_ref(), Some(active));
+
+    // Disconnected clients are released from the registry
+    assert!(first.disconnect().is_ok(), "Failed to disconnect from the server");
+    assert!(second.disconnect().is_ok(), "Failed to disconnect from the server");
+    assert!(
+        wait_until(Duration::from_secs(2), || server.registry().is_empty()),
+        "Expected the registry to be empty after clients disconnect"
+    );
+
+    server.stop();
+    assert!(handle.join().is_ok(), "Server thread panicked or failed to join");
+}
+
+/// Test to validate forced disconnection of a client by connection ID.
+#[test]
+fn test_force_disconnect_client() {
+    env::set_var("RUST_LOG", "debug");
+    let _ = env_logger::builder().is_test(true).try_init();
+
+    let (server, port) = create_server();
+    let handle = setup_server_thread(server.clone());
+
+    let mut client = client::Client::new("localhost", port.into(), 1000);
+    assert!(client.connect().is_ok(), "Failed to connect to the server");
+    assert!(
+        wait_until(Duration::from_secs(2), || server.connections().len() == 1),
+        "Expected one registered connection"
+    );
+
+    let id = server.connections()[0].id;
+    assert!(server.disconnect(id), "Expected the connection to be found");
+    assert!(!server.disconnect(id + 1000), "Unknown IDs must not be found");
+
+    // The kicked client observes the closed connection
+    assert!(client.receive().is_err(), "Expected the server to close the connection");
+    assert!(
+        wait_until(Duration::from_secs(2), || server.registry().get(id).is_none()),
+        "Expected the kicked connection to be unregistered"
+    );
+
+    server.stop();
+    assert!(handle.join().is_ok(), "Server thread panicked or failed to join");
+}
+
+/// Test to validate that idle connections are closed once the idle timeout expires.
+#[test]
+fn test_idle_timeout_closes_connection() {
+    env::set_var("RUST_LOG", "debug");
+    let _ = env_logger::builder().is_test(true).try_init();
+
+    let config = ServerConfig {
+        timeouts: Timeouts {
+            read: Duration::from_millis(100),
+            idle: Some(Duration::from_millis(300)),
+            handshake: None,
+            ..Timeouts::default()
+        },
+        ..ServerConfig::default()
+    };
+    let (server, port) = create_server_with_config(config);
+    let handle = setup_server_thread(server.clone());
+
+    let mut client = client::Client::new("localhost", port.into(), 2000);
+    assert!(client.connect().is_ok(), "Failed to connect to the server");
+
+    // Activity keeps the connection open
+    let echo_message = EchoMessage { content: "still here".to_string() };
+    assert!(client.send(client_message::Message::EchoMessage(echo_message)).is_ok(), "Failed to send message");
+    assert!(client.receive().is_ok(), "Failed to receive response for EchoMessage");
+
+    // Silence past the idle timeout closes it cleanly
+    let started = Instant::now();
+    let error = client.receive().expect_err("Expected the idle connection to be closed");
+    assert_eq!(error.kind(), ErrorKind::ConnectionAborted, "Expected a clean close, got: {}", error);
+    assert!(started.elapsed() < Duration::from_millis(1500), "Idle connection was not closed promptly");
+    assert!(
+        wait_until(Duration::from_secs(2), || server.registry().is_empty()),
+        "Expected the idle connection to be unregistered"
+    );
+
+    server.stop();
+    assert!(handle.join().is_ok(), "Server thread panicked or failed to join");
+}
+
+/// Test to validate that clients which never send a request are closed after the handshake timeout.
+#[test]
+fn test_handshake_timeout_closes_silent_connection() {
+    env::set_var("RUST_LOG", "debug");
+    let _ = env_logger::builder().is_test(true).try_init();
+
+    let config = ServerConfig {
+        timeouts: Timeouts {
+            read: Duration::from_millis(100),
+            idle: None,
+            handshake: Some(Duration::from_millis(200)),
+            ..Timeouts::default()
+        },
+        ..ServerConfig::default()
+    };
+    let (server, port) = create_server_with_config(config);
+    let handle = setup_server_thread(server.clone());
+
+    let mut client = client::Client::new("localhost", port.into(), 2000);
+    assert!(client.connect().is_ok(), "Failed to connect to the server");
+
+    let error = client.receive().expect_err("Expected the silent connection to be closed");
+    assert_eq!(error.kind(), ErrorKind::ConnectionAborted, "Expected a clean close, got: {}", error);
+
+    server.stop();
+    assert!(handle.join().is_ok(), "Server thread panicked or failed to join");
+}
+
+/// Test to validate that zero timeouts are rejected when the server is built.
+#[test]
+fn test_zero_timeout_is_rejected() {
+    let config = ServerConfig {
+        timeouts: Timeouts { write: Duration::ZERO, ..Timeouts::default() },
+        ..ServerConfig::default()
+    };
+    let error = Server::with_config("localhost:0", config).err().expect("Expected the config to be rejected");
+    assert_eq!(error.kind(), ErrorKind::InvalidInput);
+}
+
+/// Test to validate starting a server on port 0 and managing it through its handle.
+#[test]
+fn test_start_returns_handle() {
+    let _ = env_logger::builder().is_test(true).try_init();
+
+    let handle = Server::new("localhost:0").expect("Failed to start server").start().expect("Failed to run server");
+    let port = handle.local_addr().unwrap().port();
+    assert_ne!(port, 0, "Expected the handle to report the port chosen by the system");
+    assert_eq!(handle.status(), ServerStatus::Running);
+
+    let mut client = client::Client::new("localhost", port.into(), 1000);
+    assert!(client.connect().is_ok(), "Failed to connect to the server");
+    let echo_message = EchoMessage { content: "handle".to_string() };
+    assert!(client.send(client_message::Message::EchoMessage(echo_message)).is_ok(), "Failed to send message");
+    assert_eq!(client.receive().expect("Failed to receive response"), echo_push("handle"));
+    assert_eq!(handle.server().connections().len(), 1);
+
+    handle.stop();
+    assert_ne!(handle.status(), ServerStatus::Running);
+    assert!(wait_until(Duration::from_secs(2), || handle.status() == ServerStatus::Stopped), "Expected the server to stop");
+    assert!(handle.wait().is_ok(), "Expected a clean shutdown");
+    assert!(client.receive().is_err(), "Expected the connection to be closed on shutdown");
+}
+
+/// Utility function to build an `EchoMessage` push from the server.
+fn echo_push(content: &str) -> ServerMessage {
+    ServerMessage {
+        message: Some(server_message::Message::EchoMessage(EchoMessage { content: content.to_string() })),
+    }
+}
+
+/// Test to validate that the server can push unsolicited messages to a connection.
+#[test]
+fn test_server_push_to_connection() {
+    env::set_var("RUST_LOG", "debug");
+    let _ = env_logger::builder().is_test(true).try_init();
+
+    let (server, port) = create_server();
+    let handle = setup_server_thread(server.clone());
+
+    let mut client = client::Client::new("localhost", port.into(), 1000);
+    assert!(client.connect().is_ok(), "Failed to connect to the server");
+    assert!(
+        wait_until(Duration::from_secs(2), || server.connections().len() == 1),
+        "Expected one registered connection"
+    );
+    let id = server.connections()[0].id;
+
+    // Two pushes arrive in order, without the client asking for them
+    assert_eq!(server.send(id, echo_push("first")), Ok(()));
+    assert_eq!(server.send(id, echo_push("second")), Ok(()));
+    assert_eq!(client.receive().expect("Failed to receive first push"), echo_push("first"));
+    assert_eq!(client.receive().expect("Failed to receive second push"), echo_push("second"));
+
+    // Replies still work alongside pushes
+    let echo_message = EchoMessage { content: "request".to_string() };
+    assert!(client.send(client_message::Message::EchoMessage(echo_message)).is_ok(), "Failed to send message");
+    assert_eq!(client.receive().expect("Failed to receive reply"), echo_push("request"));
+
+    assert_eq!(server.send(id + 1000, echo_push("nobody")), Err(PushError::UnknownConnection));
+
+    assert!(client.disconnect().is_ok(), "Failed to disconnect from the server");
+    server.stop();
+    assert!(handle.join().is_ok(), "Server thread panicked or failed to join");
+}
+
+/// Utility function to push large messages to a client that never reads until the queue overflows.
+///
+/// # Returns
+/// - The error reported by the push that overflowed, and the time that push took.
+fn overflow_outbound_queue(policy: SlowConsumerPolicy) -> (Arc<Server>, JoinHandle<()>, client::Client, PushError, Duration) {
+    let config = ServerConfig {
+        outbound: OutboundConfig { capacity: 2, policy },
+        ..ServerConfig::default()
+    };
+    let (server, port) = create_server_with_config(config);
+    let handle = setup_server_thread(server.clone());
+
+    let mut client = client::Client::new("localhost", port.into(), 1000);
+    assert!(client.connect().is_ok(), "Failed to connect to the server");
+    assert!(
+        wait_until(Duration::from_secs(2), || server.connections().len() == 1),
+        "Expected one registered connection"
+    );
+    let id = server.connections()[0].id;
+
+    let payload = "x".repeat(32 * 1024);
+    for _ in 0..4096 {
+        let started = Instant::now();
+        if let Err(e) = server.send(id, echo_push(&payload)) {
+            return (server, handle, client, e, started.elapsed());
+        }
+    }
+    panic!("The outbound queue never filled up");
+}
+
+/// Test to validate the drop policy for slow consumers.
+#[test]
+fn test_slow_consumer_drop_policy() {
+    let _ = env_logger::builder().is_test(true).try_init();
+
+    let (server, handle, mut client, error, _) = overflow_outbound_queue(SlowConsumerPolicy::Drop);
+    assert_eq!(error, PushError::Dropped);
+    assert_eq!(server.connections().len(), 1, "Dropping messages must keep the connection open");
+
+    assert!(client.disconnect().is_ok(), "Failed to disconnect from the server");
+    server.stop();
+    assert!(handle.join().is_ok(), "Server thread panicked or failed to join");
+}
+
+/// Test to validate the disconnect policy for slow consumers.
+#[test]
+fn test_slow_consumer_disconnect_policy() {
+    let _ = env_logger::builder().is_test(true).try_init();
+
+    let (server, handle, _client, error, _) = overflow_outbound_queue(SlowConsumerPolicy::Disconnect);
+    assert_eq!(error, PushError::Disconnected);
+    assert!(
+        wait_until(Duration::from_secs(2), || server.registry().is_empty()),
+        "Expected the slow consumer to be disconnected"
+    );
+
+    server.stop();
+    assert!(handle.join().is_ok(), "Server thread panicked or failed to join");
+}
+
+/// Test to validate the blocking policy for slow consumers.
+#[test]
+fn test_slow_consumer_block_policy() {
+    let _ = env_logger::builder().is_test(true).try_init();
+
+    let timeout = Duration::from_millis(200);
+    let (server, handle, mut client, error, elapsed) = overflow_outbound_queue(SlowConsumerPolicy::Block(timeout));
+    assert_eq!(error, PushError::TimedOut);
+    assert!(elapsed >= timeout, "Expected the push to wait for space, waited {:?}", elapsed);
+
+    assert!(client.disconnect().is_ok(), "Failed to disconnect from the server");
+    server.stop();
+    assert!(handle.join().is_ok(), "Server thread panicked or failed to join");
+}
+
+/// Test to validate that pipelined requests are all answered, in order, under a tight in-flight limit.
+#[test]
+fn test_pipelined_requests_respect_in_flight_limit() {
+    let _ = env_logger::builder().is_test(true).try_init();
+
+    let config = ServerConfig { max_in_flight: 1, ..ServerConfig::default() };
+    let (server, port) = create_server_with_config(config);
+    let handle = setup_server_thread(server.clone());
+
+    let mut client = client::Client::new("localhost", port.into(), 2000);
+    assert!(client.connect().is_ok(), "Failed to connect to the server");
+    for index in 0..50 {
+        let echo_message = EchoMessage { content: format!("request {}", index) };
+        assert!(client.send(client_message::Message::EchoMessage(echo_message)).is_ok(), "Failed to send message");
+    }
+    for index in 0..50 {
+        let response = client.receive().expect("Failed to receive pipelined response");
+        assert_eq!(response, echo_push(&format!("request {}", index)));
+    }
+    assert_eq!(server.connections()[0].requests, 50);
+    assert!(
+        wait_until(Duration::from_secs(2), || server.connections()[0].in_flight == 0),
+        "Expected every request to complete once its reply was written"
+    );
+
+    assert!(client.disconnect().is_ok(), "Failed to disconnect from the server");
+    server.stop();
+    assert!(handle.join().is_ok(), "Server thread panicked or failed to join");
+}
+
+/// Test to validate that a producer that never reads its replies is throttled without starving other clients.
+#[test]
+fn test_fast_producer_cannot_starve_other_clients() {
+    let _ = env_logger::builder().is_test(true).try_init();
+
+    let config = ServerConfig { max_in_flight: 4, ..ServerConfig::default() };
+    let (server, port) = create_server_with_config(config);
+    let handle = setup_server_thread(server.clone());
+
+    // Pipeline large requests and never read the replies
+    let mut stream = TcpStream::connect(("localhost", port)).expect("Failed to connect to the server");
+    stream.set_write_timeout(Some(Duration::from_millis(500))).unwrap();
+    let frame = ClientMessage {
+        message: Some(client_message::Message::EchoMessage(EchoMessage { content: "x".repeat(60 * 1024) })),
+    }
+    .encode_length_delimited_to_vec();
+    let producer = thread::spawn(move || {
+        for _ in 0..4096 {
+            if let Err(e) = stream.write_all(&frame) {
+                return (stream, Some(e));
+            }
+        }
+        (stream, None)
+    });
+
+    // Other clients keep getting prompt answers while the producer is throttled
+    let mut client = client::Client::new("localhost", port.into(), 2000);
+    assert!(client.connect().is_ok(), "Failed to connect to the server");
+    for index in 0..20 {
+        let started = Instant::now();
+        let echo_message = EchoMessage { content: format!("request {}", index) };
+        assert!(client.send(client_message::Message::EchoMessage(echo_message)).is_ok(), "Failed to send message");
+        assert_eq!(client.receive().expect("Failed to receive response"), echo_push(&format!("request {}", index)));
+        assert!(started.elapsed() < Duration::from_secs(1), "Request {} waited {:?}", index, started.elapsed());
+        thread::sleep(Duration::from_millis(25));
+    }
+
+    // The server stopped reading, so TCP flow control blocked the producer
+    let (producer_stream, error) = producer.join().unwrap();
+    let error = error.expect("Expected the producer's writes to block");
+    assert!(
+        matches!(error.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut),
+        "Expected a write timeout, got: {}",
+        error
+    );
+    let producer_id = server.connections()[0].id;
+    let paused = server.registry().get(producer_id).expect("Expected the producer to stay connected");
+    assert_eq!(paused.in_flight, 4, "Expected the producer to be held at its in-flight limit");
+    thread::sleep(Duration::from_millis(200));
+    assert_eq!(server.registry().get(producer_id).unwrap().requests, paused.requests, "Expected reading to stay paused");
+
+    // Once the producer reads its replies, the server resumes and records the time it was throttled
+    let mut producer_stream = producer_stream;
+    let drain = thread::spawn(move || std::io::copy(&mut producer_stream, &mut std::io::sink()));
+    assert!(
+        wait_until(Duration::from_secs(5), || {
+            server.registry().get(producer_id).is_some_and(|info| info.requests > paused.requests)
+        }),
+        "Expected the server to resume reading"
+    );
+    assert!(!server.registry().get(producer_id).unwrap().throttled.is_zero(), "Expected throttled time per connection");
+    assert!(!server.throttled_time().is_zero(), "Expected throttled time across the server");
+
+    assert!(client.disconnect().is_ok(), "Failed to disconnect from the server");
+    server.stop();
+    assert!(handle.join().is_ok(), "Server thread panicked or failed to join");
+    let _ = drain.join();
+}
+
+/// Test to validate that SO_REUSEPORT acceptors share incoming connections.
+#[test]
+#[cfg(unix)]
+fn test_reuseport_acceptors_share_connections() {
+    env::set_var("RUST_LOG", "debug");
+    let _ = env_logger::builder().is_test(true).try_init();
+
+    let acceptors = 4;
+    let config = ServerConfig { acceptors, ..ServerConfig::default() };
+    let (server, port) = create_server_with_config(config);
+    let handle = setup_server_thread(server.clone());
+    assert_eq!(server.accepted_per_acceptor().len(), acceptors);
+
+    // Each client uses a different source port, so the kernel hashes them across listeners
+    let client_count = 64;
+    let mut clients: Vec<client::Client> = (0..client_count)
+        .map(|_| client::Client::new("localhost", port.into(), 1000))
+        .collect();
+    for client in clients.iter_mut() {
+        assert!(client.connect().is_ok(), "Failed to connect to the server");
+    }
+    assert!(
+        wait_until(Duration::from_secs(5), || server.accepted_per_acceptor().iter().sum::<u64>() == client_count),
+        "Expected every connection to be accepted"
+    );
+
+    let counts = server.accepted_per_acceptor();
+    let busy = counts.iter().filter(|count| **count > 0).count();
+    assert!(busy > 1, "Expected connections on several acceptors, got {:?}", counts);
+
+    // Connections accepted on any listener are fully served
+    for client in clients.iter_mut() {
+        let echo_message = EchoMessage { content: "spread".to_string() };
+        assert!(client.send(client_message::Message::EchoMessage(echo_message)).is_ok(), "Failed to send message");
+        assert!(client.receive().is_ok(), "Failed to receive response for EchoMessage");
+        assert!(client.disconnect().is_ok(), "Failed to disconnect from the server");
+    }
+
+    server.stop();
+    assert!(handle.join().is_ok(), "Server thread panicked or failed to join");
+}
+
+/// Test to validate that a server without acceptors is rejected.
+#[test]
+fn test_zero_acceptors_is_rejected() {
+    let config = ServerConfig { acceptors: 0, ..ServerConfig::default() };
+    let error = Server::with_config("localhost:0", config).err().expect("Expected the config to be rejected");
+    assert_eq!(error.kind(), ErrorKind::InvalidInput);
+}
+
+/// Utility function to build a fully populated set of socket options.
+fn tuned_socket_options() -> SocketOptions {
+    SocketOptions {
+        nodelay: Some(true),
+        keepalive: Some(Setting::On(KeepAlive {
+            idle: Duration::from_secs(30),
+            interval: Some(Duration::from_secs(5)),
+            retries: Some(3),
+        })),
+        recv_buffer_size: Some(64 * 1024),
+        send_buffer_size: Some(64 * 1024),
+        linger: Some(Setting::On(Duration::from_secs(1))),
+        backlog: 16,
+        only_v6: None,
+    }
+}
+
+/// Utility function to check that `socket` carries the options from `tuned_socket_options`.
+fn assert_tuned(socket: &socket2::SockRef<'_>, side: &str) {
+    assert!(socket.nodelay().unwrap(), "Expected TCP_NODELAY on the {}", side);
+    assert!(socket.keepalive().unwrap(), "Expected SO_KEEPALIVE on the {}", side);
+    assert_eq!(socket.linger().unwrap(), Some(Duration::from_secs(1)), "Expected SO_LINGER on the {}", side);
+    assert!(socket.recv_buffer_size().unwrap() >= 64 * 1024, "Expected SO_RCVBUF on the {}", side);
+    assert!(socket.send_buffer_size().unwrap() >= 64 * 1024, "Expected SO_SNDBUF on the {}", side);
+}
+
+/// Test to validate that socket options are applied on the server's listener, its accepted streams and the test client.
+#[test]
+fn test_socket_options_applied() {
+    env::set_var("RUST_LOG", "debug");
+    let _ = env_logger::builder().is_test(true).try_init();
+
+    let config = ServerConfig { socket: tuned_socket_options(), ..ServerConfig::default() };
+    let (server, port) = create_server_with_config(config);
+    let handle = setup_server_thread(server.clone());
+
+    let mut client = client::Client::new("localhost", port.into(), 1000);
+    client.set_socket_options(tuned_socket_options());
+    assert!(client.connect().is_ok(), "Failed to connect to the server");
+
+    // The options are visible on the client's socket
+    assert_tuned(&socket2::SockRef::from(client.stream().expect("Expected an active connection")), "client");
+
+    // And on the server's listener and the stream it accepted
+    match server.listeners()[0] {
+        Listener::Tcp(listener) => assert_tuned(&socket2::SockRef::from(listener), "listener"),
+        #[cfg(unix)]
+        Listener::Unix(_) => panic!("Expected a TCP listener"),
+    }
+    assert!(wait_until(Duration::from_secs(2), || server.connections().len() == 1), "Expected the connection to be registered");
+    let id = server.connections()[0].id;
+    let accepted = server.connection_stream(id).unwrap().expect("Expected the connection to be live");
+    assert_tuned(&socket2::SockRef::from(accepted.as_tcp().expect("Expected a TCP stream")), "accepted stream");
+
+    // The tuned server still serves requests
+    let echo_message = EchoMessage { content: "tuned".to_string() };
+    assert!(client.send(client_message::Message::EchoMessage(echo_message)).is_ok(), "Failed to send message");
+    assert!(client.receive().is_ok(), "Failed to receive response for EchoMessage");
+
+    assert!(client.disconnect().is_ok(), "Failed to disconnect from the server");
+    server.stop();
+    assert!(handle.join().is_ok(), "Server thread panicked or failed to join");
+}
+
+/// Test to validate that unusable socket options are rejected when the server is built.
+#[test]
+fn test_invalid_socket_options_are_rejected() {
+    let invalid = [
+        SocketOptions { backlog: 0, ..SocketOptions::default() },
+        SocketOptions { recv_buffer_size: Some(0), ..SocketOptions::default() },
+        SocketOptions {
+            keepalive: Some(Setting::On(KeepAlive { idle: Duration::ZERO, interval: None, retries: None })),
+            ..SocketOptions::default()
+        },
+    ];
+    for socket in invalid {
+        let config = ServerConfig { socket, ..ServerConfig::default() };
+        let error = Server::with_config("localhost:0", config).err().expect("Expected the config to be rejected");
+        assert_eq!(error.kind(), ErrorKind::InvalidInput);
+    }
+}
+
+/// Utility function to check that a freshly connected client is refused by the server.
+fn assert_connection_refused(port: u16) {
+    let mut client = client::Client::new("localhost", port.into(), 1000);
+    assert!(client.connect().is_ok(), "The TCP handshake completes before the server refuses");
+    let error = client.receive().expect_err("Expected the server to refuse the connection");
+    assert_eq!(error.kind(), ErrorKind::ConnectionAborted, "Expected a closed connection, got: {}", error);
+}
+
+/// Test to validate that reloaded settings apply without dropping open connections.
+#[test]
+fn test_reload_applies_live_settings() {
+    env::set_var("RUST_LOG", "debug");
+    let _ = env_logger::builder().is_test(true).try_init();
+
+    let base = ServerConfig {
+        timeouts: Timeouts { read: Duration::from_millis(100), ..Timeouts::default() },
+        ..ServerConfig::default()
+    };
+    let (server, port) = create_server_with_config(base.clone());
+    let handle = setup_server_thread(server.clone());
+
+    let mut client = client::Client::new("localhost", port.into(), 2000);
+    assert!(client.connect().is_ok(), "Failed to connect to the server");
+    assert!(
+        wait_until(Duration::from_secs(2), || server.connections().len() == 1),
+        "Expected one registered connection"
+    );
+
+    // Deny the loopback range: new clients are refused, the open one keeps working
+    let denied = ServerConfig {
+        access: AccessList { allow: Vec::new(), deny: vec!["127.0.0.0/8".parse::<IpNet>().unwrap()] },
+        ..base.clone()
+    };
+    assert_eq!(server.reload(denied).expect("Reload failed"), Vec::<&str>::new());
+    assert_connection_refused(port);
+    let echo_message = EchoMessage { content: "still served".to_string() };
+    assert!(client.send(client_message::Message::EchoMessage(echo_message)).is_ok(), "Failed to send message");
+    assert!(client.receive().is_ok(), "Failed to receive response for EchoMessage");
+
+    // A connection limit refuses clients beyond it
+    let limited = ServerConfig { max_connections: Some(1), ..base.clone() };
+    assert!(server.reload(limited).is_ok(), "Reload failed");
+    assert_connection_refused(port);
+
+    // Shorter timeouts reach the open connection
+    let idle = ServerConfig {
+        timeouts: Timeouts { idle: Some(Duration::from_millis(200)), ..base.timeouts },
+        ..base.clone()
+    };
+    assert!(server.reload(idle).is_ok(), "Reload failed");
+    let error = client.receive().expect_err("Expected the reloaded idle timeout to close the connection");
+    assert_eq!(error.kind(), ErrorKind::ConnectionAborted);
+
+    server.stop();
+    assert!(handle.join().is_ok(), "Server thread panicked or failed to join");
+}
+
+/// Test to validate that restart-only settings are reported and invalid reloads are rejected.
+#[test]
+fn test_reload_reports_restart_required_settings() {
+    let (server, _port) = create_server();
+
+    let changed = ServerConfig { acceptors: 2, max_frame_len: 1024, ..ServerConfig::default() };
+    assert_eq!(server.reload(changed).expect("Reload failed"), vec!["acceptors"]);
+    assert_eq!(server.config().acceptors, 1, "Restart-only settings must keep their value");
+    assert_eq!(server.config().max_frame_len, 1024, "Reloadable settings must be applied");
+
+    let invalid = ServerConfig { outbound: OutboundConfig { capacity: 0, ..OutboundConfig::default() }, ..ServerConfig::default() };
+    assert_eq!(server.reload(invalid).err().map(|e| e.kind()), Some(ErrorKind::InvalidInput));
+    assert_eq!(server.config().max_frame_len, 1024, "A rejected reload must not change the settings");
+}
+
+/// Utility function to create a server whose echo handler panics on the content `panic`.
+///
+/// # Returns
+/// - A tuple containing the `Arc`-wrapped server instance and the port number it is bound to.
+fn create_panicking_server(config: ServerConfig) -> (Arc<Server>, u16) {
+    let mut server = Server::with_config("localhost:0", config).expect("Failed to start server");
+    server.register_handler(MessageKind::EchoMessage, |request: client_message::Message, context: &Context| -> HandlerResult {
+        if matches!(&request, client_message::Message::EchoMessage(echo) if echo.content == "panic") {
+            panic!("a broken handler");
+        }
+        Echo.handle(request, context)
+    });
+    let port = server.local_addr().unwrap().port();
+    (Arc::new(server), port)
+}
+
+/// Utility function to send a request that panics the handler.
+///
+/// # Returns
+/// - The code of the error response the server sent instead of a reply.
+fn send_panicking_request(client: &mut client::Client) -> ErrorCode {
+    let echo_message = EchoMessage { content: "panic".to_string() };
+    assert!(client.send(client_message::Message::EchoMessage(echo_message)).is_ok(), "Failed to send message");
+    match client.receive().expect("Expected a response to the panicking request").message {
+        Some(server_message::Message::ErrorResponse(error)) => error.code(),
+        other => panic!("Expected an ErrorResponse, but received {:?}", other),
+    }
+}
+
+/// Test to validate that a panicking handler yields an `Internal` error and closes the connection by default.
+#[test]
+fn test_handler_panic_closes_connection() {
+    let _ = env_logger::builder().is_test(true).try_init();
+
+    let (server, port) = create_panicking_server(ServerConfig::default());
+    let handle = setup_server_thread(server.clone());
+
+    let mut client = client::Client::new("localhost", port.into(), 1000);
+    assert!(client.connect().is_ok(), "Failed to connect to the server");
+    assert_eq!(send_panicking_request(&mut client), ErrorCode::Internal);
+    let error = client.receive().expect_err("Expected the connection to be closed after the panic");
+    assert_eq!(error.kind(), ErrorKind::ConnectionAborted, "Expected a clean close, got: {}", error);
+    assert_eq!(server.handler_panics(), 1);
+
+    // Other connections are unaffected
+    let mut other = client::Client::new("localhost", port.into(), 1000);
+    assert!(other.connect().is_ok(), "Failed to connect to the server");
+    let echo_message = EchoMessage { content: "unaffected".to_string() };
+    assert!(other.send(client_message::Message::EchoMessage(echo_message)).is_ok(), "Failed to send message");
+    assert_eq!(other.receive().expect("Failed to receive response for EchoMessage"), echo_push("unaffected"));
+
+    assert!(other.disconnect().is_ok(), "Failed to disconnect from the server");
+    server.stop();
+    assert!(handle.join().is_ok(), "Server thread panicked or failed to join");
+}
+
+/// Test to validate that the keep-open panic policy keeps serving the connection.
+#[test]
+fn test_handler_panic_keep_open_policy() {
+    let _ = env_logger::builder().is_test(true).try_init();
+
+    let config = ServerConfig { on_panic: PanicPolicy::KeepOpen, ..ServerConfig::default() };
+    let (server, port) = create_panicking_server(config);
+    let handle = setup_server_thread(server.clone());
+
+    let mut client = client::Client::new("localhost", port.into(), 1000);
+    assert!(client.connect().is_ok(), "Failed to connect to the server");
+    for _ in 0..2 {
+        assert_eq!(send_panicking_request(&mut client), ErrorCode::Internal);
+    }
+    let add_request = AddRequest { a: 2, b: 3 };
+    assert!(client.send(client_message::Message::AddRequest(add_request)).is_ok(), "Failed to send message");
+    match client.receive().expect("Failed to receive response for AddRequest").message {
+        Some(server_message::Message::AddResponse(add_response)) => assert_eq!(add_response.result, 5),
+        other => panic!("Expected AddResponse, but received {:?}", other),
+    }
+    assert_eq!(server.handler_panics(), 2);
+
+    assert!(client.disconnect().is_ok(), "Failed to disconnect from the server");
+    server.stop();
+    assert!(handle.join().is_ok(), "Server thread panicked or failed to join");
+}
+
+/// Test to validate configuration file parsing.
+#[test]
+fn test_config_file_parsing() {
+    let text = "
+        # Device gateway
+        address = 0.0.0.0:9000
+        log_level = debug
+        idle_timeout_ms = 0
+        read_timeout_ms = 250
+        max_connections = 100
+        max_in_flight = 8
+        allow = 10.0.0.0/8, 192.168.1.7
+        slow_consumer = block:50
+        tcp_nodelay = true
+        on_panic = keep_open
+        subscriber_queue = 32
+    ";
+    let file = ConfigFile::parse(text).expect("Failed to parse the configuration");
+    assert_eq!(file.listeners, vec![ListenerConfig::new(ListenAddr::Tcp("0.0.0.0:9000".to_string()))]);
+    assert_eq!(file.log_level, Some(log::LevelFilter::Debug));
+    assert_eq!(ConfigFile::parse("address = 0.0.0.0:9000").unwrap().log_level, None, "Expected RUST_LOG to apply");
+    assert_eq!(file.server.timeouts.idle, None);
+    assert_eq!(file.server.timeouts.read, Duration::from_millis(250));
+    assert_eq!(file.server.max_connections, Some(100));
+    assert_eq!(file.server.max_in_flight, 8);
+    assert!(file.server.access.permits("10.1.2.3".parse().unwrap()));
+    assert!(!file.server.access.permits("192.168.1.8".parse().unwrap()));
+    assert_eq!(file.server.outbound.policy, SlowConsumerPolicy::Block(Duration::from_millis(50)));
+    assert_eq!(file.server.socket.nodelay, Some(true));
+    assert_eq!(file.server.on_panic, PanicPolicy::KeepOpen);
+    assert_eq!(file.server.subscriber_queue, 32);
+
+    let error = ConfigFile::parse("address = x\nbogus = 1").expect_err("Expected an unknown key to be rejected");
+    assert!(error.to_string().contains("line 2"), "Expected the line number in: {}", error);
+    assert!(ConfigFile::parse("read_timeout_ms = 0").is_err(), "Expected validation to run");
+}
@@ -0,0 +1,248 @@
+use embedded_recruitment_task::{
+    config::{ConfigFile, OutboundConfig, PoolConfig, ServerConfig}, // Importing server settings
+    executor::{Pool, SubmitError}, // Importing executor pools
+    handler::{Context, HandlerResult}, // Importing request handlers
+    message::{client_message, server_message, AddRequest, EchoMessage, ErrorCode}, // Importing message types for client-server communication
+    router::MessageKind, // Importing message types assigned to pools
+    server::Server, // Importing server functionalities
+    socket::SocketOptions, // Importing TCP socket tuning
+};
+use std::{
+    sync::mpsc, // For holding pool workers busy
+    time::{Duration, Instant}, // For polling timeouts and round-trip times
+};
+
+#[allow(dead_code)] // Not every client helper is used by this suite
+mod client; // Declares a client module for client-related operations
+
+use client::wait_until; // Polls for asynchronous server effects
+
+/// Utility function to send an AddRequest and return the sum, or the error code of an error reply.
+fn add(client: &mut client::Client, a: i32, b: i32) -> Result<i32, ErrorCode> {
+    let add_request = AddRequest { a, b };
+    assert!(client.send(client_message::Message::AddRequest(add_request)).is_ok(), "Failed to send message");
+    match client.receive().expect("Failed to receive response for AddRequest").message {
+        Some(server_message::Message::AddResponse(add_response)) => Ok(add_response.result),
+        Some(server_message::Message::ErrorResponse(error)) => Err(error.code()),
+        other => panic!("Expected AddResponse, but received {:?}", other),
+    }
+}
+
+/// Utility function to run an echo round trip.
+fn echo(client: &mut client::Client, content: &str) {
+    let echo_message = EchoMessage { content: content.to_string() };
+    assert!(client.send(client_message::Message::EchoMessage(echo_message.clone())).is_ok(), "Failed to send message");
+    match client.receive().expect("Failed to receive response for EchoMessage").message {
+        Some(server_message::Message::EchoMessage(echoed)) => assert_eq!(echoed, echo_message),
+        other => panic!("Expected EchoMessage, but received {:?}", other),
+    }
+}
+
+/// Test to validate that a full pool rejects work and reports its saturation.
+#[test]
+fn test_pool_rejects_when_full_and_reports_saturation() {
+    let pool = Pool::new(&PoolConfig::new("blocking", 1, 1)).expect("Failed to start the pool");
+    let (release, gate) = mpsc::channel::<()>();
+    let (started, running) = mpsc::channel();
+    let gate = std::sync::Arc::new(std::sync::Mutex::new(gate));
+    for _ in 0..2 {
+        let (gate, started) = (gate.clone(), started.clone());
+        let job = move || {
+            started.send(()).unwrap();
+            gate.lock().unwrap().recv().unwrap(); // Hold the worker until released.
+        };
+        assert!(pool.submit(job).is_ok(), "Expected room for one running and one queued job");
+    }
+    running.recv_timeout(Duration::from_secs(2)).expect("Expected the first job to start");
+    assert_eq!(pool.submit(|| {}), Err(SubmitError::Full));
+
+    std::thread::sleep(Duration::from_millis(50));
+    let stats = pool.stats();
+    assert_eq!((stats.busy, stats.queued, stats.rejected), (1, 1, 1));
+    assert_eq!(stats.peak_queued, 1);
+    assert!(stats.saturated >= Duration::from_millis(50), "Expected saturation time, got {:?}", stats.saturated);
+
+    release.send(()).unwrap();
+    release.send(()).unwrap();
+    assert!(wait_until(Duration::from_secs(2), || pool.stats().completed == 2), "Expected both jobs to finish");
+    let stats = pool.stats();
+    assert_eq!((stats.busy, stats.queued), (0, 0));
+}
+
+/// Test to validate that assigned message types run on their pool and others stay on the connection thread.
+#[test]
+fn test_requests_are_routed_to_their_pool() {
+    let _ = env_logger::builder().is_test(true).try_init();
+
+    let config = ServerConfig {
+        pools: vec![PoolConfig::new("math", 2, 8).handling(&[MessageKind::AddRequest])],
+        ..ServerConfig::default()
+    };
+    let handle = Server::with_config("127.0.0.1:0", config).unwrap().start().expect("Failed to run server");
+    let mut client = client::Client::new("127.0.0.1", handle.local_addr().unwrap().port().into(), 2000);
+    assert!(client.connect().is_ok(), "Failed to connect to the server");
+
+    for i in 0..5 {
+        assert_eq!(add(&mut client, i, 10), Ok(i + 10));
+        echo(&mut client, "inline");
+    }
+    let stats = handle.server().pool_stats();
+    assert_eq!(stats.len(), 1);
+    assert_eq!(stats[0].name, "math");
+    assert!(
+        wait_until(Duration::from_secs(2), || handle.server().pool_stats()[0].completed == 5),
+        "Expected only the AddRequests on the pool"
+    );
+    assert_eq!(stats[0].rejected, 0);
+
+    assert!(client.disconnect().is_ok(), "Failed to disconnect from the server");
+    handle.stop();
+    assert!(handle.wait().is_ok(), "Expected a clean shutdown");
+}
+
+/// Test to validate that a pool without queue space sheds requests while echoes keep flowing.
+#[test]
+fn test_saturated_pool_does_not_block_other_messages() {
+    let _ = env_logger::builder().is_test(true).try_init();
+
+    let config = ServerConfig {
+        max_in_flight: 1024,
+        pools: vec![PoolConfig::new("math", 1, 0).handling(&[MessageKind::AddRequest])],
+        ..ServerConfig::default()
+    };
+    let handle = Server::with_config("127.0.0.1:0", config).unwrap().start().expect("Failed to run server");
+    let port = handle.local_addr().unwrap().port();
+
+    // Pipeline a burst of additions; the single worker cannot keep up with all of them
+    let mut producer = client::Client::new("127.0.0.1", port.into(), 5000);
+    assert!(producer.connect().is_ok(), "Failed to connect to the server");
+    let burst = 500;
+    for i in 0..burst {
+        let add_request = AddRequest { a: i, b: 1 };
+        assert!(producer.send(client_message::Message::AddRequest(add_request)).is_ok(), "Failed to send message");
+    }
+    let mut cheap = client::Client::new("127.0.0.1", port.into(), 2000);
+    assert!(cheap.connect().is_ok(), "Failed to connect to the server");
+    echo(&mut cheap, "still fast");
+
+    let (mut answered, mut overloaded) = (0, 0);
+    for _ in 0..burst {
+        match producer.receive().expect("Expected a reply to every request").message {
+            Some(server_message::Message::AddResponse(_)) => answered += 1,
+            Some(server_message::Message::ErrorResponse(error)) => {
+                assert_eq!(error.code(), ErrorCode::Overloaded);
+                overloaded += 1;
+            }
+            other => panic!("Expected AddResponse or ErrorResponse, but received {:?}", other),
+        }
+    }
+    assert!(answered > 0, "Expected the pool to answer some requests");
+    // Workers count a job once it returns, just after its reply is queued
+    assert!(wait_until(Duration::from_secs(2), || handle.server().pool_stats()[0].completed == answered));
+    assert_eq!(handle.server().pool_stats()[0].rejected, overloaded, "Expected every rejection to be counted");
+
+    handle.stop();
+    assert!(handle.wait().is_ok(), "Expected a clean shutdown");
+}
+
+/// Test to validate that a client that stops reading its replies does not hold up a pool shared with other clients.
+#[test]
+fn test_stalled_client_does_not_block_shared_pool() {
+    let _ = env_logger::builder().is_test(true).try_init();
+
+    let config = ServerConfig {
+        max_frame_len: 1024 * 1024,
+        socket: SocketOptions { send_buffer_size: Some(4096), ..SocketOptions::default() },
+        outbound: OutboundConfig { capacity: 1, ..OutboundConfig::default() },
+        pools: vec![PoolConfig::new("echo", 1, 64).handling(&[MessageKind::EchoMessage])],
+        ..ServerConfig::default()
+    };
+    let handle = Server::with_config("127.0.0.1:0", config).unwrap().start().expect("Failed to run server");
+    let port = handle.local_addr().unwrap().port();
+
+    // Large replies the client never reads fill the socket buffers and its outbound queue
+    let mut stalled = client::Client::new("127.0.0.1", port.into(), 5000);
+    stalled.set_socket_options(SocketOptions { recv_buffer_size: Some(4096), ..SocketOptions::default() });
+    assert!(stalled.connect().is_ok(), "Failed to connect to the server");
+    for _ in 0..4 {
+        let echo_message = EchoMessage { content: "x".repeat(64 * 1024) };
+        assert!(stalled.send(client_message::Message::EchoMessage(echo_message)).is_ok(), "Failed to send message");
+    }
+    assert!(
+        wait_until(Duration::from_secs(2), || handle.server().pool_stats()[0].completed == 4),
+        "Expected the pool to answer every stalled request without waiting for the client"
+    );
+
+    // The pool's only worker is free for other clients straight away
+    let mut client = client::Client::new("127.0.0.1", port.into(), 2000);
+    assert!(client.connect().is_ok(), "Failed to connect to the server");
+    let started = Instant::now();
+    echo(&mut client, "not stuck behind a slow reader");
+    assert!(started.elapsed() < Duration::from_secs(1), "Echo waited {:?}", started.elapsed());
+
+    assert!(client.disconnect().is_ok(), "Failed to disconnect from the server");
+    assert!(stalled.disconnect().is_ok(), "Failed to disconnect from the server");
+    handle.stop();
+    assert!(handle.wait().is_ok(), "Expected a clean shutdown");
+}
+
+/// Test to validate that a panic on a pool worker answers with `Internal` and closes the connection.
+#[test]
+fn test_pooled_handler_panic_closes_connection() {
+    let _ = env_logger::builder().is_test(true).try_init();
+
+    let config = ServerConfig {
+        pools: vec![PoolConfig::new("math", 1, 4).handling(&[MessageKind::AddRequest])],
+        ..ServerConfig::default()
+    };
+    let mut server = Server::with_config("127.0.0.1:0", config).unwrap();
+    server.register_handler(MessageKind::AddRequest, |_: client_message::Message, _: &Context| -> HandlerResult {
+        panic!("a broken handler");
+    });
+    let handle = server.start().expect("Failed to run server");
+    let mut client = client::Client::new("127.0.0.1", handle.local_addr().unwrap().port().into(), 2000);
+    assert!(client.connect().is_ok(), "Failed to connect to the server");
+
+    assert_eq!(add(&mut client, 1, 2), Err(ErrorCode::Internal));
+    assert!(client.receive().is_err(), "Expected the connection to be closed after the panic");
+    assert_eq!(handle.server().handler_panics(), 1);
+    assert!(
+        wait_until(Duration::from_secs(2), || handle.server().pool_stats()[0].completed == 1),
+        "Expected the worker to survive the panic"
+    );
+
+    handle.stop();
+    assert!(handle.wait().is_ok(), "Expected a clean shutdown");
+}
+
+/// Test to validate executor pool settings in configuration files.
+#[test]
+fn test_config_file_pools() {
+    let text = "
+        pool = math threads=4 queue=16 messages=add_request
+        pool = chat messages=echo_message
+    ";
+    let file = ConfigFile::parse(text).expect("Failed to parse the configuration");
+    assert_eq!(
+        file.server.pools,
+        vec![
+            PoolConfig::new("math", 4, 16).handling(&[MessageKind::AddRequest]),
+            PoolConfig::new("chat", 1, 64).handling(&[MessageKind::EchoMessage]),
+        ]
+    );
+
+    let invalid = [
+        "pool =",
+        "pool = math threads=0",
+        "pool = math bogus=1",
+        "pool = a messages=add_request\npool = b messages=add_request",
+        "pool = a\npool = a",
+    ];
+    for text in invalid {
+        assert!(ConfigFile::parse(text).is_err(), "Expected `{}` to be rejected", text);
+    }
+
+    // Message types are names of the server's protocol, checked when the pools start
+    let file = ConfigFile::parse("pool = math messages=teleport").expect("Failed to parse the configuration");
+    assert!(Server::with_config("127.0.0.1:0", file.server).is_err(), "Expected an unknown message type to be rejected");
+}
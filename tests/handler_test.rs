@@ -0,0 +1,131 @@
+use embedded_recruitment_task::{
+    config::{PoolConfig, ServerConfig}, // Importing server settings
+    handler::{self, Add, Context, Echo, Handler, HandlerError, HandlerResult}, // Importing request handlers
+    message::{client_message, server_message, AddRequest, AddResponse, EchoMessage, ErrorCode, ServerMessage}, // Importing message types for client-server communication
+    router::MessageKind, // Importing message types handlers are registered for
+    server::Server, // Importing server functionalities
+    stream::Endpoint, // Importing connection addresses
+};
+use std::sync::{Arc, Mutex}; // For recording what handlers saw
+
+#[allow(dead_code)] // Not every client helper is used by this suite
+mod client; // Declares a client module for client-related operations
+
+/// A handler that echoes requests in upper case and records the connections it served.
+struct Shout {
+    seen: Arc<Mutex<Vec<(u64, Endpoint)>>>,
+}
+
+impl Handler for Shout {
+    fn handle(&self, request: client_message::Message, context: &Context) -> HandlerResult {
+        self.seen.lock().unwrap().push((context.connection_id(), context.peer_addr().clone()));
+        match request {
+            client_message::Message::EchoMessage(echo) => Ok(Some(server_message::Message::EchoMessage(EchoMessage {
+                content: echo.content.to_uppercase(),
+            }))),
+            _ => Err(HandlerError::invalid_argument("expected an EchoMessage")),
+        }
+    }
+}
+
+/// Utility function to build an AddRequest.
+fn add(a: i32, b: i32) -> client_message::Message {
+    client_message::Message::AddRequest(AddRequest { a, b })
+}
+
+/// Utility function to build an EchoMessage request.
+fn echo(content: &str) -> client_message::Message {
+    client_message::Message::EchoMessage(EchoMessage { content: content.to_string() })
+}
+
+/// Utility function to build the reply carrying `message`.
+fn reply(message: server_message::Message) -> ServerMessage {
+    ServerMessage { message: Some(message) }
+}
+
+/// Test to validate the built-in handlers outside a server.
+#[test]
+fn test_built_in_handlers() {
+    let context = Context::new(7, "127.0.0.1:9000".parse::<std::net::SocketAddr>().unwrap().into());
+    assert_eq!(context.connection_id(), 7);
+
+    let echo_message = EchoMessage { content: "hello".to_string() };
+    assert_eq!(
+        Echo.handle(client_message::Message::EchoMessage(echo_message.clone()), &context),
+        Ok(Some(server_message::Message::EchoMessage(echo_message)))
+    );
+    assert_eq!(Add.handle(add(2, 3), &context), Ok(Some(server_message::Message::AddResponse(AddResponse { result: 5 }))));
+    let error = Add.handle(echo("not a sum"), &context).expect_err("Expected a request of the wrong type to fail");
+    assert_eq!(error.code, handler::ErrorCode::InvalidArgument);
+}
+
+/// Test to validate that a registered handler replaces a built-in one and sees its connection.
+#[test]
+fn test_registered_handler_replaces_built_in() {
+    let _ = env_logger::builder().is_test(true).try_init();
+
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let mut server = Server::new("127.0.0.1:0").expect("Failed to start server");
+    server.register_handler(MessageKind::EchoMessage, Shout { seen: Arc::clone(&seen) });
+    let handle = server.start().expect("Failed to run server");
+    let mut client = client::Client::new("127.0.0.1", handle.local_addr().unwrap().port().into(), 2000);
+    assert!(client.connect().is_ok(), "Failed to connect to the server");
+
+    assert!(client.send(echo("quiet")).is_ok(), "Failed to send message");
+    let expected = reply(server_message::Message::EchoMessage(EchoMessage { content: "QUIET".to_string() }));
+    assert_eq!(client.receive().expect("Failed to receive response for EchoMessage"), expected);
+    // The built-in Add handler is still in place
+    assert!(client.send(add(1, 2)).is_ok(), "Failed to send message");
+    assert_eq!(client.receive().unwrap(), reply(server_message::Message::AddResponse(AddResponse { result: 3 })));
+
+    let connection = &handle.server().connections()[0];
+    assert_eq!(*seen.lock().unwrap(), vec![(connection.id, connection.peer_addr.clone())]);
+    let local = client.stream().unwrap().local_addr().unwrap();
+    assert_eq!(connection.peer_addr, Endpoint::Tcp(local), "Expected the handler to see the client's address");
+
+    assert!(client.disconnect().is_ok(), "Failed to disconnect from the server");
+    handle.stop();
+    assert!(handle.wait().is_ok(), "Expected a clean shutdown");
+}
+
+/// Test to validate handler errors, silent handlers and closure handlers, also on an executor pool.
+#[test]
+fn test_closure_handlers_and_errors() {
+    let _ = env_logger::builder().is_test(true).try_init();
+
+    let config = ServerConfig {
+        pools: vec![PoolConfig::new("math", 2, 8).handling(&[MessageKind::AddRequest])],
+        ..ServerConfig::default()
+    };
+    let mut server = Server::with_config("127.0.0.1:0", config).expect("Failed to start server");
+    server.register_handler(MessageKind::AddRequest, |request: client_message::Message, _: &Context| match request {
+        client_message::Message::AddRequest(AddRequest { a, b }) => match a.checked_add(b) {
+            Some(result) => Ok(Some(server_message::Message::AddResponse(AddResponse { result }))),
+            None => Err(HandlerError::invalid_argument("sum does not fit in 32 bits")),
+        },
+        _ => Err(HandlerError::invalid_argument("expected an AddRequest")),
+    });
+    server.register_handler(MessageKind::EchoMessage, |_: client_message::Message, _: &Context| Ok(None));
+    let handle = server.start().expect("Failed to run server");
+    let mut client = client::Client::new("127.0.0.1", handle.local_addr().unwrap().port().into(), 2000);
+    assert!(client.connect().is_ok(), "Failed to connect to the server");
+
+    assert!(client.send(add(i32::MAX, 1)).is_ok(), "Failed to send message");
+    match client.receive().expect("Failed to receive a response").message {
+        Some(server_message::Message::ErrorResponse(error)) => {
+            assert_eq!(error.code(), ErrorCode::InvalidArgument);
+            assert_eq!(error.message, "sum does not fit in 32 bits");
+        }
+        other => panic!("Expected an ErrorResponse, but received {:?}", other),
+    }
+
+    // The echo handler answers nothing, so the next reply belongs to the AddRequest
+    assert!(client.send(echo("ignored")).is_ok(), "Failed to send message");
+    assert!(client.send(add(20, 22)).is_ok(), "Failed to send message");
+    assert_eq!(client.receive().unwrap(), reply(server_message::Message::AddResponse(AddResponse { result: 42 })));
+    assert_eq!(handle.server().handler_panics(), 0, "Expected the error to be reported without a panic");
+
+    assert!(client.disconnect().is_ok(), "Failed to disconnect from the server");
+    handle.stop();
+    assert!(handle.wait().is_ok(), "Expected a clean shutdown");
+}
@@ -0,0 +1,147 @@
+use embedded_recruitment_task::{
+    config::{PoolConfig, ServerConfig, Timeouts}, // Importing server settings
+    handler::{Context, HandlerResult}, // Importing request handlers
+    hooks::DisconnectReason, // Importing why connections close
+    message::{client_message, server_message, AddRequest, EchoMessage, ErrorCode}, // Importing message types for client-server communication
+    router::MessageKind, // Importing message types handlers are registered for
+    server::Server, // Importing server functionalities
+    session::Session, // Importing per-connection state
+};
+use std::{
+    sync::{Arc, Mutex}, // For recording hook calls
+    time::Duration, // For timeouts
+};
+
+#[allow(dead_code)] // Not every client helper is used by this suite
+mod client; // Declares a client module for client-related operations
+
+use client::wait_until; // Polls for asynchronous server effects
+
+/// Hook calls in the order they happened.
+type Events = Arc<Mutex<Vec<String>>>;
+
+/// Utility function to build a server that records every hook call in `events`.
+fn create_server(config: ServerConfig, events: &Events) -> Server {
+    let mut server = Server::with_config("127.0.0.1:0", config).expect("Failed to start server");
+    let connects = Arc::clone(events);
+    server.on_connect(move |session: &Session| connects.lock().unwrap().push(format!("connect {}", session.id())));
+    let handshakes = Arc::clone(events);
+    server.on_handshake_complete(move |session: &Session| handshakes.lock().unwrap().push(format!("handshake {}", session.id())));
+    let disconnects = Arc::clone(events);
+    server.on_disconnect(move |session: &Session, reason: DisconnectReason| {
+        let identity = session.identity().unwrap_or_default();
+        disconnects.lock().unwrap().push(format!("disconnect {} {:?} {}", session.id(), reason, identity).trim_end().to_string());
+    });
+    let shutdowns = Arc::clone(events);
+    server.on_server_shutdown(move || shutdowns.lock().unwrap().push("shutdown".to_string()));
+    server
+}
+
+/// Utility function to return the recorded events that mention connection `id`.
+fn events_of(events: &Events, id: u64) -> Vec<String> {
+    let id = id.to_string();
+    let events = events.lock().unwrap();
+    events.iter().filter(|event| event.split(' ').nth(1) == Some(id.as_str())).cloned().collect()
+}
+
+/// Utility function to connect a client and return it with its connection ID.
+fn connect(server: &Server, port: u16, known: &mut Vec<u64>) -> (client::Client, u64) {
+    let mut client = client::Client::new("127.0.0.1", port.into(), 2000);
+    assert!(client.connect().is_ok(), "Failed to connect to the server");
+    let mut id = None;
+    assert!(
+        wait_until(Duration::from_secs(2), || {
+            id = server.connections().iter().map(|connection| connection.id).find(|id| !known.contains(id));
+            id.is_some()
+        }),
+        "Expected the connection to be registered"
+    );
+    known.push(id.unwrap());
+    (client, id.unwrap())
+}
+
+/// Test to validate that hooks see every stage of a connection and why it closed.
+#[test]
+fn test_hooks_follow_connection_lifecycle() {
+    let _ = env_logger::builder().is_test(true).try_init();
+
+    let events = Events::default();
+    let config = ServerConfig {
+        timeouts: Timeouts { handshake: Some(Duration::from_millis(300)), ..Timeouts::default() },
+        ..ServerConfig::default()
+    };
+    let mut server = create_server(config, &events);
+    server.on_connect(|_: &Session| panic!("a broken hook"));
+    server.register_handler(MessageKind::EchoMessage, |request: client_message::Message, context: &Context| -> HandlerResult {
+        if let client_message::Message::EchoMessage(echo) = &request {
+            context.session().set_identity(echo.content.as_str());
+        }
+        Ok(Some(server_message::Message::EchoMessage(EchoMessage { content: "welcome".to_string() })))
+    });
+    let handle = server.start().expect("Failed to run server");
+    let server = handle.server();
+    let port = handle.local_addr().unwrap().port();
+    let mut known = Vec::new();
+
+    // A client that logs in and leaves, seen with its session intact
+    let (mut alice, alice_id) = connect(server, port, &mut known);
+    let login = client_message::Message::EchoMessage(EchoMessage { content: "alice".to_string() });
+    assert!(alice.send(login).is_ok(), "Failed to send message");
+    assert!(alice.receive().is_ok(), "Expected the panicking hook to leave the connection serving");
+    assert!(alice.disconnect().is_ok(), "Failed to disconnect from the server");
+    let expected = [format!("connect {}", alice_id), format!("handshake {}", alice_id), format!("disconnect {} Eof alice", alice_id)];
+    assert!(wait_until(Duration::from_secs(2), || events_of(&events, alice_id) == expected), "Got {:?}", events_of(&events, alice_id));
+
+    // A client that never sends a request
+    let (_silent, silent_id) = connect(server, port, &mut known);
+    let expected = [format!("connect {}", silent_id), format!("disconnect {} Timeout", silent_id)];
+    assert!(wait_until(Duration::from_secs(2), || events_of(&events, silent_id) == expected), "Got {:?}", events_of(&events, silent_id));
+
+    // A client closed by the server
+    let (mut kicked, kicked_id) = connect(server, port, &mut known);
+    assert!(kicked.send(client_message::Message::EchoMessage(EchoMessage { content: "bob".to_string() })).is_ok());
+    assert!(kicked.receive().is_ok(), "Failed to receive a response");
+    assert!(server.disconnect(kicked_id), "Expected the connection to be known");
+    let expected = [format!("connect {}", kicked_id), format!("handshake {}", kicked_id), format!("disconnect {} Kicked bob", kicked_id)];
+    assert!(wait_until(Duration::from_secs(2), || events_of(&events, kicked_id) == expected), "Got {:?}", events_of(&events, kicked_id));
+
+    // A client still connected when the server stops, closed before the shutdown hook runs
+    let (mut carol, carol_id) = connect(server, port, &mut known);
+    assert!(carol.send(client_message::Message::EchoMessage(EchoMessage { content: "carol".to_string() })).is_ok());
+    assert!(carol.receive().is_ok(), "Failed to receive a response");
+    handle.stop();
+    assert!(handle.wait().is_ok(), "Expected a clean shutdown");
+    let events = events.lock().unwrap();
+    assert_eq!(events.last().map(String::as_str), Some("shutdown"));
+    assert_eq!(events[events.len() - 2], format!("disconnect {} Shutdown carol", carol_id));
+}
+
+/// Test to validate that connections closed after a handler panic are reported as errors.
+#[test]
+fn test_disconnect_after_panic_is_an_error() {
+    let _ = env_logger::builder().is_test(true).try_init();
+
+    let events = Events::default();
+    let config = ServerConfig {
+        pools: vec![PoolConfig::new("math", 2, 8).handling(&[MessageKind::AddRequest])],
+        ..ServerConfig::default()
+    };
+    let mut server = create_server(config, &events);
+    server.register_handler(MessageKind::AddRequest, |_: client_message::Message, _: &Context| -> HandlerResult {
+        panic!("a broken handler");
+    });
+    let handle = server.start().expect("Failed to run server");
+    let port = handle.local_addr().unwrap().port();
+
+    let (mut client, id) = connect(handle.server(), port, &mut Vec::new());
+    assert!(client.send(client_message::Message::AddRequest(AddRequest { a: 1, b: 2 })).is_ok(), "Failed to send message");
+    match client.receive().expect("Failed to receive a response").message {
+        Some(server_message::Message::ErrorResponse(error)) => assert_eq!(error.code(), ErrorCode::Internal),
+        other => panic!("Expected an ErrorResponse, but received {:?}", other),
+    }
+    let expected = [format!("connect {}", id), format!("handshake {}", id), format!("disconnect {} Error", id)];
+    assert!(wait_until(Duration::from_secs(2), || events_of(&events, id) == expected), "Got {:?}", events_of(&events, id));
+
+    handle.stop();
+    assert!(handle.wait().is_ok(), "Expected a clean shutdown");
+}
@@ -0,0 +1,438 @@
+#![cfg(unix)]
+
+use embedded_recruitment_task::{
+    codec::{Codec, FrameReader}, // Importing the framings endpoints speak
+    config::{ConfigFile, ListenerConfig, ServerConfig, Timeouts}, // Importing server settings
+    listener::ListenAddr, // Importing listen addresses
+    message::{client_message, server_message, ClientMessage, EchoMessage, ServerMessage}, // Importing message types for client-server communication
+    server::Server, // Importing server functionalities
+    socket::{KeepAlive, Setting, SocketOptions}, // Importing TCP socket tuning
+    stream::{Endpoint, Stream}, // Importing connection handles and addresses
+    tls::TlsConfig, // Importing endpoint security
+};
+use prost::Message; // For decoding raw replies
+use std::{
+    env, // For locating the temporary directory
+    io::{self, ErrorKind, Read, Write}, // For raw framed exchanges
+    net::{Shutdown, SocketAddr, TcpStream}, // For TCP endpoints and silent clients
+    os::unix::net::UnixStream, // For Unix socket clients and relaying secured traffic
+    path::PathBuf, // For socket paths
+    thread, // For relaying secured traffic
+    time::{Duration, Instant}, // For client timeouts and handshake times
+};
+
+#[allow(dead_code)] // Not every client helper is used by this suite
+mod client; // Declares a client module for client-related operations
+
+use client::wait_until; // Polls for asynchronous server effects
+
+/// Utility function to build a per-test Unix socket path.
+fn socket_path(name: &str) -> PathBuf {
+    env::temp_dir().join(format!("listener-{}-{}.sock", name, std::process::id()))
+}
+
+/// Utility function to send one framed request over any stream and read the framed reply.
+fn exchange<S: Read + Write>(stream: &mut S, message: client_message::Message) -> io::Result<ServerMessage> {
+    exchange_with(Codec::Varint, stream, message)
+}
+
+/// Utility function to send one request framed with `codec` over any stream and read the reply.
+fn exchange_with<S: Read + Write>(codec: Codec, stream: &mut S, message: client_message::Message) -> io::Result<ServerMessage> {
+    stream.write_all(&codec.encode(&ClientMessage { message: Some(message) }))?;
+    let mut frames = FrameReader::with_codec(codec, 1024 * 1024);
+    loop {
+        if let Some(frame) = frames.next_frame()? {
+            return ServerMessage::decode(frame.as_slice()).map_err(|e| io::Error::new(ErrorKind::InvalidData, e));
+        }
+        let mut chunk = [0; 4096];
+        match stream.read(&mut chunk)? {
+            0 => return Err(io::Error::new(ErrorKind::ConnectionAborted, "server closed the connection")),
+            read => frames.extend(&chunk[..read]),
+        }
+    }
+}
+
+/// Utility function to build an echo request and its expected reply.
+fn echo(content: &str) -> (client_message::Message, ServerMessage) {
+    let echo_message = EchoMessage { content: content.to_string() };
+    let reply = ServerMessage { message: Some(server_message::Message::EchoMessage(echo_message.clone())) };
+    (client_message::Message::EchoMessage(echo_message), reply)
+}
+
+/// Utility function to run an echo round trip with the test client over TCP.
+fn tcp_echo(addr: SocketAddr, content: &str) -> io::Result<ServerMessage> {
+    let mut client = client::Client::new(&addr.ip().to_string(), addr.port().into(), 2000);
+    client.connect()?;
+    client.send(echo(content).0)?;
+    client.receive()
+}
+
+/// Test to validate that one server serves IPv4, IPv6 and Unix socket endpoints with shared state and shutdown.
+#[test]
+fn test_server_listens_on_several_endpoints() {
+    let _ = env_logger::builder().is_test(true).try_init();
+
+    let path = socket_path("shared");
+    let listeners = [
+        ListenerConfig::new("127.0.0.1:0".parse().unwrap()),
+        ListenerConfig::new("[::1]:0".parse().unwrap()),
+        ListenerConfig::new(ListenAddr::Unix(path.clone())),
+    ];
+    let handle = Server::bind(&listeners, ServerConfig::default()).unwrap().start().expect("Failed to run server");
+    let addrs = handle.local_addrs().unwrap();
+    assert_eq!(addrs.len(), 3, "Expected one address per endpoint: {:?}", addrs);
+    assert_eq!(addrs[2], Endpoint::Unix(Some(path.clone())));
+    let (v4, v6) = (addrs[0].tcp().unwrap(), addrs[1].tcp().unwrap());
+    assert!(v4.is_ipv4() && v6.is_ipv6());
+
+    assert_eq!(tcp_echo(v4, "ipv4").unwrap(), echo("ipv4").1);
+    assert_eq!(tcp_echo(v6, "ipv6").unwrap(), echo("ipv6").1);
+    let mut unix = UnixStream::connect(&path).expect("Failed to connect to the Unix socket");
+    unix.set_read_timeout(Some(Duration::from_secs(2))).unwrap();
+    assert_eq!(exchange(&mut unix, echo("unix").0).unwrap(), echo("unix").1);
+
+    // Every endpoint feeds the same registry
+    let connections = handle.server().connections();
+    assert!(
+        connections.iter().any(|info| matches!(info.peer_addr, Endpoint::Unix(_))),
+        "Expected the Unix client in the registry: {:?}",
+        connections
+    );
+
+    handle.stop();
+    assert!(handle.wait().is_ok(), "Expected a clean shutdown");
+    assert!(exchange(&mut unix, echo("after").0).is_err(), "Expected shutdown to close Unix clients");
+    assert!(!path.exists(), "Expected the socket file to be removed when the server stopped");
+}
+
+/// Test to validate that binding replaces a stale socket file but never deletes anything else at the path.
+#[test]
+fn test_unix_bind_only_replaces_stale_sockets() {
+    let _ = env_logger::builder().is_test(true).try_init();
+
+    // A regular file at the path survives a failed bind
+    let path = socket_path("regular");
+    std::fs::write(&path, "not a socket").unwrap();
+    let listeners = [ListenerConfig::new(ListenAddr::Unix(path.clone()))];
+    let error = Server::bind(&listeners, ServerConfig::default()).err().expect("Expected binding over a regular file to fail");
+    assert_eq!(error.kind(), ErrorKind::AddrInUse);
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "not a socket", "Expected the file to be left alone");
+    std::fs::remove_file(&path).unwrap();
+
+    // A socket nobody listens on any more is replaced
+    let path = socket_path("stale");
+    drop(std::os::unix::net::UnixListener::bind(&path).unwrap());
+    let listeners = [ListenerConfig::new(ListenAddr::Unix(path.clone()))];
+    let handle = Server::bind(&listeners, ServerConfig::default()).unwrap().start().expect("Failed to run server");
+    let mut unix = UnixStream::connect(&path).expect("Failed to connect to the Unix socket");
+    unix.set_read_timeout(Some(Duration::from_secs(2))).unwrap();
+    assert_eq!(exchange(&mut unix, echo("fresh").0).unwrap(), echo("fresh").1);
+
+    handle.stop();
+    assert!(handle.wait().is_ok(), "Expected a clean shutdown");
+    let _ = std::fs::remove_file(&path);
+}
+
+/// Test to validate that a server removes only the socket file it bound, and keeps it for an upgrade.
+#[test]
+fn test_unix_socket_files_are_removed_when_done() {
+    let _ = env_logger::builder().is_test(true).try_init();
+
+    // Dropping a server that never ran removes its socket file
+    let path = socket_path("dropped");
+    let server = Server::bind(&[ListenerConfig::new(ListenAddr::Unix(path.clone()))], ServerConfig::default()).unwrap();
+    assert!(path.exists(), "Expected binding to create the socket file");
+    drop(server);
+    assert!(!path.exists(), "Expected dropping the server to remove the socket file");
+
+    // A socket another process bound at the path since is left alone
+    let path = socket_path("replaced");
+    let handle = Server::bind(&[ListenerConfig::new(ListenAddr::Unix(path.clone()))], ServerConfig::default())
+        .unwrap()
+        .start()
+        .expect("Failed to run server");
+    std::fs::remove_file(&path).unwrap();
+    let replacement = std::os::unix::net::UnixListener::bind(&path).unwrap();
+    handle.stop();
+    assert!(handle.wait().is_ok(), "Expected a clean shutdown");
+    assert!(path.exists(), "Expected the replacement socket to survive the shutdown");
+    drop(replacement);
+    std::fs::remove_file(&path).unwrap();
+
+    // Kept socket files stay for the process that inherited the listeners
+    let path = socket_path("kept");
+    let handle = Server::bind(&[ListenerConfig::new(ListenAddr::Unix(path.clone()))], ServerConfig::default())
+        .unwrap()
+        .start()
+        .expect("Failed to run server");
+    handle.server().keep_socket_files();
+    handle.stop();
+    assert!(handle.wait().is_ok(), "Expected a clean shutdown");
+    assert!(path.exists(), "Expected the kept socket file to stay");
+    std::fs::remove_file(&path).unwrap();
+}
+
+/// Test to validate that `0.0.0.0` and `[::]` can listen on the same port.
+#[test]
+fn test_dual_stack_endpoints_share_a_port() {
+    let _ = env_logger::builder().is_test(true).try_init();
+
+    // Find a port free on both stacks; another process may grab it in between, so retry
+    let server = (0..5)
+        .find_map(|_| {
+            let port = Server::new("[::]:0").ok()?.local_addr().ok()?.port();
+            let listeners = [
+                ListenerConfig::new(format!("0.0.0.0:{}", port).parse().unwrap()),
+                ListenerConfig::new(format!("[::]:{}", port).parse().unwrap()),
+            ];
+            Server::bind(&listeners, ServerConfig::default()).ok()
+        })
+        .expect("Failed to bind both stacks to one port");
+    let handle = server.start().expect("Failed to run server");
+    let port = handle.local_addr().unwrap().port();
+
+    assert_eq!(tcp_echo(([127, 0, 0, 1], port).into(), "ipv4").unwrap(), echo("ipv4").1);
+    assert_eq!(tcp_echo("[::1]:0".parse::<SocketAddr>().map(|addr| SocketAddr::new(addr.ip(), port)).unwrap(), "ipv6").unwrap(), echo("ipv6").1);
+    assert_eq!(handle.server().accepted_per_acceptor(), vec![1, 1], "Expected each stack to use its own listener");
+
+    handle.stop();
+    assert!(handle.wait().is_ok(), "Expected a clean shutdown");
+}
+
+/// Test to validate that endpoint overrides apply only to their own endpoint.
+#[test]
+fn test_listener_overrides_apply_per_endpoint() {
+    let _ = env_logger::builder().is_test(true).try_init();
+
+    let tuned = SocketOptions {
+        keepalive: Some(Setting::On(KeepAlive { idle: Duration::from_secs(30), interval: None, retries: None })),
+        linger: Some(Setting::On(Duration::from_secs(1))),
+        ..SocketOptions::default()
+    };
+    let restricted = ListenerConfig {
+        max_frame_len: Some(64),
+        socket: Some(SocketOptions {
+            nodelay: Some(true),
+            keepalive: Some(Setting::Off),
+            linger: Some(Setting::Off),
+            ..SocketOptions::default()
+        }),
+        acceptors: Some(2),
+        timeouts: Some(Timeouts { handshake: Some(Duration::from_millis(200)), ..Timeouts::default() }),
+        ..ListenerConfig::new("127.0.0.1:0".parse().unwrap())
+    };
+    let listeners = [ListenerConfig::new("127.0.0.1:0".parse().unwrap()), restricted];
+    let config = ServerConfig { socket: tuned, ..ServerConfig::default() };
+    let handle = Server::bind(&listeners, config).unwrap().start().expect("Failed to run server");
+    let addrs = handle.local_addrs().unwrap();
+    assert_eq!(handle.server().accepted_per_acceptor().len(), 3, "Expected two acceptors on the restricted endpoint");
+    let (open, restricted) = (addrs[0].tcp().unwrap(), addrs[1].tcp().unwrap());
+
+    let large = "x".repeat(1024);
+    assert_eq!(tcp_echo(open, &large).unwrap(), echo(&large).1);
+    assert!(tcp_echo(restricted, &large).is_err(), "Expected the restricted endpoint to reject large requests");
+    assert_eq!(tcp_echo(restricted, "small").unwrap(), echo("small").1);
+
+    // Only streams accepted on the restricted endpoint get its socket options, which switch the
+    // server-wide keepalive and linger off
+    let server = handle.server();
+    let expected = [(open, false, true, Some(Duration::from_secs(1))), (restricted, true, false, None)];
+    for (endpoint, nodelay, keepalive, linger) in expected {
+        let client = TcpStream::connect(endpoint).unwrap();
+        let peer = Endpoint::Tcp(client.local_addr().unwrap());
+        let mut id = None;
+        let registered = wait_until(Duration::from_secs(2), || {
+            id = server.connections().iter().find(|connection| connection.peer_addr == peer).map(|connection| connection.id);
+            id.is_some()
+        });
+        assert!(registered, "Expected the connection to {} to be registered", endpoint);
+        let stream = server.connection_stream(id.unwrap()).unwrap().expect("Expected the connection to be live");
+        let socket = socket2::SockRef::from(stream.as_tcp().expect("Expected a TCP stream"));
+        assert_eq!(socket.nodelay().unwrap(), nodelay, "Unexpected TCP_NODELAY on a stream accepted on {}", endpoint);
+        assert_eq!(socket.keepalive().unwrap(), keepalive, "Unexpected SO_KEEPALIVE on a stream accepted on {}", endpoint);
+        assert_eq!(socket.linger().unwrap(), linger, "Unexpected SO_LINGER on a stream accepted on {}", endpoint);
+    }
+
+    // Only the restricted endpoint closes clients that never send a request
+    let mut silent_open = TcpStream::connect(open).unwrap();
+    let mut silent_restricted = TcpStream::connect(restricted).unwrap();
+    silent_restricted.set_read_timeout(Some(Duration::from_secs(2))).unwrap();
+    assert_eq!(silent_restricted.read(&mut [0; 16]).unwrap(), 0, "Expected the handshake timeout to close the connection");
+    silent_open.set_read_timeout(Some(Duration::from_millis(300))).unwrap();
+    let error = silent_open.read(&mut [0; 16]).expect_err("Expected the open endpoint to keep waiting");
+    assert!(matches!(error.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut), "Got {}", error);
+
+    handle.stop();
+    assert!(handle.wait().is_ok(), "Expected a clean shutdown");
+}
+
+/// Test to validate that each endpoint frames requests and replies with its own codec.
+#[test]
+fn test_listener_codecs_apply_per_endpoint() {
+    let _ = env_logger::builder().is_test(true).try_init();
+
+    let fixed = ListenerConfig { codec: Codec::Fixed32, ..ListenerConfig::new("127.0.0.1:0".parse().unwrap()) };
+    let listeners = [ListenerConfig::new("127.0.0.1:0".parse().unwrap()), fixed];
+    let handle = Server::bind(&listeners, ServerConfig::default()).unwrap().start().expect("Failed to run server");
+    let addrs = handle.local_addrs().unwrap();
+    let (varint, fixed) = (addrs[0].tcp().unwrap(), addrs[1].tcp().unwrap());
+
+    assert_eq!(tcp_echo(varint, "varint").unwrap(), echo("varint").1);
+    let mut stream = TcpStream::connect(fixed).unwrap();
+    stream.set_read_timeout(Some(Duration::from_secs(2))).unwrap();
+    assert_eq!(exchange_with(Codec::Fixed32, &mut stream, echo("fixed32").0).unwrap(), echo("fixed32").1);
+    let large = "x".repeat(300); // A varint prefix of two bytes, unlike any fixed32 prefix
+    assert_eq!(exchange_with(Codec::Fixed32, &mut stream, echo(&large).0).unwrap(), echo(&large).1);
+
+    handle.stop();
+    assert!(handle.wait().is_ok(), "Expected a clean shutdown");
+}
+
+/// Key the stand-in for TLS scrambles traffic with.
+const KEY: u8 = 0x5a;
+
+/// Utility function to copy `from` into `to` until either closes, scrambling every byte with `KEY`.
+fn relay(mut from: Stream, mut to: Stream) {
+    thread::spawn(move || {
+        let mut buffer = [0; 4096];
+        while let Ok(read @ 1..) = from.read(&mut buffer) {
+            buffer[..read].iter_mut().for_each(|byte| *byte ^= KEY);
+            if to.write_all(&buffer[..read]).is_err() {
+                break;
+            }
+        }
+        let _ = to.shutdown(Shutdown::Write);
+    });
+}
+
+/// A stand-in for a TLS library: a `HELLO`/`OK` handshake, then every byte scrambled with `KEY`,
+/// relayed to the server through a socket pair.
+fn scrambling_acceptor(mut stream: Stream) -> io::Result<Stream> {
+    let mut hello = [0; 5];
+    stream.read_exact(&mut hello)?;
+    if &hello != b"HELLO" {
+        return Err(io::Error::new(ErrorKind::InvalidData, "unexpected handshake"));
+    }
+    stream.write_all(b"OK")?;
+    stream.set_read_timeout(None)?; // The session outlives the handshake.
+    let (plaintext, relayed) = UnixStream::pair()?;
+    let relayed = Stream::from(relayed);
+    relay(stream.try_clone()?, relayed.try_clone()?);
+    relay(relayed, stream);
+    Ok(Stream::from(plaintext))
+}
+
+/// A client end of the scrambled session.
+struct Scrambled(TcpStream);
+
+impl Read for Scrambled {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.0.read(buf)?;
+        buf[..read].iter_mut().for_each(|byte| *byte ^= KEY);
+        Ok(read)
+    }
+}
+
+impl Write for Scrambled {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let scrambled: Vec<u8> = buf.iter().map(|byte| byte ^ KEY).collect();
+        self.0.write_all(&scrambled).map(|_| buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.flush()
+    }
+}
+
+/// Test to validate that an endpoint's TLS hook secures its connections without holding up the accept loop.
+#[test]
+fn test_listener_tls_hook_secures_connections() {
+    let _ = env_logger::builder().is_test(true).try_init();
+
+    let secured = ListenerConfig {
+        tls: Some(TlsConfig::new(scrambling_acceptor)),
+        timeouts: Some(Timeouts { handshake: Some(Duration::from_millis(500)), ..Timeouts::default() }),
+        ..ListenerConfig::new("127.0.0.1:0".parse().unwrap())
+    };
+    let handle = Server::bind(&[secured], ServerConfig::default()).unwrap().start().expect("Failed to run server");
+    let addr = handle.local_addr().unwrap();
+
+    // A client stalling its handshake does not delay the next one
+    let mut stalled = TcpStream::connect(addr).unwrap();
+    let started = Instant::now();
+    let mut client = TcpStream::connect(addr).unwrap();
+    client.set_read_timeout(Some(Duration::from_secs(2))).unwrap();
+    client.write_all(b"HELLO").unwrap();
+    let mut ok = [0; 2];
+    client.read_exact(&mut ok).unwrap();
+    assert_eq!(&ok, b"OK");
+    let mut client = Scrambled(client);
+    assert_eq!(exchange(&mut client, echo("secret").0).unwrap(), echo("secret").1);
+    assert!(started.elapsed() < Duration::from_millis(400), "Expected the handshakes to run concurrently");
+
+    // Only connections that completed the handshake are registered, and failed ones are closed
+    assert_eq!(handle.server().connections().len(), 1);
+    stalled.set_read_timeout(Some(Duration::from_secs(2))).unwrap();
+    assert_eq!(stalled.read(&mut [0; 16]).unwrap(), 0, "Expected the handshake timeout to close the connection");
+    let mut plaintext = TcpStream::connect(addr).unwrap();
+    plaintext.set_read_timeout(Some(Duration::from_secs(2))).unwrap();
+    assert!(exchange(&mut plaintext, echo("in the clear").0).is_err(), "Expected plaintext requests to be refused");
+    assert_eq!(handle.server().connections().len(), 1);
+
+    handle.stop();
+    assert!(handle.wait().is_ok(), "Expected a clean shutdown");
+}
+
+/// Test to validate listener settings in configuration files.
+#[test]
+fn test_config_file_listeners() {
+    let text = "
+        backlog = 32
+        listen = 0.0.0.0:8080
+        listen = [::]:8080 acceptors=2 ipv6_only=false
+        listen = unix:/run/server.sock max_frame_len=1024 idle_timeout_ms=0 read_timeout_ms=500 codec=fixed32
+    ";
+    let file = ConfigFile::parse(text).expect("Failed to parse the configuration");
+    assert_eq!(file.listeners.len(), 3);
+    assert_eq!(file.listeners[0], ListenerConfig::new(ListenAddr::Tcp("0.0.0.0:8080".to_string())));
+    assert_eq!(file.listeners[1].acceptors, Some(2));
+    let socket = file.listeners[1].socket.expect("Expected socket overrides");
+    assert_eq!((socket.backlog, socket.only_v6), (32, Some(false)), "Overrides start from the server-wide options");
+    assert_eq!(file.listeners[2].address, ListenAddr::Unix(PathBuf::from("/run/server.sock")));
+    assert_eq!(file.listeners[2].max_frame_len, Some(1024));
+    assert_eq!((file.listeners[1].codec, file.listeners[2].codec), (Codec::Varint, Codec::Fixed32));
+    let timeouts = file.listeners[2].timeouts.expect("Expected timeout overrides");
+    assert_eq!((timeouts.idle, timeouts.read), (None, Duration::from_millis(500)));
+    assert_eq!(timeouts.handshake, Timeouts::default().handshake, "Overrides start from the server-wide timeouts");
+    assert_eq!(file.listeners[1].timeouts, None);
+
+    let file = ConfigFile::parse("address = 127.0.0.1:1, unix:/tmp/a.sock").unwrap();
+    assert_eq!(file.listeners.len(), 2);
+    assert_eq!(ConfigFile::default().listeners.len(), 1, "Expected a default endpoint");
+
+    // Keepalive and linger can be switched off server-wide, whatever the key order, and per endpoint
+    let text = "
+        keepalive_retries = 3
+        keepalive_idle_ms = 0
+        linger_ms = off
+        listen = 127.0.0.1:1
+    ";
+    let socket = ConfigFile::parse(text).unwrap().server.socket;
+    assert_eq!((socket.keepalive, socket.linger), (Some(Setting::Off), Some(Setting::Off)));
+    let text = "
+        keepalive_idle_ms = 30000
+        linger_ms = 1000
+        listen = 127.0.0.1:1 keepalive_idle_ms=0 linger_ms=off
+        listen = 127.0.0.1:2 linger_ms=0
+    ";
+    let file = ConfigFile::parse(text).unwrap();
+    let keepalive = KeepAlive { idle: Duration::from_secs(30), interval: None, retries: None };
+    assert_eq!(file.server.socket.keepalive, Some(Setting::On(keepalive)));
+    let (off, abortive) = (file.listeners[0].socket.unwrap(), file.listeners[1].socket.unwrap());
+    assert_eq!((off.keepalive, off.linger), (Some(Setting::Off), Some(Setting::Off)));
+    assert_eq!((abortive.keepalive, abortive.linger), (Some(Setting::On(keepalive)), Some(Setting::On(Duration::ZERO))));
+
+    for invalid in ["listen =", "listen = 127.0.0.1:1 bogus=1", "listen = 127.0.0.1:1 linger_ms=never", "listen = 127.0.0.1:1 acceptors=0", "listen = 127.0.0.1:1 read_timeout_ms=0", "listen = 127.0.0.1:1 codec=json", "address = unix:"] {
+        assert!(ConfigFile::parse(invalid).is_err(), "Expected `{}` to be rejected", invalid);
+    }
+}
@@ -0,0 +1,244 @@
+use embedded_recruitment_task::{
+    config::{CoDelConfig, ConfigFile, PoolConfig, ServerConfig}, // Importing server settings
+    handler::{Context, HandlerResult}, // Importing request handlers
+    message::{client_message, server_message, EchoMessage, ErrorCode, ErrorResponse, ServerMessage}, // Importing message types for client-server communication
+    router::MessageKind, // Importing message types assigned to pools
+    server::Server, // Importing server functionalities
+    shedding::{CoDel, LoadShedder}, // Importing load shedding policies
+    socket::SocketOptions, // Importing TCP socket tuning
+};
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering}, // For counting arrivals in a custom policy
+        Arc, Mutex, // For reading what a custom policy observed
+    },
+    thread, // For concurrent clients
+    time::{Duration, Instant}, // For shedding intervals and round-trip times
+};
+
+#[allow(dead_code)] // Not every client helper is used by this suite
+mod client; // Declares a client module for client-related operations
+
+/// Utility function to build an echo request.
+fn echo(content: String) -> client_message::Message {
+    client_message::Message::EchoMessage(EchoMessage { content })
+}
+
+/// Utility function to return the error carried by a reply, if it is one.
+fn error_of(response: &ServerMessage) -> Option<&ErrorResponse> {
+    match &response.message {
+        Some(server_message::Message::ErrorResponse(error)) => Some(error),
+        _ => None,
+    }
+}
+
+/// Test to validate the CoDel controller: bursts pass, sustained delay sheds, and a short wait ends shedding.
+#[test]
+fn test_codel_sheds_sustained_queue_delay() {
+    let codel = CoDel::new(CoDelConfig { target: Duration::from_millis(5), interval: Duration::from_millis(50) });
+
+    assert_eq!(codel.start(Duration::from_millis(1)), Ok(()));
+    assert_eq!(codel.start(Duration::from_millis(20)), Ok(()), "A single late request is a burst, not overload");
+    assert!(!codel.is_dropping());
+
+    // This request waited long enough to show the delay stayed above target for a whole interval
+    assert_eq!(codel.start(Duration::from_millis(80)), Err(Duration::from_millis(50)));
+    assert!(codel.is_dropping());
+    assert_eq!(codel.admit(), Err(Duration::from_millis(50)), "Expected new arrivals to be rejected while backlogged");
+
+    assert_eq!(codel.start(Duration::from_millis(1)), Ok(()), "Expected a prompt request to end shedding");
+    assert!(!codel.is_dropping());
+    assert_eq!(codel.admit(), Ok(()));
+
+    // Without requests reaching handlers for an interval, arrivals are admitted to probe the delay
+    assert!(codel.start(Duration::from_millis(80)).is_err());
+    thread::sleep(Duration::from_millis(60));
+    assert_eq!(codel.admit(), Ok(()));
+}
+
+/// A policy that rejects every other request on arrival.
+struct EveryOther {
+    arrivals: AtomicU64,
+}
+
+impl LoadShedder for EveryOther {
+    fn admit(&self) -> Result<(), Duration> {
+        match self.arrivals.fetch_add(1, Ordering::SeqCst) % 2 {
+            0 => Ok(()),
+            _ => Err(Duration::from_millis(1234)),
+        }
+    }
+
+    fn start(&self, _queued: Duration) -> Result<(), Duration> {
+        Ok(())
+    }
+}
+
+/// Test to validate that a custom policy can be plugged in and its rejections reach clients.
+#[test]
+fn test_custom_load_shedder() {
+    let _ = env_logger::builder().is_test(true).try_init();
+
+    let mut server = Server::new("127.0.0.1:0").expect("Failed to start server");
+    server.set_load_shedder(EveryOther { arrivals: AtomicU64::new(0) });
+    let handle = server.start().expect("Failed to run server");
+    let mut client = client::Client::new("127.0.0.1", handle.local_addr().unwrap().port().into(), 2000);
+    assert!(client.connect().is_ok(), "Failed to connect to the server");
+
+    for i in 0..4 {
+        assert!(client.send(echo(format!("request {}", i))).is_ok(), "Failed to send message");
+        let response = client.receive().expect("Failed to receive a response");
+        match (i % 2, error_of(&response)) {
+            (0, None) => {}
+            (1, Some(error)) => {
+                assert_eq!(error.code(), ErrorCode::Overloaded);
+                assert_eq!(error.retry_after_ms, 1234, "Expected the policy's retry-after hint");
+            }
+            (_, error) => panic!("Unexpected response to request {}: {:?}", i, error),
+        }
+    }
+    assert_eq!(handle.server().shed_requests(), 2);
+
+    assert!(client.disconnect().is_ok(), "Failed to disconnect from the server");
+    handle.stop();
+    assert!(handle.wait().is_ok(), "Expected a clean shutdown");
+}
+
+/// A policy that admits every request and records the longest queue delay it was told about.
+struct LongestDelay(Arc<Mutex<Duration>>);
+
+impl LoadShedder for LongestDelay {
+    fn start(&self, queued: Duration) -> Result<(), Duration> {
+        let mut longest = self.0.lock().unwrap();
+        *longest = (*longest).max(queued);
+        Ok(())
+    }
+}
+
+/// Test to validate that time a connection spends throttled by its own slow reading is not reported as queue delay.
+#[test]
+fn test_throttled_time_is_not_queue_delay() {
+    let _ = env_logger::builder().is_test(true).try_init();
+
+    let config = ServerConfig {
+        max_frame_len: 1024 * 1024,
+        max_in_flight: 1,
+        socket: SocketOptions { send_buffer_size: Some(4096), ..SocketOptions::default() },
+        ..ServerConfig::default()
+    };
+    let longest = Arc::new(Mutex::new(Duration::ZERO));
+    let mut server = Server::with_config("127.0.0.1:0", config).expect("Failed to start server");
+    server.set_load_shedder(LongestDelay(Arc::clone(&longest)));
+    let handle = server.start().expect("Failed to run server");
+
+    // The first large reply fills the socket buffers, so the second request waits at the in-flight limit
+    let mut client = client::Client::new("127.0.0.1", handle.local_addr().unwrap().port().into(), 5000);
+    client.set_socket_options(SocketOptions { recv_buffer_size: Some(4096), ..SocketOptions::default() });
+    assert!(client.connect().is_ok(), "Failed to connect to the server");
+    for _ in 0..2 {
+        assert!(client.send(echo("x".repeat(64 * 1024))).is_ok(), "Failed to send message");
+    }
+    thread::sleep(Duration::from_millis(300));
+    for _ in 0..2 {
+        let response = client.receive().expect("Failed to receive a response");
+        assert_eq!(error_of(&response), None);
+    }
+
+    assert!(handle.server().throttled_time() >= Duration::from_millis(200), "Expected the second request to be throttled");
+    let longest = *longest.lock().unwrap();
+    assert!(longest < Duration::from_millis(150), "Expected the throttled wait not to count as queue delay, got {:?}", longest);
+
+    assert!(client.disconnect().is_ok(), "Failed to disconnect from the server");
+    handle.stop();
+    assert!(handle.wait().is_ok(), "Expected a clean shutdown");
+}
+
+/// Load test: many clients send more work than the pool can process, the server sheds the excess with
+/// retry-after hints, and latency recovers once the load is gone.
+#[test]
+fn test_overload_is_shed_and_recovers() {
+    let _ = env_logger::builder().is_test(true).try_init();
+
+    const CLIENTS: usize = 16; // Clients sending at once
+    const REQUESTS: usize = 100; // Requests each client pipelines
+    let interval = Duration::from_millis(50);
+    let config = ServerConfig {
+        pools: vec![PoolConfig::new("echo", 2, 10_000).handling(&[MessageKind::EchoMessage])],
+        load_shedding: Some(CoDelConfig { target: Duration::from_millis(5), interval }),
+        ..ServerConfig::default()
+    };
+    let mut server = Server::with_config("127.0.0.1:0", config).expect("Failed to start server");
+    server.register_handler(MessageKind::EchoMessage, |request: client_message::Message, _: &Context| -> HandlerResult {
+        thread::sleep(Duration::from_millis(2)); // Each request costs real work.
+        match request {
+            client_message::Message::EchoMessage(echo) => Ok(Some(server_message::Message::EchoMessage(echo))),
+            _ => Ok(None),
+        }
+    });
+    let handle = server.start().expect("Failed to run server");
+    let port = handle.local_addr().unwrap().port();
+
+    // Together the clients queue far more work than two workers get through within the target delay
+    let clients: Vec<_> = (0..CLIENTS)
+        .map(|_| {
+            thread::spawn(move || {
+                let mut client = client::Client::new("127.0.0.1", port.into(), 10_000);
+                client.connect().expect("Failed to connect to the server");
+                for i in 0..REQUESTS {
+                    client.send(echo(format!("request {}", i))).expect("Failed to send message");
+                }
+                (0..REQUESTS)
+                    .map(|_| client.receive().expect("Expected a reply to every request"))
+                    .filter_map(|response| error_of(&response).cloned())
+                    .collect::<Vec<_>>()
+            })
+        })
+        .collect();
+    let errors: Vec<ErrorResponse> = clients
+        .into_iter()
+        .flat_map(|client| client.join().expect("Client thread panicked"))
+        .collect();
+
+    assert!(!errors.is_empty(), "Expected the overload to be shed");
+    assert!(errors.len() < CLIENTS * REQUESTS, "Expected some requests to be served during the overload");
+    for error in &errors {
+        assert_eq!(error.code(), ErrorCode::Overloaded);
+        assert_eq!(error.retry_after_ms as u128, interval.as_millis(), "Expected the interval as the retry-after hint");
+    }
+    assert_eq!(handle.server().shed_requests(), errors.len() as u64, "Expected every rejection to be counted");
+
+    // Once the load is gone, requests are served again without queueing
+    thread::sleep(interval * 2);
+    let mut client = client::Client::new("127.0.0.1", port.into(), 2000);
+    assert!(client.connect().is_ok(), "Failed to connect to the server");
+    for i in 0..20 {
+        let started = Instant::now();
+        assert!(client.send(echo(format!("recovered {}", i))).is_ok(), "Failed to send message");
+        let response = client.receive().expect("Failed to receive a response");
+        assert_eq!(error_of(&response), None, "Expected the server to stop shedding once the load is gone");
+        assert!(started.elapsed() < Duration::from_millis(100), "Request {} took {:?} after recovery", i, started.elapsed());
+    }
+
+    assert!(client.disconnect().is_ok(), "Failed to disconnect from the server");
+    handle.stop();
+    assert!(handle.wait().is_ok(), "Expected a clean shutdown");
+}
+
+/// Test to validate load shedding settings in configuration files.
+#[test]
+fn test_config_file_load_shedding() {
+    let file = ConfigFile::parse("shed_target_ms = 10\nshed_interval_ms = 200").expect("Failed to parse the configuration");
+    let expected = CoDelConfig { target: Duration::from_millis(10), interval: Duration::from_millis(200) };
+    assert_eq!(file.server.load_shedding, Some(expected));
+
+    let file = ConfigFile::parse("shed_interval_ms = 200").unwrap();
+    assert_eq!(file.server.load_shedding.map(|shedding| shedding.target), Some(CoDelConfig::default().target));
+    assert_eq!(ConfigFile::parse("shed_target_ms = 0").unwrap().server.load_shedding, None);
+    for text in ["shed_target_ms = 0\nshed_interval_ms = 100", "shed_interval_ms = 100\nshed_target_ms = 0"] {
+        assert_eq!(ConfigFile::parse(text).unwrap().server.load_shedding, None, "Expected `{}` to disable shedding", text);
+    }
+    let file = ConfigFile::parse("shed_interval_ms = 200\nshed_target_ms = 10").unwrap();
+    assert_eq!(file.server.load_shedding, Some(expected), "Expected the key order not to matter");
+    assert_eq!(ConfigFile::default().server.load_shedding, None, "Expected shedding to be off by default");
+    assert!(ConfigFile::parse("shed_interval_ms = 0").is_err(), "Expected a zero interval to be rejected");
+}
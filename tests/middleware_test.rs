@@ -0,0 +1,146 @@
+use embedded_recruitment_task::{
+    config::{PoolConfig, ServerConfig}, // Importing server settings
+    handler::{self, Context, HandlerError, HandlerResult}, // Importing request handlers
+    message::{client_message, server_message, AddRequest, AddResponse, EchoMessage, ErrorCode, ServerMessage}, // Importing message types for client-server communication
+    middleware::{Layer, Next}, // Importing request handling layers
+    router::MessageKind, // Importing message types assigned to pools
+    server::Server, // Importing server functionalities
+};
+use std::sync::{
+    atomic::{AtomicU64, Ordering}, // For counting handled requests
+    Arc, Mutex, // For recording what layers saw
+};
+
+#[allow(dead_code)] // Not every client helper is used by this suite
+mod client; // Declares a client module for client-related operations
+
+/// A layer that records when requests enter and replies leave it.
+struct Trace {
+    name: &'static str,
+    events: Arc<Mutex<Vec<String>>>,
+}
+
+impl Layer for Trace {
+    fn handle(&self, request: client_message::Message, context: &Context, next: Next<'_>) -> HandlerResult {
+        self.events.lock().unwrap().push(format!("{} in", self.name));
+        let response = next.run(request, context);
+        self.events.lock().unwrap().push(format!("{} out", self.name));
+        response
+    }
+}
+
+/// A layer that only lets listed connections add numbers.
+struct Auth {
+    allowed: Arc<Mutex<Vec<u64>>>,
+}
+
+impl Layer for Auth {
+    fn handle(&self, request: client_message::Message, context: &Context, next: Next<'_>) -> HandlerResult {
+        let allowed = self.allowed.lock().unwrap().contains(&context.connection_id());
+        match request {
+            client_message::Message::AddRequest(_) if !allowed => {
+                Err(HandlerError::new(handler::ErrorCode::PermissionDenied, format!("connection {} may not add", context.connection_id())))
+            }
+            request => next.run(request, context),
+        }
+    }
+}
+
+/// Utility function to build an EchoMessage request.
+fn echo(content: &str) -> client_message::Message {
+    client_message::Message::EchoMessage(EchoMessage { content: content.to_string() })
+}
+
+/// Utility function to build the reply carrying `message`.
+fn reply(message: server_message::Message) -> ServerMessage {
+    ServerMessage { message: Some(message) }
+}
+
+/// Test to validate that layers wrap handlers in the order they were added and can rewrite both ways.
+#[test]
+fn test_layers_wrap_handlers_in_order() {
+    let _ = env_logger::builder().is_test(true).try_init();
+
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let mut server = Server::new("127.0.0.1:0").expect("Failed to start server");
+    server.add_layer(Trace { name: "outer", events: Arc::clone(&events) });
+    // Tags requests on the way in
+    server.add_layer(|request: client_message::Message, context: &Context, next: Next<'_>| match request {
+        client_message::Message::EchoMessage(echo) => {
+            let tagged = EchoMessage { content: format!("{} from {}", echo.content, context.connection_id()) };
+            next.run(client_message::Message::EchoMessage(tagged), context)
+        }
+        request => next.run(request, context),
+    });
+    // Rewrites replies on the way out
+    server.add_layer(|request: client_message::Message, context: &Context, next: Next<'_>| {
+        Ok(next.run(request, context)?.map(|response| match response {
+            server_message::Message::EchoMessage(echo) => {
+                server_message::Message::EchoMessage(EchoMessage { content: echo.content.to_uppercase() })
+            }
+            response => response,
+        }))
+    });
+    server.add_layer(Trace { name: "inner", events: Arc::clone(&events) });
+    let handle = server.start().expect("Failed to run server");
+    let mut client = client::Client::new("127.0.0.1", handle.local_addr().unwrap().port().into(), 2000);
+    assert!(client.connect().is_ok(), "Failed to connect to the server");
+
+    assert!(client.send(echo("hello")).is_ok(), "Failed to send message");
+    let response = client.receive().expect("Failed to receive response for EchoMessage");
+    let id = handle.server().connections()[0].id;
+    assert_eq!(response, reply(server_message::Message::EchoMessage(EchoMessage { content: format!("HELLO FROM {}", id) })));
+    assert_eq!(*events.lock().unwrap(), ["outer in", "inner in", "inner out", "outer out"]);
+
+    assert!(client.disconnect().is_ok(), "Failed to disconnect from the server");
+    handle.stop();
+    assert!(handle.wait().is_ok(), "Expected a clean shutdown");
+}
+
+/// Test to validate that a layer can reject requests by connection before they reach a pooled handler.
+#[test]
+fn test_layer_short_circuits_with_error() {
+    let _ = env_logger::builder().is_test(true).try_init();
+
+    let allowed = Arc::new(Mutex::new(Vec::new()));
+    let handled = Arc::new(AtomicU64::new(0));
+    let config = ServerConfig {
+        pools: vec![PoolConfig::new("math", 2, 8).handling(&[MessageKind::AddRequest])],
+        ..ServerConfig::default()
+    };
+    let mut server = Server::with_config("127.0.0.1:0", config).expect("Failed to start server");
+    server.add_layer(Auth { allowed: Arc::clone(&allowed) });
+    let counter = Arc::clone(&handled);
+    server.register_handler(MessageKind::AddRequest, move |request: client_message::Message, _: &Context| {
+        counter.fetch_add(1, Ordering::SeqCst);
+        match request {
+            client_message::Message::AddRequest(AddRequest { a, b }) => Ok(Some(server_message::Message::AddResponse(AddResponse { result: a + b }))),
+            _ => Err(HandlerError::invalid_argument("expected an AddRequest")),
+        }
+    });
+    let handle = server.start().expect("Failed to run server");
+    let port = handle.local_addr().unwrap().port();
+    let mut client = client::Client::new("127.0.0.1", port.into(), 2000);
+    assert!(client.connect().is_ok(), "Failed to connect to the server");
+
+    let add = client_message::Message::AddRequest(AddRequest { a: 1, b: 2 });
+    assert!(client.send(add.clone()).is_ok(), "Failed to send message");
+    match client.receive().expect("Failed to receive a response").message {
+        Some(server_message::Message::ErrorResponse(error)) => assert_eq!(error.code(), ErrorCode::PermissionDenied),
+        other => panic!("Expected an ErrorResponse, but received {:?}", other),
+    }
+    assert_eq!(handled.load(Ordering::SeqCst), 0, "Expected the handler not to run for a rejected request");
+    // Requests the layer does not guard still pass
+    assert!(client.send(echo("still here")).is_ok(), "Failed to send message");
+    assert_eq!(client.receive().unwrap(), reply(server_message::Message::EchoMessage(EchoMessage { content: "still here".to_string() })));
+
+    // Once the connection is allowed, its requests reach the handler
+    allowed.lock().unwrap().push(handle.server().connections()[0].id);
+    assert!(client.send(add).is_ok(), "Failed to send message");
+    assert_eq!(client.receive().unwrap(), reply(server_message::Message::AddResponse(AddResponse { result: 3 })));
+    assert_eq!(handled.load(Ordering::SeqCst), 1);
+
+    assert!(client.disconnect().is_ok(), "Failed to disconnect from the server");
+    handle.stop();
+    assert!(handle.wait().is_ok(), "Expected a clean shutdown");
+}
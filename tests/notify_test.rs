@@ -0,0 +1,114 @@
+#![cfg(unix)]
+
+use embedded_recruitment_task::{
+    message::{client_message, EchoMessage}, // Importing message types for client-server communication
+    notify::Notifier, // Importing service manager notifications
+    server::Server, // Importing server functionalities
+};
+use std::{
+    env, // For locating the temporary directory
+    fs, // For removing socket files
+    os::unix::net::UnixDatagram, // Stands in for the service manager's notification socket
+    path::PathBuf, // For socket paths
+    time::Duration, // For receive timeouts
+};
+
+#[allow(dead_code)] // Not every client helper is used by this suite
+mod client; // Declares a client module for client-related operations
+mod process; // Declares a module for driving the server binary
+
+use process::{attach, send_signal, server_command, wait_for_exit};
+
+/// A notification socket standing in for systemd, removed when dropped.
+struct FakeManager {
+    socket: UnixDatagram,
+    path: PathBuf,
+}
+
+impl FakeManager {
+    /// Binds a fresh notification socket in the temporary directory.
+    fn bind(name: &str) -> Self {
+        let path = env::temp_dir().join(format!("notify-{}-{}.sock", name, std::process::id()));
+        let _ = fs::remove_file(&path);
+        let socket = UnixDatagram::bind(&path).expect("Failed to bind the notification socket");
+        FakeManager { socket, path }
+    }
+
+    /// Receives the next notification, or `None` if nothing arrives before the timeout.
+    fn next(&self, timeout: Duration) -> Option<String> {
+        self.socket.set_read_timeout(Some(timeout)).unwrap();
+        let mut buffer = [0; 1024];
+        let len = self.socket.recv(&mut buffer).ok()?;
+        Some(String::from_utf8_lossy(&buffer[..len]).into_owned())
+    }
+}
+
+impl Drop for FakeManager {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// Test to validate that an embedded server reports readiness once it accepts and stopping on shutdown.
+#[test]
+fn test_server_notifies_ready_and_stopping() {
+    let manager = FakeManager::bind("library");
+    let mut server = Server::new("127.0.0.1:0").expect("Failed to start server");
+    server.set_notifier(Notifier::new(&manager.path).expect("Failed to create the notifier"));
+    let handle = server.start().expect("Failed to run server");
+    let port = handle.local_addr().unwrap().port();
+
+    assert_eq!(manager.next(Duration::from_secs(3)).as_deref(), Some("READY=1"));
+    let mut client = client::Client::new("127.0.0.1", port.into(), 2000);
+    assert!(client.connect().is_ok(), "Failed to connect to the server");
+    let echo_message = EchoMessage { content: "ready".to_string() };
+    assert!(client.send(client_message::Message::EchoMessage(echo_message)).is_ok(), "Failed to send message");
+    assert!(client.receive().is_ok(), "Expected a ready server to serve requests");
+    assert!(client.disconnect().is_ok(), "Failed to disconnect from the server");
+
+    // No watchdog was configured, so nothing else is sent until shutdown
+    assert_eq!(manager.next(Duration::from_millis(300)), None);
+    handle.stop();
+    assert_eq!(manager.next(Duration::from_secs(3)).as_deref(), Some("STOPPING=1"));
+    assert!(handle.wait().is_ok(), "Expected a clean shutdown");
+}
+
+/// Test to validate READY, WATCHDOG and STOPPING notifications from the binary under a supervisor.
+#[test]
+fn test_binary_notifies_supervisor() {
+    let manager = FakeManager::bind("binary");
+    let mut command = server_command(&["127.0.0.1:0"]);
+    command.env("NOTIFY_SOCKET", &manager.path).env("WATCHDOG_USEC", "1000000");
+    let mut server = attach(command.spawn().expect("Failed to start the server binary"));
+
+    assert_eq!(manager.next(Duration::from_secs(3)).as_deref(), Some("READY=1"));
+    for _ in 0..2 {
+        // Pings arrive every half watchdog interval
+        assert_eq!(manager.next(Duration::from_secs(2)).as_deref(), Some("WATCHDOG=1"));
+    }
+
+    send_signal(server.pid, "TERM");
+    assert_eq!(manager.next(Duration::from_secs(3)).as_deref(), Some("STOPPING=1"));
+    let status = wait_for_exit(&mut server.child, Duration::from_secs(5)).expect("Server did not exit after SIGTERM");
+    assert_eq!(status.code(), Some(0), "Expected a clean exit, got {}", status);
+}
+
+/// Test to validate that a watchdog addressed to another process is ignored.
+#[test]
+fn test_watchdog_for_other_process_is_ignored() {
+    let manager = FakeManager::bind("other-pid");
+    let mut command = server_command(&["127.0.0.1:0"]);
+    command
+        .env("NOTIFY_SOCKET", &manager.path)
+        .env("WATCHDOG_USEC", "200000")
+        .env("WATCHDOG_PID", "1");
+    let mut server = attach(command.spawn().expect("Failed to start the server binary"));
+
+    assert_eq!(manager.next(Duration::from_secs(3)).as_deref(), Some("READY=1"));
+    assert_eq!(manager.next(Duration::from_millis(500)), None, "Expected no watchdog pings");
+
+    send_signal(server.pid, "TERM");
+    assert_eq!(manager.next(Duration::from_secs(3)).as_deref(), Some("STOPPING=1"));
+    let status = wait_for_exit(&mut server.child, Duration::from_secs(5)).expect("Server did not exit after SIGTERM");
+    assert_eq!(status.code(), Some(0), "Expected a clean exit, got {}", status);
+}
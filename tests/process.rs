@@ -0,0 +1,98 @@
+#![cfg(unix)]
+#![allow(dead_code)] // Each suite uses a different subset of these helpers
+
+use std::{
+    io::{BufRead, BufReader}, // For reading the server's startup lines
+    process::{Child, ChildStdout, Command, ExitStatus, Stdio}, // For running the server binary
+    thread, // For polling
+    time::{Duration, Instant}, // For deadlines while waiting on processes
+};
+
+/// A running server binary and the startup information it printed.
+pub struct ServerProcess {
+    pub child: Child,
+    pub port: u16,
+    pub pid: u32,
+    stdout: BufReader<ChildStdout>,
+}
+
+impl ServerProcess {
+    /// Reads the next `Server running on ADDR (pid N)` line, returning the port and PID it names.
+    ///
+    /// Upgraded processes inherit stdout, so their startup lines arrive here too.
+    pub fn next_startup_line(&mut self) -> (u16, u32) {
+        let mut line = String::new();
+        self.stdout.read_line(&mut line).expect("Failed to read a startup line");
+        parse_startup_line(&line)
+    }
+}
+
+/// Returns a command for the server binary with logging discarded.
+pub fn server_command(args: &[&str]) -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_embedded-recruitment-task"));
+    command.args(args).stdout(Stdio::piped()).stderr(Stdio::null());
+    command
+}
+
+/// Starts the server binary with the given arguments and waits for its startup line.
+pub fn spawn_server_with_args(args: &[&str]) -> ServerProcess {
+    let child = server_command(args).spawn().expect("Failed to start the server binary");
+    attach(child)
+}
+
+/// Waits for the startup line of an already spawned server binary.
+pub fn attach(mut child: Child) -> ServerProcess {
+    let stdout = BufReader::new(child.stdout.take().expect("Expected piped stdout"));
+    let mut process = ServerProcess { child, port: 0, pid: 0, stdout };
+    let (port, pid) = process.next_startup_line();
+    process.port = port;
+    process.pid = pid;
+    process
+}
+
+/// Parses `Server running on ADDR (pid N)`.
+fn parse_startup_line(line: &str) -> (u16, u32) {
+    let parsed = line.trim().strip_prefix("Server running on ").and_then(|rest| {
+        let (addr, pid) = rest.split_once(" (pid ")?;
+        let port = addr.rsplit(':').next()?.parse().ok()?;
+        let pid = pid.strip_suffix(')')?.parse().ok()?;
+        Some((port, pid))
+    });
+    parsed.unwrap_or_else(|| panic!("Unexpected startup line: {:?}", line))
+}
+
+/// Delivers a signal to a process.
+pub fn send_signal(pid: u32, signal: &str) {
+    let status = Command::new("kill")
+        .arg(format!("-{}", signal))
+        .arg(pid.to_string())
+        .status()
+        .expect("Failed to run kill");
+    assert!(status.success(), "kill -{} {} failed", signal, pid);
+}
+
+/// Returns true while a process (not necessarily our child) exists.
+pub fn is_alive(pid: u32) -> bool {
+    Command::new("kill")
+        .arg("-0")
+        .arg(pid.to_string())
+        .stderr(Stdio::null())
+        .status()
+        .map(|status| status.success())
+        .unwrap_or(false)
+}
+
+/// Waits for a child process to exit.
+///
+/// # Returns
+/// - The exit status, or `None` if the process is still running at the deadline.
+pub fn wait_for_exit(child: &mut Child, timeout: Duration) -> Option<ExitStatus> {
+    let deadline = Instant::now() + timeout;
+    while Instant::now() < deadline {
+        if let Some(status) = child.try_wait().expect("Failed to poll the server process") {
+            return Some(status);
+        }
+        thread::sleep(Duration::from_millis(20));
+    }
+    None
+}
@@ -0,0 +1,184 @@
+use embedded_recruitment_task::{
+    codec::{encode_frame, FrameReader}, // Importing framing shared by every protocol
+    config::{PoolConfig, ServerConfig}, // Importing server settings
+    handler::{Context, ErrorCode, HandlerError, HandlerResult}, // Importing request handlers and their error codes
+    protocol::Protocol, // Importing the protocol abstraction
+    server::ProtocolServer, // Importing the server generic over protocols
+};
+use prost::Message; // For encoding and decoding the custom schema
+use std::{
+    collections::HashMap, // For the key-value store
+    fmt, // For naming operations
+    io::{Read, Write}, // For talking to the server over a raw stream
+    net::TcpStream, // For connecting to the server
+    str::FromStr, // For parsing operation names in pool settings
+    sync::{Arc, Mutex}, // For sharing the store with handlers
+    time::Duration, // For read and polling timeouts
+};
+
+#[allow(dead_code)] // Only the polling helper is used by this suite
+mod client; // Declares a client module for client-related operations
+
+use client::wait_until; // Polls for asynchronous server effects
+
+/// A request of a key-value schema unrelated to messages.proto.
+#[derive(Clone, PartialEq, Message)]
+struct KvRequest {
+    #[prost(string, tag = "1")]
+    key: String,
+    #[prost(string, optional, tag = "2")]
+    value: Option<String>,
+    #[prost(bool, tag = "3")]
+    delete: bool,
+}
+
+/// A reply of the key-value schema.
+#[derive(Clone, PartialEq, Message)]
+struct KvResponse {
+    #[prost(string, optional, tag = "1")]
+    value: Option<String>,
+    #[prost(int32, tag = "2")]
+    error_code: i32,
+    #[prost(string, tag = "3")]
+    error: String,
+}
+
+/// Operations of the key-value schema.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Op {
+    Get,
+    Put,
+    Delete,
+}
+
+impl fmt::Display for Op {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Op::Get => "get",
+            Op::Put => "put",
+            Op::Delete => "delete",
+        })
+    }
+}
+
+impl FromStr for Op {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, ()> {
+        [Op::Get, Op::Put, Op::Delete].into_iter().find(|op| op.to_string() == s).ok_or(())
+    }
+}
+
+/// The key-value protocol.
+struct Kv;
+
+impl Protocol for Kv {
+    type Request = KvRequest;
+    type Response = KvResponse;
+    type Message = KvRequest;
+    type Reply = KvResponse;
+    type Kind = Op;
+
+    fn open(request: KvRequest) -> Option<KvRequest> {
+        Some(request)
+    }
+
+    fn kind(request: &KvRequest) -> Op {
+        match (&request.value, request.delete) {
+            (_, true) => Op::Delete,
+            (Some(_), false) => Op::Put,
+            (None, false) => Op::Get,
+        }
+    }
+
+    fn reply(reply: KvResponse) -> KvResponse {
+        reply
+    }
+
+    fn error(error: &HandlerError, _retry_after: Option<Duration>) -> KvResponse {
+        KvResponse { value: None, error_code: error.code.into(), error: error.message.clone() }
+    }
+}
+
+/// A client speaking the key-value protocol over a raw stream.
+struct KvClient {
+    stream: TcpStream,
+    frames: FrameReader,
+}
+
+impl KvClient {
+    fn connect(port: u16) -> Self {
+        let stream = TcpStream::connect(("127.0.0.1", port)).expect("Failed to connect to the server");
+        stream.set_read_timeout(Some(Duration::from_secs(2))).unwrap();
+        KvClient { stream, frames: FrameReader::new(1024) }
+    }
+
+    fn call(&mut self, request: KvRequest) -> KvResponse {
+        self.stream.write_all(&encode_frame(&request)).expect("Failed to send request");
+        loop {
+            if let Some(frame) = self.frames.next_frame().unwrap() {
+                return KvResponse::decode(frame.as_slice()).expect("Failed to decode response");
+            }
+            let mut buffer = [0; 256];
+            let read = self.stream.read(&mut buffer).expect("Failed to receive response");
+            assert!(read > 0, "Server closed the connection");
+            self.frames.extend(&buffer[..read]);
+        }
+    }
+}
+
+/// Utility function to build a request.
+fn request(key: &str, value: Option<&str>, delete: bool) -> KvRequest {
+    KvRequest { key: key.to_string(), value: value.map(str::to_string), delete }
+}
+
+/// Test to validate that the server core serves a schema other than messages.proto.
+#[test]
+fn test_server_serves_custom_protocol() {
+    let _ = env_logger::builder().is_test(true).try_init();
+
+    let store = Arc::new(Mutex::new(HashMap::new()));
+    let config = ServerConfig {
+        pools: vec![PoolConfig::new("writes", 2, 8).handling(&[Op::Put])],
+        ..ServerConfig::default()
+    };
+    let mut server = ProtocolServer::<Kv>::with_config("127.0.0.1:0", config).expect("Failed to start server");
+    let puts = Arc::clone(&store);
+    server.register_handler(Op::Put, move |request: KvRequest, _: &Context| -> HandlerResult<Kv> {
+        let previous = puts.lock().unwrap().insert(request.key, request.value.unwrap_or_default());
+        Ok(Some(KvResponse { value: previous, ..KvResponse::default() }))
+    });
+    let gets = Arc::clone(&store);
+    server.register_handler(Op::Get, move |request: KvRequest, _: &Context| -> HandlerResult<Kv> {
+        match gets.lock().unwrap().get(&request.key) {
+            Some(value) => Ok(Some(KvResponse { value: Some(value.clone()), ..KvResponse::default() })),
+            None => Err(HandlerError::new(ErrorCode::Custom(404), format!("no key `{}`", request.key))),
+        }
+    });
+    let handle = server.start().expect("Failed to run server");
+    let mut client = KvClient::connect(handle.local_addr().unwrap().port());
+
+    assert_eq!(client.call(request("colour", Some("red"), false)).value, None);
+    assert_eq!(client.call(request("colour", Some("blue"), false)).value.as_deref(), Some("red"));
+    assert_eq!(client.call(request("colour", None, false)).value.as_deref(), Some("blue"));
+    assert!(
+        wait_until(Duration::from_secs(2), || handle.server().pool_stats()[0].completed == 2),
+        "Expected writes to run on their pool"
+    );
+
+    let missing = client.call(request("size", None, false));
+    assert_eq!((missing.error_code, missing.error.as_str()), (404, "no key `size`"), "Expected the protocol's own code");
+    // Custom protocols start without built-in handlers
+    let unhandled = client.call(request("colour", None, true));
+    assert_eq!(unhandled.error_code, i32::from(ErrorCode::Unimplemented));
+
+    // Pushes use the protocol's reply type
+    let id = handle.server().connections()[0].id;
+    let pushed = KvResponse { value: Some("pushed".to_string()), ..KvResponse::default() };
+    assert!(handle.server().send(id, pushed.clone()).is_ok(), "Failed to push a reply");
+    assert_eq!(client.call(request("colour", None, false)).value, pushed.value);
+
+    drop(client);
+    handle.stop();
+    assert!(handle.wait().is_ok(), "Expected a clean shutdown");
+}
@@ -0,0 +1,395 @@
+use embedded_recruitment_task::{
+    config::{OutboundConfig, ServerConfig, Timeouts}, // Importing server settings
+    broker, // Importing topic matching
+    message::{
+        client_message, server_message, ConnectRequest, DisconnectRequest, ErrorCode, LastWill, PingRequest, Publication,
+        PublishRequest, SubscribeRequest, UnsubscribeRequest,
+    }, // Importing message types for client-server communication
+    outbound::SlowConsumerPolicy, // Importing slow-consumer handling
+    server::Server, // Importing server functionalities
+    socket::SocketOptions, // Importing socket options for small buffers
+};
+use std::time::{Duration, Instant}; // For timeouts and polling deadlines
+
+#[allow(dead_code)] // Not every client helper is used by this suite
+mod client; // Declares a client module for client-related operations
+
+use client::wait_until; // Polls for asynchronous server effects
+
+/// Utility function to send a request and return the reply.
+fn call(client: &mut client::Client, request: client_message::Message) -> server_message::Message {
+    assert!(client.send(request).is_ok(), "Failed to send message");
+    client.receive().expect("Failed to receive a response").message.expect("Expected a reply")
+}
+
+/// Utility function to subscribe to `topic` and return the number of subscriptions.
+fn subscribe(client: &mut client::Client, topic: &str) -> u32 {
+    match call(client, client_message::Message::SubscribeRequest(SubscribeRequest { topic: topic.to_string() })) {
+        server_message::Message::SubscribeResponse(response) => response.subscriptions,
+        other => panic!("Expected a SubscribeResponse, but received {:?}", other),
+    }
+}
+
+/// Utility function to publish `payload` on `topic` and return the number of subscribers reached.
+fn publish(client: &mut client::Client, topic: &str, payload: &[u8]) -> u32 {
+    let request = PublishRequest { topic: topic.to_string(), payload: payload.to_vec(), retain: false };
+    publish_request(client, request)
+}
+
+/// Utility function to publish `payload` as the retained message of `topic`.
+fn retain(client: &mut client::Client, topic: &str, payload: &[u8]) -> u32 {
+    let request = PublishRequest { topic: topic.to_string(), payload: payload.to_vec(), retain: true };
+    publish_request(client, request)
+}
+
+/// Utility function to send a publish request and return the number of subscribers reached.
+fn publish_request(client: &mut client::Client, request: PublishRequest) -> u32 {
+    match call(client, client_message::Message::PublishRequest(request)) {
+        server_message::Message::PublishResponse(response) => response.subscribers,
+        other => panic!("Expected a PublishResponse, but received {:?}", other),
+    }
+}
+
+/// Utility function to receive the next publication.
+fn next_publication(client: &mut client::Client) -> Publication {
+    match client.receive().expect("Failed to receive a publication").message {
+        Some(server_message::Message::Publication(publication)) => publication,
+        other => panic!("Expected a Publication, but received {:?}", other),
+    }
+}
+
+/// Utility function to build a publication as a subscriber receives it.
+fn publication(topic: &str, payload: &[u8], retained: bool) -> Publication {
+    Publication { topic: topic.to_string(), payload: payload.to_vec(), retained }
+}
+
+/// Utility function to send a request that should fail and return the error code.
+fn error_code(client: &mut client::Client, request: client_message::Message) -> ErrorCode {
+    match call(client, request) {
+        server_message::Message::ErrorResponse(error) => error.code(),
+        other => panic!("Expected an ErrorResponse, but received {:?}", other),
+    }
+}
+
+/// Utility function to connect a client to the server.
+fn connect(port: u16) -> client::Client {
+    let mut client = client::Client::new("127.0.0.1", port.into(), 2000);
+    assert!(client.connect().is_ok(), "Failed to connect to the server");
+    client
+}
+
+/// Test to validate that publications reach every subscriber of their topic, in order.
+#[test]
+fn test_publications_fan_out_to_subscribers() {
+    let _ = env_logger::builder().is_test(true).try_init();
+
+    let server = Server::new("127.0.0.1:0").expect("Failed to start server");
+    let handle = server.start().expect("Failed to run server");
+    let port = handle.local_addr().unwrap().port();
+    let broker = handle.server().broker().expect("Expected the built-in broker");
+
+    let mut alice = connect(port);
+    let mut bob = connect(port);
+    let mut carol = connect(port);
+    let mut publisher = connect(port);
+    assert_eq!(subscribe(&mut alice, "telemetry"), 1);
+    assert_eq!(subscribe(&mut alice, "telemetry"), 1, "Expected subscribing twice to be a no-op");
+    assert_eq!(subscribe(&mut bob, "telemetry"), 1);
+    assert_eq!(subscribe(&mut bob, "alerts"), 2);
+    assert_eq!(subscribe(&mut carol, "alerts"), 1);
+    assert_eq!(broker.subscribers("telemetry").len(), 2);
+
+    for i in 0..10u8 {
+        assert_eq!(publish(&mut publisher, "telemetry", &[i]), 2);
+    }
+    assert_eq!(publish(&mut publisher, "alerts", b"fire"), 2);
+    assert_eq!(publish(&mut publisher, "nobody", b"hello"), 0);
+    for subscriber in [&mut alice, &mut bob] {
+        for i in 0..10u8 {
+            assert_eq!(next_publication(subscriber), publication("telemetry", &[i], false));
+        }
+    }
+    assert_eq!(next_publication(&mut bob).payload, b"fire");
+    assert_eq!(next_publication(&mut carol), publication("alerts", b"fire", false));
+
+    // Unsubscribing stops delivery on that topic only
+    match call(&mut bob, client_message::Message::UnsubscribeRequest(UnsubscribeRequest { topic: "telemetry".to_string() })) {
+        server_message::Message::UnsubscribeResponse(response) => assert_eq!(response.subscriptions, 1),
+        other => panic!("Expected an UnsubscribeResponse, but received {:?}", other),
+    }
+    assert_eq!(publish(&mut publisher, "telemetry", b"only alice"), 1);
+    assert_eq!(next_publication(&mut alice).payload, b"only alice");
+
+    // Topics are validated
+    let empty = client_message::Message::PublishRequest(PublishRequest::default());
+    assert_eq!(error_code(&mut publisher, empty), ErrorCode::InvalidArgument);
+
+    // Disconnecting removes every subscription of the connection
+    let bob_id = broker.subscribers("alerts")[0];
+    assert_eq!(broker.subscriptions(bob_id), ["alerts"]);
+    assert!(bob.disconnect().is_ok(), "Failed to disconnect from the server");
+    assert!(wait_until(Duration::from_secs(2), || broker.subscribers("alerts").len() == 1), "Expected bob's subscriptions to be removed");
+    assert!(broker.subscriptions(bob_id).is_empty());
+    assert!(alice.disconnect().is_ok(), "Failed to disconnect from the server");
+    assert!(wait_until(Duration::from_secs(2), || broker.subscribers("telemetry").is_empty()));
+    assert_eq!(publish(&mut publisher, "telemetry", b"gone"), 0);
+
+    handle.stop();
+    assert!(handle.wait().is_ok(), "Expected a clean shutdown");
+}
+
+/// Test to validate that a subscriber that stops reading loses publications instead of stalling publishers.
+#[test]
+fn test_slow_subscriber_hits_its_queue_limit() {
+    let _ = env_logger::builder().is_test(true).try_init();
+
+    let small_buffers = SocketOptions {
+        send_buffer_size: Some(32 * 1024),
+        recv_buffer_size: Some(32 * 1024),
+        nodelay: Some(true),
+        ..SocketOptions::default()
+    };
+    let config = ServerConfig {
+        outbound: OutboundConfig { capacity: 2, policy: SlowConsumerPolicy::Block(Duration::from_secs(10)) },
+        socket: small_buffers,
+        subscriber_queue: 4,
+        ..ServerConfig::default()
+    };
+    let server = Server::with_config("127.0.0.1:0", config).expect("Failed to start server");
+    let handle = server.start().expect("Failed to run server");
+    let port = handle.local_addr().unwrap().port();
+    let broker = handle.server().broker().expect("Expected the built-in broker");
+
+    let mut slow = client::Client::new("127.0.0.1", port.into(), 5000);
+    slow.set_socket_options(small_buffers);
+    assert!(slow.connect().is_ok(), "Failed to connect to the server");
+    assert_eq!(subscribe(&mut slow, "video"), 1);
+
+    // Publish far more than the socket buffers, outbound queue and mailbox hold together
+    let started = Instant::now();
+    let mut queued = 0;
+    for i in 0..200u32 {
+        let mut payload = i.to_be_bytes().to_vec();
+        payload.resize(64 * 1024, 0);
+        queued += broker.publish("video", payload, false);
+    }
+    assert!(started.elapsed() < Duration::from_secs(2), "Expected publishing not to wait for the subscriber");
+    assert!(queued < 200, "Expected the subscriber queue to overflow");
+    assert_eq!(broker.dropped(), 200 - queued as u64);
+
+    // What was queued arrives in publication order
+    let mut sequences = Vec::new();
+    for _ in 0..queued {
+        let publication = next_publication(&mut slow);
+        sequences.push(u32::from_be_bytes(publication.payload[..4].try_into().unwrap()));
+    }
+    assert_eq!(sequences[0], 0, "Expected the first publication to be delivered");
+    assert!(sequences.windows(2).all(|pair| pair[0] < pair[1]), "Expected publications in order: {:?}", sequences);
+
+    assert!(slow.disconnect().is_ok(), "Failed to disconnect from the server");
+    handle.stop();
+    assert!(handle.wait().is_ok(), "Expected a clean shutdown");
+}
+
+/// Test to validate MQTT topic filter matching and validation.
+#[test]
+fn test_topic_filters() {
+    let cases = [
+        ("sensors/+/temperature", "sensors/kitchen/temperature", true),
+        ("sensors/+/temperature", "sensors/kitchen/humidity", false),
+        ("sensors/+/temperature", "sensors/kitchen/fridge/temperature", false),
+        ("sensors/#", "sensors", true),
+        ("sensors/#", "sensors/kitchen/temperature", true),
+        ("sensors/#", "sensor", false),
+        ("+", "sensors", true),
+        ("+", "sensors/kitchen", false),
+        ("+/+", "/kitchen", true),
+        ("#", "sensors/kitchen", true),
+        ("#", "$SYS/uptime", false),
+        ("$SYS/#", "$SYS/uptime", true),
+        ("sensors", "sensors", true),
+        ("sensors", "sensors/kitchen", false),
+    ];
+    for (filter, topic, expected) in cases {
+        assert_eq!(broker::topic_matches(filter, topic), expected, "Matching {} against {}", topic, filter);
+    }
+
+    for filter in ["a/+/b", "a/#", "#", "+", "/"] {
+        assert!(broker::validate_filter(filter).is_ok(), "Expected {} to be a valid filter", filter);
+    }
+    for filter in ["", "a/#/b", "a/b#", "a+/b"] {
+        assert!(broker::validate_filter(filter).is_err(), "Expected {} to be rejected", filter);
+    }
+    assert!(broker::validate_topic("a/b").is_ok());
+    assert!(broker::validate_topic("a/+").is_err(), "Expected wildcards to be rejected in topics");
+}
+
+/// Test to validate that wildcard subscriptions receive every matching publication once.
+#[test]
+fn test_wildcard_subscriptions() {
+    let _ = env_logger::builder().is_test(true).try_init();
+
+    let server = Server::new("127.0.0.1:0").expect("Failed to start server");
+    let handle = server.start().expect("Failed to run server");
+    let port = handle.local_addr().unwrap().port();
+
+    let mut subscriber = connect(port);
+    let mut publisher = connect(port);
+    assert_eq!(subscribe(&mut subscriber, "sensors/+/temperature"), 1);
+    assert_eq!(subscribe(&mut subscriber, "alerts/#"), 2);
+    assert_eq!(subscribe(&mut subscriber, "alerts/+"), 3);
+
+    assert_eq!(publish(&mut publisher, "sensors/kitchen/temperature", b"21"), 1);
+    assert_eq!(publish(&mut publisher, "sensors/kitchen/humidity", b"40"), 0);
+    assert_eq!(publish(&mut publisher, "alerts", b"all"), 1);
+    assert_eq!(publish(&mut publisher, "alerts/fire", b"once"), 1, "Expected overlapping filters to deliver once");
+    assert_eq!(publish(&mut publisher, "alerts/fire/floor2", b"deep"), 1);
+    assert_eq!(next_publication(&mut subscriber), publication("sensors/kitchen/temperature", b"21", false));
+    assert_eq!(next_publication(&mut subscriber), publication("alerts", b"all", false));
+    assert_eq!(next_publication(&mut subscriber), publication("alerts/fire", b"once", false));
+    assert_eq!(next_publication(&mut subscriber), publication("alerts/fire/floor2", b"deep", false));
+
+    // Malformed filters and wildcard topics are rejected
+    let invalid = client_message::Message::SubscribeRequest(SubscribeRequest { topic: "alerts/#/fire".to_string() });
+    assert_eq!(error_code(&mut subscriber, invalid), ErrorCode::InvalidArgument);
+    let wildcard = client_message::Message::PublishRequest(PublishRequest { topic: "alerts/+".to_string(), ..PublishRequest::default() });
+    assert_eq!(error_code(&mut publisher, wildcard), ErrorCode::InvalidArgument);
+
+    handle.stop();
+    assert!(handle.wait().is_ok(), "Expected a clean shutdown");
+}
+
+/// Test to validate that new subscriptions receive the retained messages of matching topics.
+#[test]
+fn test_retained_messages() {
+    let _ = env_logger::builder().is_test(true).try_init();
+
+    let server = Server::new("127.0.0.1:0").expect("Failed to start server");
+    let handle = server.start().expect("Failed to run server");
+    let port = handle.local_addr().unwrap().port();
+    let broker = handle.server().broker().expect("Expected the built-in broker");
+
+    let mut publisher = connect(port);
+    assert_eq!(retain(&mut publisher, "lights/kitchen", b"off"), 0);
+    assert_eq!(retain(&mut publisher, "lights/kitchen", b"on"), 0, "Expected the latest retained message to win");
+    assert_eq!(retain(&mut publisher, "lights/hall", b"off"), 0);
+    assert_eq!(publish(&mut publisher, "lights/porch", b"on"), 0);
+    assert_eq!(broker.retained("lights/kitchen"), Some(b"on".to_vec()));
+    assert_eq!(broker.retained("lights/porch"), None, "Expected plain publications not to be retained");
+
+    // The retained messages may be pushed before the subscription is acknowledged
+    let mut subscriber = connect(port);
+    let request = client_message::Message::SubscribeRequest(SubscribeRequest { topic: "lights/+".to_string() });
+    assert!(subscriber.send(request).is_ok(), "Failed to send message");
+    let mut publications = Vec::new();
+    let mut acknowledged = None;
+    while acknowledged.is_none() || publications.len() < 2 {
+        match subscriber.receive().expect("Failed to receive a message").message {
+            Some(server_message::Message::SubscribeResponse(response)) => acknowledged = Some(response),
+            Some(server_message::Message::Publication(publication)) => publications.push(publication),
+            other => panic!("Expected a SubscribeResponse or Publication, but received {:?}", other),
+        }
+    }
+    assert_eq!(acknowledged.unwrap().retained, 2);
+    assert_eq!(publications, [publication("lights/hall", b"off", true), publication("lights/kitchen", b"on", true)]);
+
+    // An empty retained publication clears the topic's retained message
+    assert_eq!(retain(&mut publisher, "lights/hall", b""), 1);
+    assert_eq!(next_publication(&mut subscriber), publication("lights/hall", b"", false));
+    assert_eq!(broker.retained("lights/hall"), None);
+    let mut late = connect(port);
+    let request = client_message::Message::SubscribeRequest(SubscribeRequest { topic: "lights/hall".to_string() });
+    match call(&mut late, request) {
+        server_message::Message::SubscribeResponse(response) => assert_eq!(response.retained, 0),
+        other => panic!("Expected a SubscribeResponse, but received {:?}", other),
+    }
+
+    handle.stop();
+    assert!(handle.wait().is_ok(), "Expected a clean shutdown");
+}
+
+/// Test to validate that last wills are published for dropped connections but not after a clean disconnect.
+#[test]
+fn test_last_will_on_unclean_disconnect() {
+    let _ = env_logger::builder().is_test(true).try_init();
+
+    let server = Server::new("127.0.0.1:0").expect("Failed to start server");
+    let handle = server.start().expect("Failed to run server");
+    let port = handle.local_addr().unwrap().port();
+    let broker = handle.server().broker().expect("Expected the built-in broker");
+
+    let mut watcher = connect(port);
+    assert_eq!(subscribe(&mut watcher, "status/#"), 1);
+    let connect_with_will = |device: &str| {
+        let mut client = connect(port);
+        let will = LastWill { topic: format!("status/{}", device), payload: b"offline".to_vec(), retain: true };
+        let request = client_message::Message::ConnectRequest(ConnectRequest { will: Some(will) });
+        assert!(matches!(call(&mut client, request), server_message::Message::ConnectResponse(_)));
+        client
+    };
+    let connections = || handle.server().connections().len();
+
+    // A connection that drops without a DisconnectRequest
+    let mut dropped = connect_with_will("sensor");
+    assert!(dropped.disconnect().is_ok(), "Failed to disconnect from the server");
+    assert_eq!(next_publication(&mut watcher), publication("status/sensor", b"offline", false));
+    assert_eq!(broker.retained("status/sensor"), Some(b"offline".to_vec()));
+
+    // A clean disconnect leaves no will
+    let mut clean = connect_with_will("lamp");
+    let request = client_message::Message::DisconnectRequest(DisconnectRequest {});
+    assert!(matches!(call(&mut clean, request), server_message::Message::DisconnectResponse(_)));
+    assert!(clean.disconnect().is_ok(), "Failed to disconnect from the server");
+    assert!(wait_until(Duration::from_secs(2), || connections() == 1), "Expected the clean connection to close");
+
+    // A connection closed by the server
+    let before: Vec<u64> = handle.server().connections().iter().map(|connection| connection.id).collect();
+    let _kicked = connect_with_will("camera");
+    let kicked_id = handle.server().connections().iter().map(|connection| connection.id).find(|id| !before.contains(id)).unwrap();
+    assert!(handle.server().disconnect(kicked_id), "Expected the connection to be known");
+    assert_eq!(next_publication(&mut watcher), publication("status/camera", b"offline", false), "Expected no will from the clean disconnect");
+    assert_eq!(broker.retained("status/lamp"), None);
+
+    handle.stop();
+    assert!(handle.wait().is_ok(), "Expected a clean shutdown");
+}
+
+/// Test to validate that delivered publications and pings keep subscribers within the idle timeout.
+#[test]
+fn test_pushes_and_pings_keep_subscribers_connected() {
+    let _ = env_logger::builder().is_test(true).try_init();
+
+    let idle = Duration::from_millis(300);
+    let config = ServerConfig { timeouts: Timeouts { idle: Some(idle), ..Timeouts::default() }, ..ServerConfig::default() };
+    let handle = Server::with_config("127.0.0.1:0", config).unwrap().start().expect("Failed to run server");
+    let port = handle.local_addr().unwrap().port();
+    let broker = handle.server().broker().expect("Expected the built-in broker");
+
+    let mut fed = connect(port);
+    let mut pinging = connect(port);
+    let mut silent = connect(port);
+    let mut publisher = connect(port);
+    assert_eq!(subscribe(&mut fed, "telemetry"), 1);
+    assert_eq!(subscribe(&mut pinging, "alarms"), 1);
+    assert_eq!(subscribe(&mut silent, "alarms"), 1);
+
+    // The fed subscriber never sends a request, yet the publications it receives keep it connected
+    let deadline = Instant::now() + idle * 3;
+    while Instant::now() < deadline {
+        assert_eq!(publish(&mut publisher, "telemetry", b"reading"), 1);
+        assert_eq!(next_publication(&mut fed), publication("telemetry", b"reading", false));
+        let ping = client_message::Message::PingRequest(PingRequest {});
+        assert!(matches!(call(&mut pinging, ping), server_message::Message::PingResponse(_)), "Expected a PingResponse");
+        std::thread::sleep(idle / 3);
+    }
+    assert!(wait_until(Duration::from_secs(2), || broker.subscribers("alarms").len() == 1), "Expected the silent subscriber to time out");
+    assert!(silent.receive().is_err(), "Expected the silent subscriber to be closed");
+
+    assert_eq!(publish(&mut publisher, "telemetry", b"still here"), 1);
+    assert_eq!(next_publication(&mut fed), publication("telemetry", b"still here", false));
+    assert_eq!(publish(&mut publisher, "alarms", b"still here"), 1);
+    assert_eq!(next_publication(&mut pinging), publication("alarms", b"still here", false));
+
+    handle.stop();
+    assert!(handle.wait().is_ok(), "Expected a clean shutdown");
+}
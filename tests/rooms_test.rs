@@ -0,0 +1,252 @@
+use embedded_recruitment_task::{
+    handler::{Context, HandlerResult}, // Importing request handlers
+    message::{
+        client_message, server_message, EchoMessage, ErrorCode, JoinRoomRequest, LeaveRoomRequest, RoomEvent, RoomEventKind,
+        RoomMember, RoomMembersRequest, RoomMessage, RoomMessageRequest,
+    }, // Importing message types for client-server communication
+    router::MessageKind, // Importing message types handlers are registered for
+    server::Server, // Importing server functionalities
+};
+use std::{
+    collections::HashMap, // For grouping received messages by sender
+    thread, // For running clients concurrently
+    time::Duration, // For timeouts
+};
+
+#[allow(dead_code)] // Not every client helper is used by this suite
+mod client; // Declares a client module for client-related operations
+
+use client::wait_until; // Polls for asynchronous server effects
+
+/// Utility function to start a server whose echo handler logs the session in under the echoed name.
+fn create_server() -> Server {
+    let mut server = Server::new("127.0.0.1:0").expect("Failed to start server");
+    server.register_handler(MessageKind::EchoMessage, |request: client_message::Message, context: &Context| -> HandlerResult {
+        if let client_message::Message::EchoMessage(echo) = &request {
+            context.session().set_identity(echo.content.as_str());
+        }
+        Ok(Some(server_message::Message::EchoMessage(EchoMessage { content: "welcome".to_string() })))
+    });
+    server
+}
+
+/// Utility function to connect a client and log it in as `identity`.
+fn login(port: u16, identity: &str) -> client::Client {
+    let mut client = client::Client::new("127.0.0.1", port.into(), 2000);
+    assert!(client.connect().is_ok(), "Failed to connect to the server");
+    let login = client_message::Message::EchoMessage(EchoMessage { content: identity.to_string() });
+    assert!(matches!(call(&mut client, login), server_message::Message::EchoMessage(_)), "Expected to be logged in");
+    client
+}
+
+/// Utility function to send a request and return the reply.
+fn call(client: &mut client::Client, request: client_message::Message) -> server_message::Message {
+    assert!(client.send(request).is_ok(), "Failed to send message");
+    client.receive().expect("Failed to receive a response").message.expect("Expected a reply")
+}
+
+/// Utility function to join `room` and return its members.
+fn join(client: &mut client::Client, room: &str) -> Vec<RoomMember> {
+    match call(client, client_message::Message::JoinRoomRequest(JoinRoomRequest { room: room.to_string() })) {
+        server_message::Message::JoinRoomResponse(response) => response.members,
+        other => panic!("Expected a JoinRoomResponse, but received {:?}", other),
+    }
+}
+
+/// Utility function to leave `room` and return whether the client was a member.
+fn leave(client: &mut client::Client, room: &str) -> bool {
+    match call(client, client_message::Message::LeaveRoomRequest(LeaveRoomRequest { room: room.to_string() })) {
+        server_message::Message::LeaveRoomResponse(response) => response.was_member,
+        other => panic!("Expected a LeaveRoomResponse, but received {:?}", other),
+    }
+}
+
+/// Utility function to build a request sending `content` to `room`.
+fn room_message(room: &str, content: &str) -> client_message::Message {
+    client_message::Message::RoomMessageRequest(RoomMessageRequest { room: room.to_string(), content: content.to_string() })
+}
+
+/// Utility function to send `content` to `room` and return the number of recipients.
+fn say(client: &mut client::Client, room: &str, content: &str) -> u32 {
+    match call(client, room_message(room, content)) {
+        server_message::Message::RoomMessageResponse(response) => response.recipients,
+        other => panic!("Expected a RoomMessageResponse, but received {:?}", other),
+    }
+}
+
+/// Utility function to receive the next room message.
+fn next_message(client: &mut client::Client) -> RoomMessage {
+    match client.receive().expect("Failed to receive a room message").message {
+        Some(server_message::Message::RoomMessage(message)) => message,
+        other => panic!("Expected a RoomMessage, but received {:?}", other),
+    }
+}
+
+/// Utility function to receive the next room event.
+fn next_event(client: &mut client::Client) -> RoomEvent {
+    match client.receive().expect("Failed to receive a room event").message {
+        Some(server_message::Message::RoomEvent(event)) => event,
+        other => panic!("Expected a RoomEvent, but received {:?}", other),
+    }
+}
+
+/// Utility function to return the identities of `members`.
+fn identities(members: &[RoomMember]) -> Vec<&str> {
+    members.iter().map(|member| member.identity.as_str()).collect()
+}
+
+/// Test to validate joining, leaving, member lists and the notifications members receive.
+#[test]
+fn test_room_membership_and_notifications() {
+    let _ = env_logger::builder().is_test(true).try_init();
+
+    let handle = create_server().start().expect("Failed to run server");
+    let port = handle.local_addr().unwrap().port();
+    let rooms = handle.server().rooms().expect("Expected the built-in rooms");
+
+    let mut alice = login(port, "alice");
+    let mut bob = login(port, "bob");
+    let mut carol = login(port, "carol");
+    assert_eq!(identities(&join(&mut alice, "lobby")), ["alice"]);
+    let members = join(&mut bob, "lobby");
+    assert_eq!(identities(&members), ["alice", "bob"]);
+    let (alice_id, bob_id) = (members[0].connection_id, members[1].connection_id);
+    assert_eq!(identities(&join(&mut bob, "lobby")), ["alice", "bob"], "Expected joining twice to be a no-op");
+    let event = next_event(&mut alice);
+    assert_eq!((event.room.as_str(), event.kind(), event.member.unwrap().identity.as_str()), ("lobby", RoomEventKind::Joined, "bob"));
+
+    // Anyone can list the members, but only members can talk
+    match call(&mut carol, client_message::Message::RoomMembersRequest(RoomMembersRequest { room: "lobby".to_string() })) {
+        server_message::Message::RoomMembersResponse(response) => assert_eq!(identities(&response.members), ["alice", "bob"]),
+        other => panic!("Expected a RoomMembersResponse, but received {:?}", other),
+    }
+    match call(&mut carol, room_message("lobby", "let me in")) {
+        server_message::Message::ErrorResponse(error) => assert_eq!(error.code(), ErrorCode::PermissionDenied),
+        other => panic!("Expected an ErrorResponse, but received {:?}", other),
+    }
+    match call(&mut carol, client_message::Message::JoinRoomRequest(JoinRoomRequest::default())) {
+        server_message::Message::ErrorResponse(error) => assert_eq!(error.code(), ErrorCode::InvalidArgument),
+        other => panic!("Expected an ErrorResponse, but received {:?}", other),
+    }
+
+    // Messages reach every member except the sender
+    assert_eq!(say(&mut bob, "lobby", "hi"), 1);
+    let message = next_message(&mut alice);
+    assert_eq!((message.room.as_str(), message.content.as_str()), ("lobby", "hi"));
+    assert_eq!(message.sender, Some(RoomMember { connection_id: bob_id, identity: "bob".to_string() }));
+    assert_eq!(say(&mut alice, "lobby", "hello"), 1);
+    assert_eq!(next_message(&mut bob).content, "hello", "Expected bob not to receive his own message");
+
+    // Leaving tells the remaining members
+    assert!(leave(&mut bob, "lobby"));
+    assert!(!leave(&mut bob, "lobby"), "Expected leaving twice to report no membership");
+    let event = next_event(&mut alice);
+    assert_eq!((event.kind(), event.member.unwrap().connection_id), (RoomEventKind::Left, bob_id));
+    assert_eq!(say(&mut alice, "lobby", "anyone?"), 0);
+
+    // Disconnecting leaves every room the connection joined
+    join(&mut carol, "lobby");
+    join(&mut carol, "garden");
+    assert_eq!(next_event(&mut alice).kind(), RoomEventKind::Joined);
+    let carol_id = rooms.members("garden")[0].connection_id;
+    assert_eq!(rooms.rooms_of(carol_id), ["garden", "lobby"]);
+    assert!(carol.disconnect().is_ok(), "Failed to disconnect from the server");
+    let event = next_event(&mut alice);
+    assert_eq!((event.kind(), event.member.unwrap().identity.as_str()), (RoomEventKind::Left, "carol"));
+    assert!(wait_until(Duration::from_secs(2), || rooms.rooms_of(carol_id).is_empty()), "Expected carol's rooms to be left");
+    assert!(rooms.members("garden").is_empty(), "Expected the empty room to be removed");
+
+    // The last member leaving removes the room
+    assert!(leave(&mut alice, "lobby"));
+    assert!(rooms.members("lobby").is_empty());
+    assert!(rooms.rooms_of(alice_id).is_empty());
+
+    handle.stop();
+    assert!(handle.wait().is_ok(), "Expected a clean shutdown");
+}
+
+/// Test to validate that concurrent senders' messages reach every other member in one consistent order.
+#[test]
+fn test_concurrent_room_messages_keep_order() {
+    let _ = env_logger::builder().is_test(true).try_init();
+
+    const MEMBERS: usize = 4; // Clients talking at once
+    const MESSAGES: usize = 50; // Messages each client sends
+
+    let handle = create_server().start().expect("Failed to run server");
+    let port = handle.local_addr().unwrap().port();
+
+    // Everyone joins before anyone talks, so every member hears every other member
+    let mut clients = Vec::new();
+    for i in 0..MEMBERS {
+        let mut client = login(port, &format!("member-{}", i));
+        assert_eq!(join(&mut client, "lobby").len(), i + 1);
+        clients.push(client);
+    }
+    for (i, client) in clients.iter_mut().enumerate() {
+        for _ in i + 1..MEMBERS {
+            assert_eq!(next_event(client).kind(), RoomEventKind::Joined);
+        }
+    }
+
+    // Each client sends its messages while receiving the others', setting pushes aside until its reply arrives
+    let threads: Vec<_> = clients
+        .into_iter()
+        .enumerate()
+        .map(|(i, mut client)| {
+            thread::spawn(move || {
+                let mut received = Vec::new();
+                for n in 0..MESSAGES {
+                    assert!(client.send(room_message("lobby", &format!("member-{} {}", i, n))).is_ok(), "Failed to send message");
+                    loop {
+                        match client.receive().expect("Failed to receive a response").message {
+                            Some(server_message::Message::RoomMessage(message)) => received.push(message),
+                            Some(server_message::Message::RoomMessageResponse(response)) => {
+                                assert_eq!(response.recipients as usize, MEMBERS - 1);
+                                break;
+                            }
+                            other => panic!("Expected a RoomMessageResponse, but received {:?}", other),
+                        }
+                    }
+                }
+                while received.len() < (MEMBERS - 1) * MESSAGES {
+                    received.push(next_message(&mut client));
+                }
+                (format!("member-{}", i), received)
+            })
+        })
+        .collect();
+    let received: Vec<(String, Vec<RoomMessage>)> = threads.into_iter().map(|thread| thread.join().expect("Client panicked")).collect();
+
+    // Every member hears each other member's messages exactly once, in the order they were sent
+    for (identity, messages) in &received {
+        let mut by_sender: HashMap<String, Vec<String>> = HashMap::new();
+        for message in messages {
+            let sender = message.sender.as_ref().expect("Expected a sender").identity.clone();
+            assert_ne!(&sender, identity, "Expected no echo of the member's own messages");
+            by_sender.entry(sender).or_default().push(message.content.clone());
+        }
+        assert_eq!(by_sender.len(), MEMBERS - 1);
+        for (sender, contents) in by_sender {
+            let expected: Vec<String> = (0..MESSAGES).map(|n| format!("{} {}", sender, n)).collect();
+            assert_eq!(contents, expected, "{} heard {} out of order", identity, sender);
+        }
+    }
+
+    // Any two members hear the messages of the remaining members interleaved the same way
+    for (a, a_messages) in &received {
+        for (b, b_messages) in &received {
+            let shared = |messages: &[RoomMessage]| -> Vec<String> {
+                messages
+                    .iter()
+                    .filter(|message| ![a, b].contains(&&message.sender.as_ref().unwrap().identity))
+                    .map(|message| message.content.clone())
+                    .collect()
+            };
+            assert_eq!(shared(a_messages), shared(b_messages), "{} and {} disagree on the order", a, b);
+        }
+    }
+
+    handle.stop();
+    assert!(handle.wait().is_ok(), "Expected a clean shutdown");
+}
@@ -0,0 +1,159 @@
+use embedded_recruitment_task::{
+    handler::{BuiltIn, Context, ErrorCode, HandlerError}, // Importing request handlers and their error codes
+    message::{
+        client_message, server_message, AddRequest, AddResponse, ConnectRequest, ConnectResponse, DisconnectRequest,
+        DisconnectResponse, EchoMessage, JoinRoomRequest, JoinRoomResponse, LeaveRoomRequest, LeaveRoomResponse,
+        PingRequest, PingResponse, PublishRequest, PublishResponse, RoomMembersRequest, RoomMembersResponse,
+        RoomMessageRequest, RoomMessageResponse, ServerMessage, SubscribeRequest, SubscribeResponse, UnsubscribeRequest,
+        UnsubscribeResponse,
+    }, // Importing message types for client-server communication
+    router::{self, MessageKind, Router}, // Importing the generated request routing
+    server::Server, // Importing server functionalities
+};
+use std::sync::atomic::{AtomicU64, Ordering}; // For counting routed requests
+
+#[allow(dead_code)] // Not every client helper is used by this suite
+mod client; // Declares a client module for client-related operations
+
+/// A router that reverses echoes, multiplies instead of adding, and counts the requests it served.
+#[derive(Default)]
+struct Reversing {
+    served: AtomicU64,
+}
+
+impl Router for Reversing {
+    fn echo_message(&self, request: EchoMessage, _context: &Context) -> Result<EchoMessage, HandlerError> {
+        self.served.fetch_add(1, Ordering::SeqCst);
+        Ok(EchoMessage { content: request.content.chars().rev().collect() })
+    }
+
+    fn add_request(&self, request: AddRequest, _context: &Context) -> Result<AddResponse, HandlerError> {
+        self.served.fetch_add(1, Ordering::SeqCst);
+        match request.a.checked_mul(request.b) {
+            Some(result) => Ok(AddResponse { result }),
+            None => Err(HandlerError::invalid_argument("product does not fit in 32 bits")),
+        }
+    }
+
+    fn subscribe_request(&self, request: SubscribeRequest, context: &Context) -> Result<SubscribeResponse, HandlerError> {
+        BuiltIn.subscribe_request(request, context)
+    }
+
+    fn unsubscribe_request(&self, request: UnsubscribeRequest, context: &Context) -> Result<UnsubscribeResponse, HandlerError> {
+        BuiltIn.unsubscribe_request(request, context)
+    }
+
+    fn publish_request(&self, request: PublishRequest, context: &Context) -> Result<PublishResponse, HandlerError> {
+        BuiltIn.publish_request(request, context)
+    }
+
+    fn connect_request(&self, request: ConnectRequest, context: &Context) -> Result<ConnectResponse, HandlerError> {
+        BuiltIn.connect_request(request, context)
+    }
+
+    fn disconnect_request(&self, request: DisconnectRequest, context: &Context) -> Result<DisconnectResponse, HandlerError> {
+        BuiltIn.disconnect_request(request, context)
+    }
+
+    fn join_room_request(&self, request: JoinRoomRequest, context: &Context) -> Result<JoinRoomResponse, HandlerError> {
+        BuiltIn.join_room_request(request, context)
+    }
+
+    fn leave_room_request(&self, request: LeaveRoomRequest, context: &Context) -> Result<LeaveRoomResponse, HandlerError> {
+        BuiltIn.leave_room_request(request, context)
+    }
+
+    fn room_members_request(&self, request: RoomMembersRequest, context: &Context) -> Result<RoomMembersResponse, HandlerError> {
+        BuiltIn.room_members_request(request, context)
+    }
+
+    fn room_message_request(&self, request: RoomMessageRequest, context: &Context) -> Result<RoomMessageResponse, HandlerError> {
+        BuiltIn.room_message_request(request, context)
+    }
+
+    fn ping_request(&self, request: PingRequest, context: &Context) -> Result<PingResponse, HandlerError> {
+        BuiltIn.ping_request(request, context)
+    }
+}
+
+/// Utility function to build the reply carrying `message`.
+fn reply(message: server_message::Message) -> ServerMessage {
+    ServerMessage { message: Some(message) }
+}
+
+/// Test to validate that the generated message kinds follow the oneof in messages.proto.
+#[test]
+fn test_message_kinds_follow_the_proto() {
+    let expected = [
+        MessageKind::EchoMessage,
+        MessageKind::AddRequest,
+        MessageKind::SubscribeRequest,
+        MessageKind::UnsubscribeRequest,
+        MessageKind::PublishRequest,
+        MessageKind::ConnectRequest,
+        MessageKind::DisconnectRequest,
+        MessageKind::JoinRoomRequest,
+        MessageKind::LeaveRoomRequest,
+        MessageKind::RoomMembersRequest,
+        MessageKind::RoomMessageRequest,
+        MessageKind::PingRequest,
+    ];
+    assert_eq!(MessageKind::ALL, expected);
+    for kind in MessageKind::ALL {
+        assert_eq!(kind.name().parse::<MessageKind>().unwrap(), kind);
+        assert_eq!(kind.to_string(), kind.name());
+    }
+    assert_eq!(MessageKind::AddRequest.name(), "add_request");
+    assert!("add".parse::<MessageKind>().is_err(), "Expected names other than proto fields to be rejected");
+
+    let request = client_message::Message::EchoMessage(EchoMessage::default());
+    assert_eq!(MessageKind::of(&request), MessageKind::EchoMessage);
+}
+
+/// Test to validate that `route` calls the method for each request and wraps its reply.
+#[test]
+fn test_route_wraps_replies() {
+    let context = Context::new(1, "127.0.0.1:9000".parse::<std::net::SocketAddr>().unwrap().into());
+    let request = client_message::Message::AddRequest(AddRequest { a: 6, b: 7 });
+    assert_eq!(
+        router::route(&BuiltIn, request.clone(), &context),
+        Ok(Some(server_message::Message::AddResponse(AddResponse { result: 13 })))
+    );
+
+    let reversing = Reversing::default();
+    assert_eq!(
+        router::route(&reversing, request, &context),
+        Ok(Some(server_message::Message::AddResponse(AddResponse { result: 42 })))
+    );
+    let overflow = client_message::Message::AddRequest(AddRequest { a: i32::MAX, b: 2 });
+    let error = router::route(&BuiltIn, overflow.clone(), &context).expect_err("Expected the sum to overflow");
+    assert_eq!(error, HandlerError::invalid_argument("sum overflows int32"));
+    let error = router::route(&reversing, overflow, &context).expect_err("Expected the router's error");
+    assert_eq!(error.code, ErrorCode::InvalidArgument);
+    assert_eq!(reversing.served.load(Ordering::SeqCst), 2);
+}
+
+/// Test to validate that a registered router serves every message type on a running server.
+#[test]
+fn test_registered_router_serves_requests() {
+    let _ = env_logger::builder().is_test(true).try_init();
+
+    let mut server = Server::new("127.0.0.1:0").expect("Failed to start server");
+    server.register_router(Reversing::default());
+    let handle = server.start().expect("Failed to run server");
+    let mut client = client::Client::new("127.0.0.1", handle.local_addr().unwrap().port().into(), 2000);
+    assert!(client.connect().is_ok(), "Failed to connect to the server");
+
+    let echo = client_message::Message::EchoMessage(EchoMessage { content: "router".to_string() });
+    assert!(client.send(echo).is_ok(), "Failed to send message");
+    let expected = reply(server_message::Message::EchoMessage(EchoMessage { content: "retuor".to_string() }));
+    assert_eq!(client.receive().expect("Failed to receive response for EchoMessage"), expected);
+
+    assert!(client.send(client_message::Message::AddRequest(AddRequest { a: 3, b: 4 })).is_ok(), "Failed to send message");
+    let expected = reply(server_message::Message::AddResponse(AddResponse { result: 12 }));
+    assert_eq!(client.receive().expect("Failed to receive response for AddRequest"), expected);
+
+    assert!(client.disconnect().is_ok(), "Failed to disconnect from the server");
+    handle.stop();
+    assert!(handle.wait().is_ok(), "Expected a clean shutdown");
+}
@@ -0,0 +1,138 @@
+use embedded_recruitment_task::{
+    handler::{Context, HandlerResult}, // Importing request handlers
+    message::{client_message, server_message, EchoMessage}, // Importing message types for client-server communication
+    router::MessageKind, // Importing message types handlers are registered for
+    server::Server, // Importing server functionalities
+    session::Session, // Importing per-connection state
+};
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering}, // For observing session teardown
+        Arc, // For sharing state with handlers
+    },
+    time::Duration, // For polling timeouts
+};
+
+#[allow(dead_code)] // Not every client helper is used by this suite
+mod client; // Declares a client module for client-related operations
+
+use client::wait_until; // Polls for asynchronous server effects
+
+/// Requests served on a connection, kept in its session.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+struct Served(u32);
+
+/// A session extension that counts how many times it was dropped.
+struct Guard(Arc<AtomicUsize>);
+
+impl Drop for Guard {
+    fn drop(&mut self) {
+        self.0.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+/// Utility function to send an echo and return the content of its reply.
+fn call(client: &mut client::Client, content: &str) -> String {
+    let request = client_message::Message::EchoMessage(EchoMessage { content: content.to_string() });
+    assert!(client.send(request).is_ok(), "Failed to send message");
+    match client.receive().expect("Failed to receive a response").message {
+        Some(server_message::Message::EchoMessage(echo)) => echo.content,
+        other => panic!("Expected an EchoMessage, but received {:?}", other),
+    }
+}
+
+/// Test to validate the session API outside a server.
+#[test]
+fn test_session_state() {
+    let session = Session::new(3, "127.0.0.1:9000".parse::<std::net::SocketAddr>().unwrap().into());
+    assert_eq!(session.id(), 3);
+    assert!(session.is_open());
+
+    assert_eq!(session.identity(), None);
+    session.set_identity("alice");
+    assert_eq!(session.identity().as_deref(), Some("alice"));
+    session.clear_identity();
+    assert_eq!(session.identity(), None);
+
+    assert!(session.enable_feature("compression"));
+    assert!(session.enable_feature("batching"));
+    assert!(!session.enable_feature("compression"), "Expected a feature to be negotiated once");
+    assert!(session.has_feature("batching"));
+    assert_eq!(session.features(), ["batching", "compression"]);
+    assert!(session.disable_feature("batching"));
+    assert!(!session.has_feature("batching"));
+
+    // Extensions are keyed by type
+    assert_eq!(session.get::<Served>(), None);
+    assert_eq!(session.update(|served: &mut Served| { served.0 += 1; served.0 }), 1);
+    assert_eq!(session.insert(String::from("note")), None);
+    assert_eq!(session.insert(Served(5)), Some(Served(1)));
+    assert_eq!(session.get::<Served>(), Some(Served(5)));
+    assert_eq!(session.remove::<String>().as_deref(), Some("note"));
+    assert_eq!(session.get::<String>(), None);
+}
+
+/// Test to validate that handlers keep separate state per connection and that it is torn down with it.
+#[test]
+fn test_sessions_follow_connections() {
+    let _ = env_logger::builder().is_test(true).try_init();
+
+    let dropped = Arc::new(AtomicUsize::new(0));
+    let guards = Arc::clone(&dropped);
+    let mut server = Server::new("127.0.0.1:0").expect("Failed to start server");
+    server.register_handler(MessageKind::EchoMessage, move |request: client_message::Message, context: &Context| -> HandlerResult {
+        let session = context.session();
+        session.update(|guard: &mut Option<Guard>| {
+            guard.get_or_insert_with(|| Guard(Arc::clone(&guards)));
+        });
+        let served = session.update(|served: &mut Served| {
+            served.0 += 1;
+            served.0
+        });
+        let content = match request {
+            client_message::Message::EchoMessage(echo) => echo.content,
+            _ => String::new(),
+        };
+        let reply = match content.split_once(' ') {
+            Some(("login", name)) => {
+                session.set_identity(name);
+                format!("hello {}", name)
+            }
+            _ => format!("{} #{} as {}", session.id(), served, session.identity().unwrap_or_default()),
+        };
+        Ok(Some(server_message::Message::EchoMessage(EchoMessage { content: reply })))
+    });
+    let handle = server.start().expect("Failed to run server");
+    let port = handle.local_addr().unwrap().port();
+
+    let mut alice = client::Client::new("127.0.0.1", port.into(), 2000);
+    let mut bob = client::Client::new("127.0.0.1", port.into(), 2000);
+    assert!(alice.connect().is_ok(), "Failed to connect to the server");
+    assert!(bob.connect().is_ok(), "Failed to connect to the server");
+
+    assert_eq!(call(&mut alice, "login alice"), "hello alice");
+    assert_eq!(call(&mut bob, "login bob"), "hello bob");
+    let connections = handle.server().connections();
+    let ids: Vec<u64> = connections.iter().map(|connection| connection.id).collect();
+    let alice_id = ids.iter().copied().find(|id| handle.server().session(*id).unwrap().identity().as_deref() == Some("alice"));
+    let alice_id = alice_id.expect("Expected a session authenticated as alice");
+    assert_eq!(call(&mut alice, "whoami"), format!("{} #2 as alice", alice_id));
+    assert_eq!(call(&mut alice, "whoami"), format!("{} #3 as alice", alice_id));
+    let bob_session = handle.server().session(*ids.iter().find(|id| **id != alice_id).unwrap()).unwrap();
+    assert_eq!(bob_session.get::<Served>(), Some(Served(1)), "Expected each connection to keep its own state");
+    let bob_connection = connections.iter().find(|connection| connection.id == bob_session.id()).unwrap();
+    assert_eq!(bob_session.peer_addr(), &bob_connection.peer_addr);
+
+    // Closing a connection tears its session down
+    assert!(bob.disconnect().is_ok(), "Failed to disconnect from the server");
+    assert!(wait_until(Duration::from_secs(2), || dropped.load(Ordering::SeqCst) == 1), "Expected bob's extensions to be dropped");
+    assert!(!bob_session.is_open());
+    assert_eq!(bob_session.identity(), None);
+    assert!(wait_until(Duration::from_secs(2), || handle.server().session(bob_session.id()).is_none()));
+    assert!(handle.server().session(alice_id).unwrap().is_open());
+
+    assert!(alice.disconnect().is_ok(), "Failed to disconnect from the server");
+    handle.stop();
+    assert!(handle.wait().is_ok(), "Expected a clean shutdown");
+    assert_eq!(dropped.load(Ordering::SeqCst), 2);
+}
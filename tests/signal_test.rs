@@ -0,0 +1,127 @@
+#![cfg(unix)]
+
+use embedded_recruitment_task::message::{client_message, ClientMessage, EchoMessage}; // Importing message types for client-server communication
+use prost::Message; // For framing raw requests
+use std::{
+    env, // For locating the temporary directory
+    fs, // For writing configuration files
+    io::Write, // For writing raw frames
+    net::TcpStream, // For a client that never reads its replies
+    thread, // For background writers
+    time::Duration, // For deadlines while waiting on the child process
+};
+
+#[allow(dead_code)] // Not every client helper is used by this suite
+mod client; // Declares a client module for client-related operations
+mod process; // Declares a module for driving the server binary
+
+use process::{send_signal, spawn_server_with_args, wait_for_exit, ServerProcess};
+
+/// Utility function to start the server binary on an ephemeral port.
+fn spawn_server() -> ServerProcess {
+    spawn_server_with_args(&["127.0.0.1:0"])
+}
+
+/// Test to validate that SIGTERM shuts the server down cleanly and closes connected clients.
+#[test]
+fn test_sigterm_graceful_shutdown() {
+    let mut server = spawn_server();
+    let port = server.port;
+
+    let mut client = client::Client::new("127.0.0.1", port.into(), 2000);
+    assert!(client.connect().is_ok(), "Failed to connect to the server");
+    let echo_message = EchoMessage { content: "before shutdown".to_string() };
+    assert!(client.send(client_message::Message::EchoMessage(echo_message)).is_ok(), "Failed to send message");
+    assert!(client.receive().is_ok(), "Failed to receive response for EchoMessage");
+
+    send_signal(server.pid, "TERM");
+    let status = wait_for_exit(&mut server.child, Duration::from_secs(5)).expect("Server did not exit after SIGTERM");
+    assert_eq!(status.code(), Some(0), "Expected a clean exit, got {}", status);
+    assert!(client.receive().is_err(), "Expected the connection to be closed on shutdown");
+}
+
+/// Test to validate that SIGINT triggers the same graceful shutdown.
+#[test]
+fn test_sigint_graceful_shutdown() {
+    let mut server = spawn_server();
+
+    send_signal(server.pid, "INT");
+    let status = wait_for_exit(&mut server.child, Duration::from_secs(5)).expect("Server did not exit after SIGINT");
+    assert_eq!(status.code(), Some(0), "Expected a clean exit, got {}", status);
+}
+
+/// Test to validate that a second signal forces an exit while shutdown is stuck draining.
+#[test]
+fn test_second_signal_forces_exit() {
+    let mut server = spawn_server();
+    let port = server.port;
+
+    // Flood the server with requests and never read the replies, so its writer blocks
+    let mut stream = TcpStream::connect(("127.0.0.1", port)).expect("Failed to connect to the server");
+    stream.set_write_timeout(Some(Duration::from_secs(5))).unwrap();
+    let frame = ClientMessage {
+        message: Some(client_message::Message::EchoMessage(EchoMessage { content: "x".repeat(60 * 1024) })),
+    }
+    .encode_length_delimited_to_vec();
+    let flooder = thread::spawn(move || {
+        for _ in 0..1024 {
+            if stream.write_all(&frame).is_err() {
+                break; // The server stopped reading or went away.
+            }
+        }
+        stream
+    });
+    thread::sleep(Duration::from_millis(500));
+
+    send_signal(server.pid, "TERM");
+    assert!(
+        wait_for_exit(&mut server.child, Duration::from_millis(500)).is_none(),
+        "Expected shutdown to wait for the blocked connection"
+    );
+
+    send_signal(server.pid, "TERM");
+    let status = wait_for_exit(&mut server.child, Duration::from_secs(5)).expect("Server did not exit after the second signal");
+    assert_eq!(status.code(), Some(128 + 15), "Expected a forced exit, got {}", status);
+    let _ = flooder.join();
+}
+
+/// Test to validate that SIGHUP reloads the configuration file without dropping connections.
+#[test]
+fn test_sighup_reloads_config() {
+    let path = env::temp_dir().join(format!("server-reload-{}.conf", std::process::id()));
+    fs::write(&path, "address = 127.0.0.1:0\nlog_level = info\n").expect("Failed to write the configuration");
+    let mut server = spawn_server_with_args(&["--config", path.to_str().unwrap()]);
+    let port = server.port;
+
+    let mut client = client::Client::new("127.0.0.1", port.into(), 2000);
+    assert!(client.connect().is_ok(), "Failed to connect to the server");
+    let echo_message = EchoMessage { content: "before reload".to_string() };
+    assert!(client.send(client_message::Message::EchoMessage(echo_message)).is_ok(), "Failed to send message");
+    assert!(client.receive().is_ok(), "Failed to receive response for EchoMessage");
+
+    // Deny every loopback client and reload
+    fs::write(&path, "address = 127.0.0.1:0\nlog_level = debug\ndeny = 127.0.0.0/8\n").expect("Failed to write the configuration");
+    send_signal(server.pid, "HUP");
+    thread::sleep(Duration::from_millis(300));
+
+    // New clients are refused...
+    let mut refused = client::Client::new("127.0.0.1", port.into(), 2000);
+    assert!(refused.connect().is_ok(), "The TCP handshake completes before the server refuses");
+    assert!(refused.receive().is_err(), "Expected the reloaded access list to refuse the client");
+
+    // ...while the existing connection is still served
+    let echo_message = EchoMessage { content: "after reload".to_string() };
+    assert!(client.send(client_message::Message::EchoMessage(echo_message)).is_ok(), "Failed to send message");
+    assert!(client.receive().is_ok(), "Failed to receive response for EchoMessage");
+
+    // A broken file is rejected and the server keeps running
+    fs::write(&path, "bogus = 1\n").expect("Failed to write the configuration");
+    send_signal(server.pid, "HUP");
+    thread::sleep(Duration::from_millis(300));
+    assert!(server.child.try_wait().unwrap().is_none(), "Expected the server to survive a failed reload");
+
+    send_signal(server.pid, "TERM");
+    let status = wait_for_exit(&mut server.child, Duration::from_secs(5)).expect("Server did not exit after SIGTERM");
+    assert_eq!(status.code(), Some(0), "Expected a clean exit, got {}", status);
+    let _ = fs::remove_file(&path);
+}
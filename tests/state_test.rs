@@ -0,0 +1,115 @@
+use embedded_recruitment_task::{
+    config::{PoolConfig, ServerConfig}, // Importing server settings
+    handler::{Context, ErrorCode, HandlerError, HandlerResult}, // Importing request handlers and their error codes
+    message::{client_message, server_message, AddRequest, AddResponse}, // Importing message types for client-server communication
+    router::MessageKind, // Importing message types handlers are registered for
+    server::Server, // Importing server functionalities
+    state::AppState, // Importing application state
+};
+use std::{
+    collections::HashMap, // For per-client totals
+    sync::{
+        atomic::{AtomicU64, Ordering}, // For lock-free shared counters
+        Arc, Mutex, // For shared state behind a lock
+    },
+    thread, // For concurrent clients
+};
+
+#[allow(dead_code)] // Not every client helper is used by this suite
+mod client; // Declares a client module for client-related operations
+
+/// Shared counters updated by every handler.
+#[derive(Default)]
+struct Stats {
+    requests: AtomicU64,
+    totals: Mutex<HashMap<u64, i64>>,
+}
+
+/// Read-only settings shared with handlers.
+struct Limits {
+    max_operand: i32,
+}
+
+/// Handler that adds numbers within the configured limit and records them in the shared stats.
+fn add(request: client_message::Message, context: &Context) -> HandlerResult {
+    let stats = context.state::<Stats>().ok_or_else(|| HandlerError::new(ErrorCode::Internal, "no stats"))?;
+    let limits = context.state::<Limits>().ok_or_else(|| HandlerError::new(ErrorCode::Internal, "no limits"))?;
+    let client_message::Message::AddRequest(AddRequest { a, b }) = request else {
+        return Err(HandlerError::invalid_argument("expected an AddRequest"));
+    };
+    if a.abs() > limits.max_operand || b.abs() > limits.max_operand {
+        return Err(HandlerError::invalid_argument("operand too large"));
+    }
+    stats.requests.fetch_add(1, Ordering::SeqCst);
+    *stats.totals.lock().unwrap().entry(context.connection_id()).or_default() += i64::from(a + b);
+    Ok(Some(server_message::Message::AddResponse(AddResponse { result: a + b })))
+}
+
+/// Test to validate the state container and a context carrying it outside a server.
+#[test]
+fn test_app_state_is_typed() {
+    let mut state = AppState::new();
+    assert!(state.is_empty());
+    state.insert(Limits { max_operand: 10 });
+    state.insert(String::from("first"));
+    state.insert(String::from("second"));
+    assert_eq!(state.len(), 2, "Expected one value per type");
+    assert_eq!(state.get::<String>().map(String::as_str), Some("second"));
+    assert!(state.get::<Stats>().is_none());
+
+    let context = Context::new(1, "127.0.0.1:9000".parse::<std::net::SocketAddr>().unwrap().into()).with_state(Arc::new(state));
+    let request = client_message::Message::AddRequest(AddRequest { a: 11, b: 1 });
+    let error = add(request, &context).expect_err("Expected the handler to report missing stats");
+    assert_eq!(error.code, ErrorCode::Internal);
+}
+
+/// Test to validate that concurrent handlers on pool workers share one state without losing updates.
+#[test]
+fn test_concurrent_handlers_share_state() {
+    let _ = env_logger::builder().is_test(true).try_init();
+
+    let config = ServerConfig {
+        pools: vec![PoolConfig::new("math", 4, 256).handling(&[MessageKind::AddRequest])],
+        ..ServerConfig::default()
+    };
+    let mut server = Server::with_config("127.0.0.1:0", config).expect("Failed to start server");
+    server.insert_state(Stats::default());
+    server.insert_state(Limits { max_operand: 1000 });
+    server.register_handler(MessageKind::AddRequest, add);
+    let handle = server.start().expect("Failed to run server");
+    let port = handle.local_addr().unwrap().port();
+
+    let clients = 8;
+    let requests = 100;
+    let threads: Vec<_> = (0..clients)
+        .map(|_| {
+            thread::spawn(move || {
+                let mut client = client::Client::new("127.0.0.1", port.into(), 5000);
+                client.connect().expect("Failed to connect to the server");
+                for i in 0..requests {
+                    let request = client_message::Message::AddRequest(AddRequest { a: i, b: 1 });
+                    client.send(request).expect("Failed to send message");
+                }
+                for _ in 0..requests {
+                    let response = client.receive().expect("Failed to receive a response");
+                    assert!(matches!(response.message, Some(server_message::Message::AddResponse(_))));
+                }
+                client.disconnect().expect("Failed to disconnect from the server");
+            })
+        })
+        .collect();
+    for thread in threads {
+        thread.join().expect("Client thread panicked");
+    }
+
+    let stats = handle.server().state::<Stats>().expect("Expected the registered stats");
+    assert_eq!(stats.requests.load(Ordering::SeqCst), (clients * requests) as u64, "Expected no lost updates");
+    let totals = stats.totals.lock().unwrap();
+    assert_eq!(totals.len(), clients as usize, "Expected one total per connection");
+    let expected: i64 = (0..requests).map(|i| i64::from(i + 1)).sum();
+    assert!(totals.values().all(|total| *total == expected), "Expected every sum to be recorded: {:?}", totals);
+    drop(totals);
+
+    handle.stop();
+    assert!(handle.wait().is_ok(), "Expected a clean shutdown");
+}